@@ -0,0 +1,348 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::findings::{self, Finding, Severity};
+use crate::graph::{DesignGraph, EdgeKind, GraphNode};
+
+/// Categories that generate a clock. Other nodes become sources with
+/// `clock_source: true` and an output `clock_freq_mhz`.
+const CLOCK_SOURCE_CATEGORIES: &[&str] = &["Crystal", "Oscillator", "Clock"];
+/// Categories treated as synchronous consumers unless `clock_required` is
+/// explicitly false.
+const SYNCHRONOUS_CATEGORIES: &[&str] = &["SoC", "MCU", "RF"];
+const DEFAULT_TOLERANCE_PPM: f64 = 100.0;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockDomain {
+    /// Id of the root clock source; nodes derived from it through buffers or
+    /// PLLs share the domain.
+    pub id: String,
+    pub label: String,
+    pub frequency_mhz: Option<f64>,
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockedNode {
+    pub node_id: String,
+    pub domain: String,
+    pub frequency_mhz: Option<f64>,
+    /// RSS of the jitter of every source and buffer on the path.
+    pub jitter_ps: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainCrossing {
+    pub edge_id: String,
+    pub source_node: String,
+    pub target_node: String,
+    pub source_domain: String,
+    pub target_domain: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClockReport {
+    pub status: &'static str,
+    pub domains: Vec<ClockDomain>,
+    pub clocked_nodes: Vec<ClockedNode>,
+    pub crossings: Vec<DomainCrossing>,
+    pub findings: Vec<Finding>,
+}
+
+/// Clock tree analysis over `clk-out` → `clk-in` edges.
+///
+/// Clock sources declare `clock_freq_mhz` and optionally `clock_jitter_ps`.
+/// A node with a clock input that also declares `clock_freq_mhz` is a PLL or
+/// divider and re-times its outputs; one without passes its input through.
+/// Consumers constrain the clock with `clock_in_mhz` (±`clock_tolerance_ppm`)
+/// or `clock_min_mhz`/`clock_max_mhz`, and `clock_max_jitter_ps`.
+pub fn analyze(graph: &DesignGraph) -> ClockReport {
+    let mut findings = Vec::new();
+    let mut clock_inputs: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut clock_outputs: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for edge in &graph.edges {
+        if edge.kind() == EdgeKind::Clock
+            && graph.nodes.iter().any(|node| node.id == edge.source)
+            && graph.nodes.iter().any(|node| node.id == edge.target)
+        {
+            clock_inputs
+                .entry(edge.target.as_str())
+                .or_default()
+                .push(edge.source.as_str());
+            clock_outputs
+                .entry(edge.source.as_str())
+                .or_default()
+                .push(edge.target.as_str());
+        }
+    }
+
+    let by_id: BTreeMap<&str, &GraphNode> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect();
+    let roots: Vec<&GraphNode> = graph
+        .nodes
+        .iter()
+        .filter(|node| is_clock_source(node) && !clock_inputs.contains_key(node.id.as_str()))
+        .collect();
+
+    for root in &roots {
+        if root.number("clock_freq_mhz").is_none() {
+            findings.push(
+                Finding::new(
+                    "clock-source-without-frequency",
+                    Severity::Warning,
+                    format!("Clock source '{}' has no clock_freq_mhz.", root.label()),
+                )
+                .with_nodes(vec![root.id.clone()]),
+            );
+        }
+    }
+
+    // Breadth-first from each root. A node reached from several roots keeps
+    // the first one and is reported as multiply driven.
+    let mut clocked: BTreeMap<&str, ClockedNode> = BTreeMap::new();
+    for root in &roots {
+        let mut queue = VecDeque::new();
+        clocked.insert(
+            root.id.as_str(),
+            ClockedNode {
+                node_id: root.id.clone(),
+                domain: root.id.clone(),
+                frequency_mhz: root.number("clock_freq_mhz"),
+                jitter_ps: root.number("clock_jitter_ps").unwrap_or(0.0),
+            },
+        );
+        queue.push_back(root.id.as_str());
+
+        while let Some(current) = queue.pop_front() {
+            let upstream = clocked[current].clone();
+            for &next in clock_outputs.get(current).into_iter().flatten() {
+                if let Some(existing) = clocked.get(next) {
+                    if existing.domain != upstream.domain {
+                        findings.push(
+                            Finding::new(
+                                "multiple-clock-drivers",
+                                Severity::Warning,
+                                format!(
+                                    "'{}' is driven by clocks from both '{}' and '{}'.",
+                                    by_id[next].label(),
+                                    by_id[existing.domain.as_str()].label(),
+                                    by_id[upstream.domain.as_str()].label()
+                                ),
+                            )
+                            .with_nodes(vec![
+                                next.to_string(),
+                                existing.domain.clone(),
+                                upstream.domain.clone(),
+                            ]),
+                        );
+                    }
+                    continue;
+                }
+                let node = by_id[next];
+                let own_jitter = node.number("clock_jitter_ps").unwrap_or(0.0);
+                clocked.insert(
+                    next,
+                    ClockedNode {
+                        node_id: node.id.clone(),
+                        domain: upstream.domain.clone(),
+                        frequency_mhz: node.number("clock_freq_mhz").or(upstream.frequency_mhz),
+                        jitter_ps: upstream.jitter_ps.hypot(own_jitter),
+                    },
+                );
+                queue.push_back(next);
+            }
+        }
+    }
+
+    for node in &graph.nodes {
+        if !is_synchronous(node) {
+            continue;
+        }
+        let Some(clock) = clocked.get(node.id.as_str()) else {
+            let message = if clock_inputs.contains_key(node.id.as_str()) {
+                format!(
+                    "'{}' has a clock input that no clock source drives.",
+                    node.label()
+                )
+            } else {
+                format!("'{}' has no clock input connected.", node.label())
+            };
+            findings.push(
+                Finding::new("undriven-clock-input", Severity::Error, message)
+                    .with_nodes(vec![node.id.clone()]),
+            );
+            continue;
+        };
+        check_requirements(node, clock, &mut findings);
+    }
+
+    let crossings: Vec<DomainCrossing> = graph
+        .edges
+        .iter()
+        .filter(|edge| edge.kind() == EdgeKind::Data)
+        .filter_map(|edge| {
+            let source = clocked.get(edge.source.as_str())?;
+            let target = clocked.get(edge.target.as_str())?;
+            (source.domain != target.domain).then(|| DomainCrossing {
+                edge_id: edge.display_id(),
+                source_node: edge.source.clone(),
+                target_node: edge.target.clone(),
+                source_domain: source.domain.clone(),
+                target_domain: target.domain.clone(),
+            })
+        })
+        .collect();
+
+    let domains = roots
+        .iter()
+        .map(|root| ClockDomain {
+            id: root.id.clone(),
+            label: root.label(),
+            frequency_mhz: root.number("clock_freq_mhz"),
+            members: clocked
+                .values()
+                .filter(|clocked| clocked.domain == root.id)
+                .map(|clocked| clocked.node_id.clone())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        })
+        .collect();
+
+    findings.sort_by_key(|finding| finding.severity);
+    ClockReport {
+        status: findings::overall_status(&findings),
+        domains,
+        clocked_nodes: clocked.into_values().collect(),
+        crossings,
+        findings,
+    }
+}
+
+pub fn is_clock_source(node: &GraphNode) -> bool {
+    node.flag("clock_source") || CLOCK_SOURCE_CATEGORIES.contains(&node.category().as_str())
+}
+
+fn is_synchronous(node: &GraphNode) -> bool {
+    if node.data.contains_key("clock_required") {
+        return node.flag("clock_required");
+    }
+    ["clock_in_mhz", "clock_min_mhz", "clock_max_mhz"]
+        .iter()
+        .any(|key| node.number(key).is_some())
+        || SYNCHRONOUS_CATEGORIES.contains(&node.category().as_str())
+}
+
+fn check_requirements(node: &GraphNode, clock: &ClockedNode, findings: &mut Vec<Finding>) {
+    let nodes = vec![node.id.clone(), clock.domain.clone()];
+    if let Some(frequency) = clock.frequency_mhz {
+        let (min, max) = match node.number("clock_in_mhz") {
+            Some(nominal) => {
+                let tolerance = node
+                    .number("clock_tolerance_ppm")
+                    .unwrap_or(DEFAULT_TOLERANCE_PPM);
+                let delta = nominal * tolerance / 1e6;
+                (Some(nominal - delta), Some(nominal + delta))
+            }
+            None => (node.number("clock_min_mhz"), node.number("clock_max_mhz")),
+        };
+        let too_low = min.is_some_and(|min| frequency < min);
+        let too_high = max.is_some_and(|max| frequency > max);
+        if too_low || too_high {
+            findings.push(
+                Finding::new(
+                    "clock-frequency-mismatch",
+                    Severity::Error,
+                    format!(
+                        "'{}' receives {} MHz, outside its accepted range {}–{} MHz.",
+                        node.label(),
+                        frequency,
+                        min.map_or("?".to_string(), |v| v.to_string()),
+                        max.map_or("?".to_string(), |v| v.to_string())
+                    ),
+                )
+                .with_nodes(nodes.clone()),
+            );
+        }
+    }
+
+    if let Some(max_jitter) = node.number("clock_max_jitter_ps") {
+        if clock.jitter_ps > max_jitter {
+            findings.push(
+                Finding::new(
+                    "clock-jitter-exceeded",
+                    Severity::Warning,
+                    format!(
+                        "'{}' sees {:.1} ps of clock jitter, above its {:.1} ps budget.",
+                        node.label(),
+                        clock.jitter_ps,
+                        max_jitter
+                    ),
+                )
+                .with_nodes(nodes),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_domains_through_plls_and_flags_problems() {
+        let graph = DesignGraph::from_json(
+            r#"{
+                "nodes": [
+                    {"id":"xo","data":{"label":"25 MHz XO","category":"Oscillator","clock_freq_mhz":25,"clock_jitter_ps":3}},
+                    {"id":"pll","data":{"label":"PLL","clock_source":true,"clock_freq_mhz":100,"clock_jitter_ps":4}},
+                    {"id":"soc","data":{"label":"SoC","category":"SoC","clock_min_mhz":90,"clock_max_mhz":110,"clock_max_jitter_ps":4}},
+                    {"id":"mcu","data":{"label":"MCU","category":"MCU","clock_in_mhz":24}},
+                    {"id":"rtc","data":{"label":"RTC","category":"Crystal","clock_freq_mhz":0.032768}},
+                    {"id":"ble","data":{"label":"BLE","category":"RF"}},
+                    {"id":"imu","data":{"label":"IMU","category":"Sensor"}}
+                ],
+                "edges": [
+                    {"id":"c1","source":"xo","target":"pll","sourceHandle":"clk-out","targetHandle":"clk-in"},
+                    {"id":"c2","source":"pll","target":"soc","sourceHandle":"clk-out","targetHandle":"clk-in"},
+                    {"id":"c3","source":"xo","target":"mcu","sourceHandle":"clk-out","targetHandle":"clk-in"},
+                    {"id":"d1","source":"mcu","target":"soc","sourceHandle":"data-out","targetHandle":"data-in"},
+                    {"id":"d2","source":"imu","target":"soc","sourceHandle":"data-out","targetHandle":"data-in"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let report = analyze(&graph);
+        let codes: Vec<(&str, &str)> = report
+            .findings
+            .iter()
+            .map(|finding| (finding.code, finding.node_ids[0].as_str()))
+            .collect();
+
+        assert!(codes.contains(&("clock-frequency-mismatch", "mcu")));
+        assert!(codes.contains(&("undriven-clock-input", "ble")));
+        assert!(codes.contains(&("clock-jitter-exceeded", "soc")));
+        assert!(!codes.iter().any(|(_, id)| *id == "imu"));
+
+        let soc = report
+            .clocked_nodes
+            .iter()
+            .find(|node| node.node_id == "soc")
+            .unwrap();
+        assert_eq!(soc.domain, "xo");
+        assert_eq!(soc.frequency_mhz, Some(100.0));
+        assert!((soc.jitter_ps - 5.0).abs() < 1e-9);
+
+        assert_eq!(report.domains.len(), 2);
+        // Both ends of d1 are clocked from the same XO, so no crossing.
+        assert!(report.crossings.is_empty());
+    }
+}
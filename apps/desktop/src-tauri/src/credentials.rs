@@ -0,0 +1,558 @@
+//! Encrypted credential vault for API keys, so secrets are stored once in
+//! the backend and commands reference them by name instead of receiving
+//! them over IPC.
+//!
+//! The vault is a single XChaCha20-Poly1305 sealed file in the app data
+//! directory. Its key is derived with Argon2 either from this machine's ID
+//! (the default, nothing to type) or from a master passphrase that has to be
+//! unlocked once per session.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+const VAULT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// Mixed into the machine ID so the key isn't just a hash of a public value.
+const MACHINE_KEY_CONTEXT: &str = "aha-designer/credential-vault/v1";
+
+/// Keys derived this session, with the salt they belong to. Argon2 is slow
+/// on purpose, and a passphrase is only typed once.
+static SESSION_KEYS: Mutex<Vec<SessionKey>> = Mutex::new(Vec::new());
+
+struct SessionKey {
+    salt: Vec<u8>,
+    source: KeySource,
+    key: [u8; 32],
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum KeySource {
+    Machine,
+    Passphrase,
+}
+
+/// On-disk format. Everything but the key source is encrypted, names
+/// included.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    key_source: KeySource,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct Secrets {
+    credentials: BTreeMap<String, Credential>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Credential {
+    fields: BTreeMap<String, String>,
+    updated_at: u64,
+}
+
+/// What the frontend may see of a credential: never the values.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialInfo {
+    pub name: String,
+    pub fields: Vec<String>,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub exists: bool,
+    pub key_source: KeySource,
+    /// False while a passphrase vault waits for `unlock_credential_vault`.
+    pub unlocked: bool,
+    /// Empty while locked.
+    pub credentials: Vec<CredentialInfo>,
+}
+
+pub struct CredentialVault {
+    path: PathBuf,
+}
+
+impl CredentialVault {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn status(&self) -> Result<VaultStatus, String> {
+        let Some(file) = self.read_file()? else {
+            return Ok(VaultStatus {
+                exists: false,
+                key_source: KeySource::Machine,
+                unlocked: true,
+                credentials: Vec::new(),
+            });
+        };
+        let unlocked =
+            file.key_source == KeySource::Machine || session_key(&from_hex(&file.salt)?).is_some();
+        Ok(VaultStatus {
+            exists: true,
+            key_source: file.key_source,
+            unlocked,
+            credentials: if unlocked { self.list()? } else { Vec::new() },
+        })
+    }
+
+    /// Derives the passphrase key and checks it against the vault.
+    pub fn unlock(&self, passphrase: &str) -> Result<(), String> {
+        let file = self
+            .read_file()?
+            .ok_or_else(|| "No credential vault exists yet.".to_string())?;
+        if file.key_source != KeySource::Passphrase {
+            return Ok(());
+        }
+        let salt = from_hex(&file.salt)?;
+        let key = derive_key(passphrase.as_bytes(), &salt)?;
+        decrypt(&file, &key).map_err(|_| "Wrong passphrase.".to_string())?;
+        remember_key(salt, KeySource::Passphrase, key);
+        Ok(())
+    }
+
+    pub fn lock() {
+        if let Ok(mut keys) = SESSION_KEYS.lock() {
+            keys.retain(|entry| entry.source != KeySource::Passphrase);
+        }
+    }
+
+    /// Re-encrypts the vault under a new passphrase, or under the machine
+    /// key when `passphrase` is `None`.
+    pub fn set_passphrase(&self, passphrase: Option<&str>) -> Result<(), String> {
+        let secrets = self.load()?;
+        let passphrase = passphrase.map(str::trim).filter(|p| !p.is_empty());
+        if passphrase.is_some_and(|p| p.chars().count() < 8) {
+            return Err("Vault passphrase must be at least 8 characters.".to_string());
+        }
+        let salt = random_bytes(SALT_LEN);
+        let (source, key) = match passphrase {
+            Some(passphrase) => (
+                KeySource::Passphrase,
+                derive_key(passphrase.as_bytes(), &salt)?,
+            ),
+            None => (KeySource::Machine, machine_key(&salt)?),
+        };
+        Self::lock();
+        remember_key(salt.clone(), source, key);
+        self.write(&secrets, source, &salt, &key)
+    }
+
+    pub fn list(&self) -> Result<Vec<CredentialInfo>, String> {
+        Ok(self
+            .load()?
+            .credentials
+            .into_iter()
+            .map(|(name, credential)| CredentialInfo {
+                name,
+                fields: credential.fields.into_keys().collect(),
+                updated_at: credential.updated_at,
+            })
+            .collect())
+    }
+
+    /// Stores `fields` under `name`, replacing what was there.
+    pub fn set(&self, name: &str, fields: BTreeMap<String, String>) -> Result<(), String> {
+        let name = credential_name(name)?;
+        let fields: BTreeMap<String, String> = fields
+            .into_iter()
+            .map(|(field, value)| (field.trim().to_string(), value.trim().to_string()))
+            .filter(|(field, value)| !field.is_empty() && !value.is_empty())
+            .collect();
+        if fields.is_empty() {
+            return Err(format!("Credential {} has no values.", name));
+        }
+        self.update(|secrets| {
+            secrets.credentials.insert(
+                name,
+                Credential {
+                    fields,
+                    updated_at: crate::unix_now(),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        self.update(|secrets| {
+            secrets
+                .credentials
+                .remove(name.trim())
+                .map(|_| ())
+                .ok_or_else(|| format!("No credential named {}.", name.trim()))
+        })
+    }
+
+    /// The stored values of `name`, for backend use only.
+    pub fn fields(&self, name: &str) -> Result<BTreeMap<String, String>, String> {
+        self.load()?
+            .credentials
+            .remove(name.trim())
+            .map(|credential| credential.fields)
+            .ok_or_else(|| format!("No credential named {}.", name.trim()))
+    }
+
+    fn update(&self, edit: impl FnOnce(&mut Secrets) -> Result<(), String>) -> Result<(), String> {
+        let mut secrets = self.load()?;
+        edit(&mut secrets)?;
+        let (source, salt, key) = match self.read_file()? {
+            Some(file) => {
+                let salt = from_hex(&file.salt)?;
+                let key = self.key(&file)?;
+                (file.key_source, salt, key)
+            }
+            None => {
+                let salt = random_bytes(SALT_LEN);
+                let key = machine_key(&salt)
+                    .map_err(|e| format!("{} Set a vault passphrase to store credentials.", e))?;
+                remember_key(salt.clone(), KeySource::Machine, key);
+                (KeySource::Machine, salt, key)
+            }
+        };
+        self.write(&secrets, source, &salt, &key)
+    }
+
+    fn load(&self) -> Result<Secrets, String> {
+        match self.read_file()? {
+            Some(file) => decrypt(&file, &self.key(&file)?),
+            None => Ok(Secrets::default()),
+        }
+    }
+
+    fn key(&self, file: &VaultFile) -> Result<[u8; 32], String> {
+        let salt = from_hex(&file.salt)?;
+        if let Some(key) = session_key(&salt) {
+            return Ok(key);
+        }
+        match file.key_source {
+            KeySource::Passphrase => Err("Credential vault is locked.".to_string()),
+            KeySource::Machine => {
+                let key = machine_key(&salt)?;
+                remember_key(salt, KeySource::Machine, key);
+                Ok(key)
+            }
+        }
+    }
+
+    fn read_file(&self) -> Result<Option<VaultFile>, String> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read credential vault: {}", e))?;
+        let file: VaultFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse credential vault: {}", e))?;
+        if file.version != VAULT_VERSION {
+            return Err(format!(
+                "Unsupported credential vault version {}.",
+                file.version
+            ));
+        }
+        Ok(Some(file))
+    }
+
+    fn write(
+        &self,
+        secrets: &Secrets,
+        key_source: KeySource,
+        salt: &[u8],
+        key: &[u8; 32],
+    ) -> Result<(), String> {
+        let plaintext = serde_json::to_vec(secrets)
+            .map_err(|e| format!("Failed to serialize credentials: {}", e))?;
+        let nonce = random_bytes(NONCE_LEN);
+        let ciphertext = XChaCha20Poly1305::new(key.into())
+            .encrypt(XNonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_| "Failed to encrypt credentials.".to_string())?;
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            key_source,
+            salt: to_hex(salt),
+            nonce: to_hex(&nonce),
+            ciphertext: to_hex(&ciphertext),
+        };
+        let contents = serde_json::to_string_pretty(&file)
+            .map_err(|e| format!("Failed to serialize credential vault: {}", e))?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create credential directory: {}", e))?;
+        }
+        // Write next to the vault and rename, so a crash never leaves half a file.
+        let staging = self.path.with_extension("tmp");
+        fs::write(&staging, contents)
+            .map_err(|e| format!("Failed to write credential vault: {}", e))?;
+        restrict_permissions(&staging)?;
+        fs::rename(&staging, &self.path)
+            .map_err(|e| format!("Failed to write credential vault: {}", e))
+    }
+}
+
+#[tauri::command]
+pub fn credential_vault_status(app: AppHandle) -> Result<VaultStatus, String> {
+    vault(&app)?.status()
+}
+
+#[tauri::command]
+pub fn unlock_credential_vault(app: AppHandle, passphrase: String) -> Result<VaultStatus, String> {
+    let vault = vault(&app)?;
+    vault.unlock(&passphrase)?;
+    vault.status()
+}
+
+#[tauri::command]
+pub fn lock_credential_vault(app: AppHandle) -> Result<VaultStatus, String> {
+    CredentialVault::lock();
+    vault(&app)?.status()
+}
+
+/// Protects the vault with `passphrase`, or with this machine's key when
+/// it's empty.
+#[tauri::command]
+pub fn set_credential_vault_passphrase(
+    app: AppHandle,
+    passphrase: Option<String>,
+) -> Result<VaultStatus, String> {
+    let vault = vault(&app)?;
+    vault.set_passphrase(passphrase.as_deref())?;
+    vault.status()
+}
+
+#[tauri::command]
+pub fn list_credentials(app: AppHandle) -> Result<Vec<CredentialInfo>, String> {
+    vault(&app)?.list()
+}
+
+/// Stores named values such as `{companyId, apiKey}` under `name`.
+#[tauri::command]
+pub fn set_credential(
+    app: AppHandle,
+    name: String,
+    fields: BTreeMap<String, String>,
+) -> Result<Vec<CredentialInfo>, String> {
+    let vault = vault(&app)?;
+    vault.set(&name, fields)?;
+    vault.list()
+}
+
+#[tauri::command]
+pub fn delete_credential(app: AppHandle, name: String) -> Result<Vec<CredentialInfo>, String> {
+    let vault = vault(&app)?;
+    vault.delete(&name)?;
+    vault.list()
+}
+
+/// Values of the credential `name`, for commands that take a credential
+/// reference.
+pub fn credential_fields(app: &AppHandle, name: &str) -> Result<BTreeMap<String, String>, String> {
+    vault(app)?.fields(name)
+}
+
+fn vault(app: &AppHandle) -> Result<CredentialVault, String> {
+    Ok(CredentialVault::new(
+        crate::app_data_subdir(app, "credentials")?.join("vault.json"),
+    ))
+}
+
+fn credential_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-'));
+    if !valid {
+        return Err(format!(
+            "'{}' is not a valid credential name (letters, digits, '.', '_', '-').",
+            name
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn decrypt(file: &VaultFile, key: &[u8; 32]) -> Result<Secrets, String> {
+    let nonce = from_hex(&file.nonce)?;
+    if nonce.len() != NONCE_LEN {
+        return Err("Credential vault is corrupt.".to_string());
+    }
+    let plaintext = XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            from_hex(&file.ciphertext)?.as_slice(),
+        )
+        .map_err(|_| "Failed to decrypt credential vault.".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("Failed to parse credentials: {}", e))
+}
+
+fn derive_key(secret: &[u8], salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret, salt, &mut key)
+        .map_err(|e| format!("Failed to derive vault key: {}", e))?;
+    Ok(key)
+}
+
+fn machine_key(salt: &[u8]) -> Result<[u8; 32], String> {
+    let id = machine_id().ok_or_else(|| "This machine has no readable ID.".to_string())?;
+    derive_key(format!("{}:{}", MACHINE_KEY_CONTEXT, id).as_bytes(), salt)
+}
+
+#[cfg(target_os = "linux")]
+fn machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|line| line.contains("IOPlatformUUID"))?;
+    line.rsplit('"').nth(1).map(str::to_string)
+}
+
+#[cfg(target_os = "windows")]
+fn machine_id() -> Option<String> {
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|line| line.contains("MachineGuid"))?;
+    line.split_whitespace().last().map(str::to_string)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn machine_id() -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to protect credential vault: {}", e))
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+fn session_key(salt: &[u8]) -> Option<[u8; 32]> {
+    let keys = SESSION_KEYS.lock().ok()?;
+    keys.iter()
+        .find(|entry| entry.salt == salt)
+        .map(|entry| entry.key)
+}
+
+fn remember_key(salt: Vec<u8>, source: KeySource, key: [u8; 32]) {
+    if let Ok(mut keys) = SESSION_KEYS.lock() {
+        keys.retain(|entry| entry.salt != salt);
+        keys.push(SessionKey { salt, source, key });
+    }
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Result<Vec<u8>, String> {
+    if !text.len().is_multiple_of(2) {
+        return Err("Credential vault is corrupt.".to_string());
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&text[index..index + 2], 16)
+                .map_err(|_| "Credential vault is corrupt.".to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrase_vault_round_trips_and_locks() {
+        let dir = std::env::temp_dir().join(format!("aha-vault-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let vault = CredentialVault::new(dir.join("vault.json"));
+
+        vault.set_passphrase(Some("correct horse")).unwrap();
+        vault
+            .set(
+                "trustedparts",
+                BTreeMap::from([
+                    ("companyId".to_string(), "acme".to_string()),
+                    ("apiKey".to_string(), " secret-key ".to_string()),
+                ]),
+            )
+            .unwrap();
+        assert!(vault.set("bad name", BTreeMap::new()).is_err());
+
+        let listed = vault.list().unwrap();
+        assert_eq!(listed[0].name, "trustedparts");
+        assert_eq!(listed[0].fields, ["apiKey", "companyId"]);
+        assert_eq!(
+            vault.fields("trustedparts").unwrap()["apiKey"],
+            "secret-key"
+        );
+
+        let on_disk = fs::read_to_string(dir.join("vault.json")).unwrap();
+        assert!(!on_disk.contains("secret-key") && !on_disk.contains("trustedparts"));
+
+        CredentialVault::lock();
+        assert!(!vault.status().unwrap().unlocked);
+        assert_eq!(
+            vault.fields("trustedparts").unwrap_err(),
+            "Credential vault is locked."
+        );
+        assert_eq!(
+            vault.unlock("wrong horse").unwrap_err(),
+            "Wrong passphrase."
+        );
+        vault.unlock("correct horse").unwrap();
+
+        vault.delete("trustedparts").unwrap();
+        assert!(vault.list().unwrap().is_empty());
+        assert!(vault.delete("trustedparts").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
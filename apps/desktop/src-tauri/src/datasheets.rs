@@ -0,0 +1,615 @@
+//! Local archive of the datasheets a design links to. PDFs are stored once
+//! under their SHA-256 in the app data folder, so every project that uses a
+//! part shares one copy, and `index.json` records which blob each URL
+//! returned and when. Nodes keep the hash next to `datasheet_url`, so a
+//! design still finds its datasheets after the link rots.
+
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Node data keys the archive fills in, next to `datasheet_url`.
+const SHA256_KEY: &str = "datasheet_sha256";
+const RETRIEVED_AT_KEY: &str = "datasheet_retrieved_at";
+
+const INDEX_VERSION: u32 = 1;
+const DOWNLOAD_CONCURRENCY: usize = 4;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_DATASHEET_BYTES: usize = 64 * 1024 * 1024;
+
+/// Serializes index updates from concurrent downloads.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+static WRITE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredDatasheet {
+    pub sha256: String,
+    pub bytes: u64,
+    /// Unix seconds.
+    pub retrieved_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveIndex {
+    version: u32,
+    /// Source URL -> the blob it returned last.
+    #[serde(default)]
+    urls: BTreeMap<String, StoredDatasheet>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDatasheet {
+    pub node_id: String,
+    pub label: Option<String>,
+    pub mpn: Option<String>,
+    pub url: String,
+    pub stored: Option<StoredDatasheet>,
+    /// Fetched by this run rather than found in the archive.
+    pub downloaded: bool,
+    /// Set when the download failed; an earlier copy is kept.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasheetReport {
+    pub datasheets: Vec<WorkspaceDatasheet>,
+    pub downloaded: usize,
+    pub failed: usize,
+    /// The workspace with the hashes stored on its nodes, for the frontend
+    /// to load and save.
+    pub workspace_json: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasheetExport {
+    pub path: String,
+    pub files: usize,
+    /// Labels of parts whose datasheet isn't archived yet.
+    pub missing: Vec<String>,
+}
+
+/// Content-addressed PDF store.
+#[derive(Debug, Clone)]
+pub struct DatasheetStore {
+    dir: PathBuf,
+}
+
+impl DatasheetStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The archived copy of `url`, if its blob is still there.
+    pub fn lookup(&self, url: &str) -> Result<Option<StoredDatasheet>, String> {
+        Ok(self
+            .load_index()?
+            .urls
+            .remove(url)
+            .filter(|stored| self.blob_path(&stored.sha256).exists()))
+    }
+
+    /// Stores `bytes` fetched from `url`. Identical content from any URL or
+    /// project shares one blob.
+    pub fn store(&self, url: &str, bytes: &[u8]) -> Result<StoredDatasheet, String> {
+        if !bytes.starts_with(b"%PDF-") {
+            return Err(format!("{} did not return a PDF.", url));
+        }
+        let sha256 = format!("{:x}", Sha256::digest(bytes));
+        let path = self.blob_path(&sha256);
+        if !path.exists() {
+            let dir = path.parent().unwrap_or(&self.dir);
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create datasheet archive: {}", e))?;
+            write_atomic(&path, bytes)?;
+        }
+
+        let stored = StoredDatasheet {
+            sha256,
+            bytes: bytes.len() as u64,
+            retrieved_at: crate::unix_now(),
+        };
+        let _guard = INDEX_LOCK
+            .lock()
+            .map_err(|_| "Datasheet index is poisoned.".to_string())?;
+        let mut index = self.load_index()?;
+        index.urls.insert(url.to_string(), stored.clone());
+        self.save_index(&index)?;
+        Ok(stored)
+    }
+
+    /// Path of the blob with `sha256`, if it is archived.
+    pub fn path(&self, sha256: &str) -> Result<PathBuf, String> {
+        let valid = sha256.len() == 64 && sha256.chars().all(|ch| ch.is_ascii_hexdigit());
+        let path = self.blob_path(&sha256.to_ascii_lowercase());
+        if valid && path.exists() {
+            Ok(path)
+        } else {
+            Err(format!("Datasheet {} is not archived.", sha256))
+        }
+    }
+
+    fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join("blobs").join(format!("{}.pdf", sha256))
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.json")
+    }
+
+    fn load_index(&self) -> Result<ArchiveIndex, String> {
+        let path = self.index_path();
+        if !path.exists() {
+            return Ok(ArchiveIndex {
+                version: INDEX_VERSION,
+                ..ArchiveIndex::default()
+            });
+        }
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read datasheet index: {}", e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse datasheet index: {}", e))
+    }
+
+    fn save_index(&self, index: &ArchiveIndex) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create datasheet archive: {}", e))?;
+        let contents = serde_json::to_vec_pretty(index)
+            .map_err(|e| format!("Failed to serialize datasheet index: {}", e))?;
+        write_atomic(&self.index_path(), &contents)
+    }
+}
+
+/// Downloads the datasheet of every workspace node that links one and
+/// isn't archived yet (or all of them with `refresh`), and stores the hash
+/// on the nodes.
+#[tauri::command]
+pub async fn archive_workspace_datasheets(
+    app: AppHandle,
+    workspace_json: String,
+    refresh: Option<bool>,
+) -> Result<DatasheetReport, String> {
+    let mut workspace: Value = serde_json::from_str(&workspace_json)
+        .map_err(|e| format!("Failed to parse workspace: {}", e))?;
+    let store = store(&app)?;
+    let refresh = refresh.unwrap_or(false);
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to initialize HTTP client: {}", e))?;
+
+    let mut urls: Vec<String> = Vec::new();
+    for datasheet in workspace_datasheets(&workspace) {
+        if !urls.contains(&datasheet.url) {
+            urls.push(datasheet.url);
+        }
+    }
+    let fetched: HashMap<String, (Result<StoredDatasheet, String>, bool)> = stream::iter(urls)
+        .map(|url| {
+            let (client, store) = (&client, &store);
+            async move {
+                if !refresh {
+                    if let Ok(Some(stored)) = store.lookup(&url) {
+                        return (url, (Ok(stored), false));
+                    }
+                }
+                let result = match download(client, &url).await {
+                    Ok(bytes) => store.store(&url, &bytes),
+                    Err(error) => Err(error),
+                };
+                (url, (result, true))
+            }
+        })
+        .buffer_unordered(DOWNLOAD_CONCURRENCY)
+        .collect()
+        .await;
+
+    record(&mut workspace, &store, &fetched)
+}
+
+/// Opens an archived datasheet in the system PDF viewer.
+#[tauri::command]
+pub fn open_datasheet(app: AppHandle, sha256: String) -> Result<(), String> {
+    open_path(&store(&app)?.path(&sha256)?)
+}
+
+/// Writes the archived datasheets of a workspace to a zip at `path`, with
+/// a `manifest.json` of sources, hashes and retrieval dates.
+#[tauri::command]
+pub fn export_workspace_datasheets(
+    app: AppHandle,
+    workspace_json: String,
+    path: String,
+) -> Result<DatasheetExport, String> {
+    let workspace: Value = serde_json::from_str(&workspace_json)
+        .map_err(|e| format!("Failed to parse workspace: {}", e))?;
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("Export path is empty.".to_string());
+    }
+    export_zip(&store(&app)?, &workspace, Path::new(path))
+}
+
+/// Nodes with an http(s) `datasheet_url`, in node order.
+pub fn workspace_datasheets(workspace: &Value) -> Vec<WorkspaceDatasheet> {
+    let nodes = workspace.get("nodes").and_then(Value::as_array);
+    nodes
+        .into_iter()
+        .flatten()
+        .filter_map(|node| {
+            let data = node.get("data")?.as_object()?;
+            let url = data
+                .get("datasheet_url")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|url| url.starts_with("http://") || url.starts_with("https://"))?;
+            let text = |key: &str| {
+                data.get(key)
+                    .and_then(Value::as_str)
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
+            };
+            Some(WorkspaceDatasheet {
+                node_id: node.get("id").and_then(Value::as_str)?.to_string(),
+                label: text("label"),
+                mpn: text("mpn"),
+                url: url.to_string(),
+                stored: None,
+                downloaded: false,
+                error: None,
+            })
+        })
+        .collect()
+}
+
+fn record(
+    workspace: &mut Value,
+    store: &DatasheetStore,
+    fetched: &HashMap<String, (Result<StoredDatasheet, String>, bool)>,
+) -> Result<DatasheetReport, String> {
+    let mut datasheets = workspace_datasheets(workspace);
+    for datasheet in &mut datasheets {
+        match fetched.get(&datasheet.url) {
+            Some((Ok(stored), downloaded)) => {
+                datasheet.stored = Some(stored.clone());
+                datasheet.downloaded = *downloaded;
+            }
+            Some((Err(error), _)) => {
+                datasheet.error = Some(error.clone());
+                datasheet.stored = store.lookup(&datasheet.url).ok().flatten();
+            }
+            None => {}
+        }
+    }
+
+    let by_node: HashMap<&str, &StoredDatasheet> = datasheets
+        .iter()
+        .filter_map(|datasheet| Some((datasheet.node_id.as_str(), datasheet.stored.as_ref()?)))
+        .collect();
+    let nodes = workspace.get_mut("nodes").and_then(Value::as_array_mut);
+    for node in nodes.into_iter().flatten() {
+        let id = node.get("id").and_then(Value::as_str).unwrap_or_default();
+        let Some(stored) = by_node.get(id) else {
+            continue;
+        };
+        if let Some(data) = node.get_mut("data").and_then(Value::as_object_mut) {
+            data.insert(SHA256_KEY.to_string(), json!(stored.sha256));
+            data.insert(RETRIEVED_AT_KEY.to_string(), json!(stored.retrieved_at));
+        }
+    }
+
+    let workspace_json = serde_json::to_string_pretty(workspace)
+        .map_err(|e| format!("Failed to serialize workspace: {}", e))?;
+    Ok(DatasheetReport {
+        downloaded: datasheets
+            .iter()
+            .filter(|d| d.downloaded && d.error.is_none())
+            .count(),
+        failed: datasheets.iter().filter(|d| d.error.is_some()).count(),
+        datasheets,
+        workspace_json,
+    })
+}
+
+fn export_zip(
+    store: &DatasheetStore,
+    workspace: &Value,
+    path: &Path,
+) -> Result<DatasheetExport, String> {
+    // The blob each node was archived with; the URL may have been archived
+    // again since.
+    let pinned: HashMap<&str, (&str, u64)> = workspace
+        .get("nodes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|node| {
+            let data = node.get("data")?;
+            let retrieved_at = data.get(RETRIEVED_AT_KEY).and_then(Value::as_u64);
+            Some((
+                node.get("id")?.as_str()?,
+                (data.get(SHA256_KEY)?.as_str()?, retrieved_at.unwrap_or(0)),
+            ))
+        })
+        .collect();
+
+    // One file per blob, named after the first part that uses it.
+    let mut files: Vec<(String, StoredDatasheet, Vec<WorkspaceDatasheet>)> = Vec::new();
+    let mut missing = Vec::new();
+    for datasheet in workspace_datasheets(workspace) {
+        let stored = match pinned.get(datasheet.node_id.as_str()) {
+            Some(&(sha256, retrieved_at)) => store
+                .path(sha256)
+                .ok()
+                .and_then(|path| fs::metadata(path).ok())
+                .map(|meta| StoredDatasheet {
+                    sha256: sha256.to_ascii_lowercase(),
+                    bytes: meta.len(),
+                    retrieved_at,
+                }),
+            None => store.lookup(&datasheet.url)?,
+        };
+        let Some(stored) = stored else {
+            missing.push(
+                datasheet
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| datasheet.node_id.clone()),
+            );
+            continue;
+        };
+        match files
+            .iter_mut()
+            .find(|(_, known, _)| known.sha256 == stored.sha256)
+        {
+            Some((_, _, parts)) => parts.push(datasheet),
+            None => {
+                let base = file_stem(datasheet.mpn.as_ref().or(datasheet.label.as_ref()));
+                let name = (1..)
+                    .map(|n| match n {
+                        1 => format!("{}.pdf", base),
+                        _ => format!("{}-{}.pdf", base, n),
+                    })
+                    .find(|name| files.iter().all(|(known, _, _)| known != name))
+                    .unwrap_or_default();
+                files.push((name, stored, vec![datasheet]));
+            }
+        }
+    }
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create zip: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut manifest = Vec::new();
+    for (name, stored, parts) in &files {
+        let bytes = fs::read(store.path(&stored.sha256)?)
+            .map_err(|e| format!("Failed to read archived datasheet: {}", e))?;
+        zip.start_file(format!("datasheets/{}", name), options)
+            .and_then(|_| zip.write_all(&bytes).map_err(Into::into))
+            .map_err(|e| format!("Failed to write zip: {}", e))?;
+        manifest.push(json!({
+            "file": format!("datasheets/{}", name),
+            "sha256": stored.sha256,
+            "bytes": stored.bytes,
+            "retrievedAt": stored.retrieved_at,
+            "parts": parts.iter().map(|part| json!({
+                "nodeId": part.node_id,
+                "label": part.label,
+                "mpn": part.mpn,
+                "url": part.url,
+            })).collect::<Vec<_>>(),
+        }));
+    }
+    let manifest =
+        serde_json::to_vec_pretty(&json!({ "datasheets": manifest, "missing": missing }))
+            .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    zip.start_file("manifest.json", options)
+        .and_then(|_| zip.write_all(&manifest).map_err(Into::into))
+        .and_then(|_| zip.finish().map(|_| ()))
+        .map_err(|e| format!("Failed to write zip: {}", e))?;
+
+    Ok(DatasheetExport {
+        path: path.to_string_lossy().to_string(),
+        files: files.len(),
+        missing,
+    })
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e.without_url()))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download {}: HTTP {}",
+            url,
+            response.status()
+        ));
+    }
+    let too_large = || format!("{} is larger than {} MB.", url, MAX_DATASHEET_BYTES >> 20);
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_DATASHEET_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+    // The header may be missing or wrong, so the body is counted as it
+    // arrives.
+    let mut bytes = Vec::new();
+    let mut body = response.bytes_stream();
+    while let Some(chunk) = body.next().await {
+        let chunk =
+            chunk.map_err(|e| format!("Failed to download {}: {}", url, e.without_url()))?;
+        if bytes.len() + chunk.len() > MAX_DATASHEET_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+fn store(app: &AppHandle) -> Result<DatasheetStore, String> {
+    Ok(DatasheetStore::new(crate::app_data_subdir(
+        app,
+        "datasheets",
+    )?))
+}
+
+/// Safe file name from a part number or label.
+fn file_stem(name: Option<&String>) -> String {
+    let stem: String = name
+        .map(String::as_str)
+        .unwrap_or("datasheet")
+        .chars()
+        .map(|ch| match ch {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => ch,
+            _ => '_',
+        })
+        .collect();
+    let stem = stem.trim_matches(|ch| ch == '_' || ch == '.');
+    if stem.is_empty() {
+        "datasheet".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    // Unique per call: concurrent downloads of the same PDF write one blob.
+    let temp_path = path.with_extension(format!(
+        "tmp{}-{}",
+        std::process::id(),
+        WRITE_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temp_path, bytes)
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to write datasheet archive: {}", e)
+        })
+}
+
+#[cfg(target_os = "macos")]
+fn open_path(path: &Path) -> Result<(), String> {
+    spawn_viewer(std::process::Command::new("open").arg(path))
+}
+
+#[cfg(target_os = "windows")]
+fn open_path(path: &Path) -> Result<(), String> {
+    spawn_viewer(
+        std::process::Command::new("cmd")
+            .args(["/C", "start", ""])
+            .arg(path),
+    )
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn open_path(path: &Path) -> Result<(), String> {
+    spawn_viewer(std::process::Command::new("xdg-open").arg(path))
+}
+
+fn spawn_viewer(command: &mut std::process::Command) -> Result<(), String> {
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open datasheet: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_blobs_across_urls_and_exports_a_bundle() {
+        let dir = std::env::temp_dir().join(format!("aha-datasheets-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let store = DatasheetStore::new(dir.join("store"));
+        let pdf = b"%PDF-1.7\nstub datasheet\n%%EOF";
+
+        assert!(store.store("https://example.com/page", b"<html>").is_err());
+        let first = store.store("https://example.com/a.pdf", pdf).unwrap();
+        let second = store
+            .store("https://mirror.example.com/a.pdf", pdf)
+            .unwrap();
+        assert_eq!(first.sha256, second.sha256);
+        assert_eq!(fs::read_dir(dir.join("store/blobs")).unwrap().count(), 1);
+        assert!(store.path(&first.sha256).is_ok());
+        assert!(store.path("../index").is_err());
+
+        let mut workspace = json!({
+            "nodes": [
+                {"id": "a", "data": {"label": "Buck", "mpn": "TPS62130/RGT", "datasheet_url": "https://example.com/a.pdf"}},
+                {"id": "b", "data": {"label": "Buck 2", "datasheet_url": "https://mirror.example.com/a.pdf"}},
+                {"id": "c", "data": {"label": "MCU", "datasheet_url": "https://example.com/missing.pdf"}},
+                {"id": "d", "data": {"label": "Cap", "datasheet_url": "file:///etc/passwd"}}
+            ],
+            "edges": []
+        });
+        let fetched = HashMap::from([
+            (
+                "https://example.com/a.pdf".to_string(),
+                (Ok(first.clone()), true),
+            ),
+            (
+                "https://mirror.example.com/a.pdf".to_string(),
+                (Ok(second), false),
+            ),
+            (
+                "https://example.com/missing.pdf".to_string(),
+                (Err("HTTP 404".to_string()), true),
+            ),
+        ]);
+        let report = record(&mut workspace, &store, &fetched).unwrap();
+        assert_eq!(report.datasheets.len(), 3);
+        assert_eq!((report.downloaded, report.failed), (1, 1));
+        assert_eq!(
+            workspace["nodes"][1]["data"][SHA256_KEY],
+            json!(first.sha256)
+        );
+        assert!(workspace["nodes"][2]["data"].get(SHA256_KEY).is_none());
+
+        // A later revision at the same URL doesn't change what the nodes
+        // were archived with.
+        let revised = store
+            .store("https://example.com/a.pdf", b"%PDF-1.7\nrevision B\n%%EOF")
+            .unwrap();
+        assert_ne!(revised.sha256, first.sha256);
+
+        let export = export_zip(&store, &workspace, &dir.join("bundle.zip")).unwrap();
+        assert_eq!(export.files, 1);
+        assert_eq!(export.missing, ["MCU"]);
+        let mut archive =
+            zip::ZipArchive::new(fs::File::open(dir.join("bundle.zip")).unwrap()).unwrap();
+        let mut exported = Vec::new();
+        std::io::Read::read_to_end(
+            &mut archive.by_name("datasheets/TPS62130_RGT.pdf").unwrap(),
+            &mut exported,
+        )
+        .unwrap();
+        assert_eq!(exported, pdf);
+        assert!(archive.by_name("manifest.json").is_ok());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::profile::SimulationProfile;
+use crate::progress::ProgressSink;
+use crate::simulator;
+
+/// Name under which the bundled Python thermal/DRC runner is registered.
+pub const BUILTIN_ENGINE: &str = "thermal-drc";
+/// Version of the graph-in / result-out JSON contract this build speaks.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Manifest describing an external analysis engine.
+///
+/// Manifests are `*.json` files in the engines directory:
+///
+/// ```json
+/// {
+///   "name": "power-tree",
+///   "command": "julia",
+///   "args": ["./power_tree.jl", "{input}"],
+///   "inputSchemaVersion": 1,
+///   "outputSchemaVersion": 1,
+///   "capabilities": ["power"]
+/// }
+/// ```
+///
+/// A `command` or argument starting with `./` or `../` is resolved against
+/// the manifest's directory; `{input}` is replaced by the input file path.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_schema_version")]
+    pub input_schema_version: u32,
+    #[serde(default = "default_schema_version")]
+    pub output_schema_version: u32,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineInfo {
+    #[serde(flatten)]
+    pub manifest: EngineManifest,
+    /// `"builtin"` or the manifest file path.
+    pub source: String,
+    pub available: bool,
+    pub problem: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct RegisteredEngine {
+    manifest: EngineManifest,
+    base_dir: Option<PathBuf>,
+    source: String,
+    problem: Option<String>,
+}
+
+fn default_schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+/// Built-in engine plus every manifest found in `engines_dir`.
+pub fn list(engines_dir: &Path) -> Vec<EngineInfo> {
+    discover(engines_dir)
+        .into_iter()
+        .map(|engine| EngineInfo {
+            available: engine.problem.is_none(),
+            manifest: engine.manifest,
+            source: engine.source,
+            problem: engine.problem,
+        })
+        .collect()
+}
+
+/// Runs `engine_name` on `document` with the same contract as the built-in
+/// runner: the input file carries the graph, the resolved `profile` and the
+/// caller's `options`; stdout must be a result object with `status` and
+/// `issues`.
+pub fn run(
+    engines_dir: &Path,
+    engine_name: &str,
+    document: &Value,
+    options: &Value,
+    on_progress: ProgressSink,
+) -> Result<Value, String> {
+    let engine = discover(engines_dir)
+        .into_iter()
+        .find(|engine| engine.manifest.name == engine_name)
+        .ok_or_else(|| format!("Unknown analysis engine '{}'.", engine_name))?;
+    if let Some(problem) = &engine.problem {
+        return Err(format!(
+            "Analysis engine '{}' is unavailable: {}",
+            engine_name, problem
+        ));
+    }
+
+    let profile =
+        SimulationProfile::resolve(options.get("profile").and_then(Value::as_str).unwrap_or(""));
+    let mut input = simulator::with_profile(document, &profile)?;
+    if let Some(object) = input.as_object_mut() {
+        object.insert("options".to_string(), options.clone());
+    }
+
+    let (program, args) = resolve_invocation(&engine);
+    let stdout = simulator::invoke(&program, &args, &input, &engine.manifest.name, on_progress)?;
+    parse_result(&engine.manifest.name, &stdout)
+}
+
+fn discover(engines_dir: &Path) -> Vec<RegisteredEngine> {
+    let mut engines = vec![builtin_engine()];
+
+    let mut manifest_paths: Vec<PathBuf> = fs::read_dir(engines_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    manifest_paths.sort();
+
+    for path in manifest_paths {
+        let source = path.to_string_lossy().to_string();
+        let manifest = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read manifest: {}", e))
+            .and_then(|text| {
+                serde_json::from_str::<EngineManifest>(&text)
+                    .map_err(|e| format!("Invalid manifest: {}", e))
+            });
+        let manifest = match manifest {
+            Ok(manifest) => manifest,
+            Err(problem) => {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                engines.push(RegisteredEngine {
+                    manifest: EngineManifest {
+                        name,
+                        description: None,
+                        command: String::new(),
+                        args: Vec::new(),
+                        input_schema_version: SCHEMA_VERSION,
+                        output_schema_version: SCHEMA_VERSION,
+                        capabilities: Vec::new(),
+                    },
+                    base_dir: None,
+                    source,
+                    problem: Some(problem),
+                });
+                continue;
+            }
+        };
+
+        let problem = validate(&manifest, &engines);
+        engines.push(RegisteredEngine {
+            manifest,
+            base_dir: path.parent().map(Path::to_path_buf),
+            source,
+            problem,
+        });
+    }
+
+    engines
+}
+
+fn builtin_engine() -> RegisteredEngine {
+    RegisteredEngine {
+        manifest: EngineManifest {
+            name: BUILTIN_ENGINE.to_string(),
+            description: Some(
+                "Bundled Python power aggregation, thermal and DRC runner.".to_string(),
+            ),
+            command: "python3".to_string(),
+            args: vec![simulator::runner_script_path()
+                .to_string_lossy()
+                .to_string()],
+            input_schema_version: SCHEMA_VERSION,
+            output_schema_version: SCHEMA_VERSION,
+            capabilities: vec![
+                "power".to_string(),
+                "thermal".to_string(),
+                "drc".to_string(),
+            ],
+        },
+        base_dir: None,
+        source: "builtin".to_string(),
+        problem: None,
+    }
+}
+
+fn validate(manifest: &EngineManifest, registered: &[RegisteredEngine]) -> Option<String> {
+    if manifest.name.trim().is_empty() {
+        return Some("Manifest has an empty name.".to_string());
+    }
+    if manifest.command.trim().is_empty() {
+        return Some("Manifest has an empty command.".to_string());
+    }
+    if registered
+        .iter()
+        .any(|engine| engine.manifest.name == manifest.name)
+    {
+        return Some(format!(
+            "Engine name '{}' is already registered.",
+            manifest.name
+        ));
+    }
+    if manifest.input_schema_version != SCHEMA_VERSION
+        || manifest.output_schema_version != SCHEMA_VERSION
+    {
+        return Some(format!(
+            "Unsupported schema version (input {}, output {}); this build supports {}.",
+            manifest.input_schema_version, manifest.output_schema_version, SCHEMA_VERSION
+        ));
+    }
+    None
+}
+
+fn resolve_invocation(engine: &RegisteredEngine) -> (String, Vec<String>) {
+    let resolve = |value: &str| -> String {
+        match &engine.base_dir {
+            Some(base) if value.starts_with("./") || value.starts_with("../") => {
+                base.join(value).to_string_lossy().to_string()
+            }
+            _ => value.to_string(),
+        }
+    };
+
+    (
+        resolve(&engine.manifest.command),
+        engine
+            .manifest
+            .args
+            .iter()
+            .map(|arg| resolve(arg))
+            .collect(),
+    )
+}
+
+fn parse_result(engine_name: &str, stdout: &str) -> Result<Value, String> {
+    let result: Value = serde_json::from_str(stdout.trim()).map_err(|e| {
+        format!(
+            "Analysis engine '{}' returned invalid JSON: {}",
+            engine_name, e
+        )
+    })?;
+    let has_status = result.get("status").is_some_and(Value::is_string);
+    let has_issues = result.get("issues").is_some_and(Value::is_array);
+    if !has_status || !has_issues {
+        return Err(format!(
+            "Analysis engine '{}' output does not match schema version {}: expected `status` and `issues`.",
+            engine_name, SCHEMA_VERSION
+        ));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovers_manifests_and_flags_invalid_ones() {
+        let dir = std::env::temp_dir().join(format!("aha_engines_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("power.json"),
+            r#"{"name":"power-tree","command":"./power_tree","args":["--in","{input}"],"capabilities":["power"]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("future.json"),
+            r#"{"name":"future","command":"future-engine","inputSchemaVersion":2}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("clash.json"),
+            r#"{"name":"thermal-drc","command":"python3"}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("broken.json"), "{ not json").unwrap();
+
+        let engines = list(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        let by_name = |name: &str| {
+            engines
+                .iter()
+                .filter(|engine| engine.manifest.name == name)
+                .collect::<Vec<_>>()
+        };
+        assert!(by_name(BUILTIN_ENGINE)[0].available);
+        assert!(by_name(BUILTIN_ENGINE)[1].problem.is_some());
+        assert!(by_name("power-tree")[0].available);
+        assert!(!by_name("future")[0].available);
+        assert!(!by_name("broken")[0].available);
+    }
+
+    #[test]
+    fn rejects_output_without_status_and_issues() {
+        assert!(parse_result("x", r#"{"status":"success","issues":[]}"#).is_ok());
+        assert!(parse_result("x", r#"{"status":"success"}"#).is_err());
+        assert!(parse_result("x", "not json").is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One issue reported by a backend design check, with the graph elements it
+/// involves so the canvas can highlight them.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    /// Stable machine-readable identifier, e.g. `unpowered-consumer`.
+    pub code: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub node_ids: Vec<String>,
+    pub edge_ids: Vec<String>,
+}
+
+impl Finding {
+    pub fn new(code: &'static str, severity: Severity, message: String) -> Self {
+        Self {
+            code,
+            severity,
+            message,
+            node_ids: Vec::new(),
+            edge_ids: Vec::new(),
+        }
+    }
+
+    pub fn with_nodes(mut self, node_ids: Vec<String>) -> Self {
+        self.node_ids = node_ids;
+        self
+    }
+
+    pub fn with_edges(mut self, edge_ids: Vec<String>) -> Self {
+        self.edge_ids = edge_ids;
+        self
+    }
+}
+
+/// Result status in the same vocabulary as the simulation engine.
+pub fn overall_status(findings: &[Finding]) -> &'static str {
+    match findings.iter().map(|finding| finding.severity).min() {
+        Some(Severity::Error) => "error",
+        Some(Severity::Warning) => "warning",
+        _ => "success",
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Typed view of the React Flow graph the frontend sends over IPC.
+///
+/// Both the bare `{ nodes, edges }` shape used by the simulation panel and the
+/// `.aha.json` workspace envelope deserialize into this struct; unknown fields
+/// are ignored and node data stays an open map so analyses can read whatever
+/// attributes they need.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DesignGraph {
+    #[serde(default)]
+    pub nodes: Vec<GraphNode>,
+    #[serde(default)]
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    #[serde(default)]
+    pub data: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdge {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub source: String,
+    pub target: String,
+    #[serde(default)]
+    pub source_handle: Option<String>,
+    #[serde(default)]
+    pub target_handle: Option<String>,
+}
+
+/// What an edge carries, from its handle ids (`pwr-out`, `data-in`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    Power,
+    Data,
+    Clock,
+    /// RF signal path (`rf-*` or `ant-*` handles).
+    Rf,
+    Unknown,
+}
+
+impl DesignGraph {
+    pub fn from_json(graph_json: &str) -> Result<Self, String> {
+        serde_json::from_str(graph_json).map_err(|e| format!("Invalid graph JSON: {}", e))
+    }
+}
+
+impl GraphEdge {
+    /// Edge id for reports; React Flow always sets one, hand-written graphs
+    /// may not.
+    pub fn display_id(&self) -> String {
+        self.id
+            .clone()
+            .unwrap_or_else(|| format!("{}->{}", self.source, self.target))
+    }
+
+    /// Classified by the source handle, falling back to the target handle.
+    pub fn kind(&self) -> EdgeKind {
+        [&self.source_handle, &self.target_handle]
+            .into_iter()
+            .flatten()
+            .find_map(|handle| {
+                if handle.starts_with("pwr") {
+                    Some(EdgeKind::Power)
+                } else if handle.starts_with("data") {
+                    Some(EdgeKind::Data)
+                } else if handle.starts_with("clk") {
+                    Some(EdgeKind::Clock)
+                } else if handle.starts_with("rf") || handle.starts_with("ant") {
+                    Some(EdgeKind::Rf)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(EdgeKind::Unknown)
+    }
+}
+
+impl GraphNode {
+    pub fn label(&self) -> String {
+        self.text("label").unwrap_or_else(|| self.id.clone())
+    }
+
+    pub fn category(&self) -> String {
+        self.text("category")
+            .unwrap_or_else(|| "Component".to_string())
+    }
+
+    pub fn text(&self, key: &str) -> Option<String> {
+        match self.data.get(key)? {
+            Value::String(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+            Value::Number(number) => Some(number.to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn flag(&self, key: &str) -> bool {
+        match self.data.get(key) {
+            Some(Value::Bool(flag)) => *flag,
+            Some(Value::String(text)) => text.trim().eq_ignore_ascii_case("true"),
+            _ => false,
+        }
+    }
+
+    /// Reads a numeric attribute, accepting numbers and numeric strings since
+    /// the property panel stores whatever the user typed.
+    pub fn number(&self, key: &str) -> Option<f64> {
+        match self.data.get(key)? {
+            Value::Number(number) => number.as_f64(),
+            Value::String(text) => text.trim().parse::<f64>().ok(),
+            _ => None,
+        }
+        .filter(|value| value.is_finite())
+    }
+}
@@ -0,0 +1,352 @@
+mod clock;
+mod credentials;
+mod datasheets;
+mod engines;
+mod findings;
+mod graph;
+mod library;
+mod lifecycle;
+mod llm;
+mod parts;
+mod profile;
+mod progress;
+mod reliability;
+mod rf;
+mod sandbox;
+mod sim_cache;
+mod simulator;
+pub mod sourcing;
+mod sweep;
+mod topology;
+
+use clock::ClockReport;
+use engines::EngineInfo;
+use graph::DesignGraph;
+use profile::SimulationProfile;
+use progress::{ProgressEvent, ProgressUpdate, PROGRESS_EVENT};
+use reliability::ReliabilityReport;
+use rf::RfReport;
+use serde_json::{json, Value};
+use sim_cache::{CacheStats, SimulationCache};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use sweep::{SweepReport, SweepSpec};
+use tauri::{AppHandle, Emitter, Manager};
+use topology::{TopologyOptions, TopologyReport};
+
+#[tauri::command]
+fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
+
+#[tauri::command]
+async fn run_thermal_simulation(
+    app: AppHandle,
+    graph_json: String,
+    profile: String,
+    force: Option<bool>,
+    run_id: Option<String>,
+) -> Result<String, String> {
+    let document: Value =
+        serde_json::from_str(&graph_json).map_err(|e| format!("Invalid graph JSON: {}", e))?;
+    let cache = simulation_cache(&app)?;
+    let on_progress = progress_emitter(app, run_id, engines::BUILTIN_ENGINE);
+    tauri::async_runtime::spawn_blocking(move || {
+        cache.run(
+            &document,
+            &SimulationProfile::resolve(&profile),
+            force.unwrap_or(false),
+            &on_progress,
+        )
+    })
+    .await
+    .map_err(|e| format!("Simulation task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn run_parameter_sweep(
+    app: AppHandle,
+    graph_json: String,
+    sweep: SweepSpec,
+    run_id: Option<String>,
+) -> Result<SweepReport, String> {
+    let document: Value =
+        serde_json::from_str(&graph_json).map_err(|e| format!("Invalid graph JSON: {}", e))?;
+    let cache = simulation_cache(&app)?;
+    let on_progress = progress_emitter(app, run_id, "sweep");
+    tauri::async_runtime::spawn_blocking(move || sweep::run(document, sweep, &cache, &on_progress))
+        .await
+        .map_err(|e| format!("Parameter sweep task failed: {}", e))?
+}
+
+#[tauri::command]
+fn get_simulation_cache_stats(app: AppHandle) -> Result<CacheStats, String> {
+    Ok(simulation_cache(&app)?.stats())
+}
+
+#[tauri::command]
+fn clear_simulation_cache(app: AppHandle) -> Result<CacheStats, String> {
+    simulation_cache(&app)?.clear()
+}
+
+#[tauri::command]
+fn list_analysis_engines(app: AppHandle) -> Result<Vec<EngineInfo>, String> {
+    Ok(engines::list(&app_data_subdir(&app, "engines")?))
+}
+
+#[tauri::command]
+async fn run_analysis(
+    app: AppHandle,
+    engine: String,
+    graph_json: String,
+    options: Option<Value>,
+    run_id: Option<String>,
+) -> Result<Value, String> {
+    let document: Value =
+        serde_json::from_str(&graph_json).map_err(|e| format!("Invalid graph JSON: {}", e))?;
+    let engines_dir = app_data_subdir(&app, "engines")?;
+    let options = options.unwrap_or_else(|| json!({}));
+    let on_progress = progress_emitter(app, run_id, &engine);
+    tauri::async_runtime::spawn_blocking(move || {
+        engines::run(&engines_dir, &engine, &document, &options, &on_progress)
+    })
+    .await
+    .map_err(|e| format!("Analysis task failed: {}", e))?
+}
+
+/// Forwards engine progress to the webview as `analysis-progress` events
+/// tagged with the caller's run id (or a generated one).
+fn progress_emitter(
+    app: AppHandle,
+    run_id: Option<String>,
+    engine: &str,
+) -> impl Fn(ProgressUpdate) + Send + Sync + 'static {
+    let run_id = run_id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(progress::new_run_id);
+    let engine = engine.to_string();
+    move |update| {
+        let _ = app.emit(
+            PROGRESS_EVENT,
+            ProgressEvent {
+                run_id: run_id.clone(),
+                engine: engine.clone(),
+                update,
+            },
+        );
+    }
+}
+
+fn simulation_cache(app: &AppHandle) -> Result<SimulationCache, String> {
+    Ok(SimulationCache::new(app_data_subdir(
+        app,
+        "simulation-cache",
+    )?))
+}
+
+fn app_data_subdir(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    Ok(dir.join(name))
+}
+
+/// Current Unix time in seconds, 0 if the clock is before the epoch.
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+fn analyze_reliability(graph_json: &str, profile: &str) -> Result<ReliabilityReport, String> {
+    let graph = DesignGraph::from_json(graph_json)?;
+    Ok(reliability::analyze(
+        &graph,
+        &SimulationProfile::resolve(profile),
+    ))
+}
+
+#[tauri::command]
+fn check_graph_topology(
+    graph_json: &str,
+    options: Option<TopologyOptions>,
+) -> Result<TopologyReport, String> {
+    let graph = DesignGraph::from_json(graph_json)?;
+    Ok(topology::check(&graph, &options.unwrap_or_default()))
+}
+
+#[tauri::command]
+fn analyze_clock_tree(graph_json: &str) -> Result<ClockReport, String> {
+    let graph = DesignGraph::from_json(graph_json)?;
+    Ok(clock::analyze(&graph))
+}
+
+#[tauri::command]
+fn analyze_rf_coexistence(graph_json: &str) -> Result<RfReport, String> {
+    let graph = DesignGraph::from_json(graph_json)?;
+    Ok(rf::analyze(&graph))
+}
+
+#[tauri::command]
+fn execute_git_command(args: Vec<String>) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to execute git command: {}", e))?;
+
+    let stdout = String::from_utf8(output.stdout).unwrap_or_default();
+    let stderr = String::from_utf8(output.stderr).unwrap_or_default();
+
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        Err(format!("Git error: {}\n{}", stderr, stdout))
+    }
+}
+
+#[tauri::command]
+fn save_workspace_file(path: String, graph_json: String) -> Result<String, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Workspace path is empty.".to_string());
+    }
+
+    let workspace_path = PathBuf::from(trimmed);
+    if let Some(parent) = workspace_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create workspace directory: {}", e))?;
+    }
+
+    fs::write(&workspace_path, graph_json)
+        .map_err(|e| format!("Failed to write workspace file: {}", e))?;
+
+    let resolved_path = workspace_path
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_path.clone());
+
+    Ok(resolved_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn load_workspace_file(path: String) -> Result<String, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Workspace path is empty.".to_string());
+    }
+
+    fs::read_to_string(trimmed).map_err(|e| format!("Failed to read workspace file: {}", e))
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_dialog::init())
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            run_thermal_simulation,
+            run_parameter_sweep,
+            get_simulation_cache_stats,
+            clear_simulation_cache,
+            list_analysis_engines,
+            run_analysis,
+            analyze_reliability,
+            check_graph_topology,
+            analyze_clock_tree,
+            analyze_rf_coexistence,
+            execute_git_command,
+            save_workspace_file,
+            load_workspace_file,
+            credentials::credential_vault_status,
+            credentials::unlock_credential_vault,
+            credentials::lock_credential_vault,
+            credentials::set_credential_vault_passphrase,
+            credentials::list_credentials,
+            credentials::set_credential,
+            credentials::delete_credential,
+            datasheets::archive_workspace_datasheets,
+            datasheets::open_datasheet,
+            datasheets::export_workspace_datasheets,
+            library::list_library,
+            library::search_library,
+            library::save_library_entry,
+            library::delete_library_entry,
+            library::set_team_library_root,
+            llm::list_llm_models,
+            llm::llm_chat_completion,
+            sourcing::search_trustedparts_inventory,
+            sourcing::list_parts_providers,
+            sourcing::search_parts,
+            sourcing::lookup_part_by_mpn,
+            sourcing::lookup_trustedparts_mpns,
+            sourcing::check_workspace_lifecycle,
+            sourcing::find_part_alternates,
+            sourcing::quote_part_offer,
+            sourcing::get_part_taxonomy,
+            sourcing::set_part_taxonomy,
+            sourcing::classify_part,
+            sourcing::get_exchange_rates,
+            sourcing::set_exchange_rates,
+            sourcing::import_exchange_rates_csv,
+            sourcing::get_part_cache_stats,
+            sourcing::invalidate_part_search_cache
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_python_simulator_bridge() {
+        let pydantic_check = Command::new("python3")
+            .arg("-c")
+            .arg("import pydantic")
+            .output()
+            .expect("failed to execute python dependency check");
+
+        if !pydantic_check.status.success() {
+            eprintln!("Skipping simulator bridge test: python dependency 'pydantic' is missing.");
+            return;
+        }
+
+        let mut temp_path = std::env::temp_dir();
+        temp_path.push("aha_test_input.json");
+        let mut temp_file = std::fs::File::create(&temp_path).unwrap();
+        write!(temp_file, r#"{{"nodes":[{{"id":"1"}},{{"id":"2"}}]}}"#).unwrap();
+
+        let current_dir = std::env::current_dir().unwrap_or_default();
+        let possible_paths = [
+            current_dir.join("../../../simulator/python-runner/main.py"),
+            current_dir.join("../../simulator/python-runner/main.py"),
+            current_dir.join("simulator/python-runner/main.py"),
+        ];
+        let python_script = possible_paths
+            .into_iter()
+            .find(|path| path.exists())
+            .expect("failed to locate simulator/python-runner/main.py");
+
+        let output = Command::new("python3")
+            .arg(python_script)
+            .arg(&temp_path)
+            .output()
+            .expect("failed to execute python script");
+
+        let _ = std::fs::remove_file(&temp_path);
+
+        assert!(
+            output.status.success(),
+            "python runner failed with stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains("status"));
+        assert!(stdout.contains("total_power_w"));
+    }
+}
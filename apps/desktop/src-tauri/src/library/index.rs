@@ -0,0 +1,807 @@
+//! In-memory search index over library entries and cached distributor hits.
+//!
+//! Part numbers are compared with everything but letters and digits
+//! removed, so "TPS62130-RGTR", "tps62130rgtr" and the base number
+//! "TPS62130" find each other, by prefix in either direction or with a typo.
+//! Words of labels, manufacturers, descriptions, ports and parsed parameters
+//! match exactly, by prefix or with one typo. Every whitespace-separated
+//! query term has to match; scores add up across terms.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Instant, UNIX_EPOCH};
+
+use super::LibraryEntry;
+use crate::parts::{AttributeValue, TrustedPartHit};
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// Field weights; a word found in several fields of a part counts once, at
+/// its best weight.
+const LABEL_WEIGHT: f32 = 3.0;
+const MANUFACTURER_WEIGHT: f32 = 2.0;
+const CATEGORY_WEIGHT: f32 = 2.0;
+const PACKAGE_WEIGHT: f32 = 1.5;
+const TEXT_WEIGHT: f32 = 1.0;
+
+const MPN_EXACT_SCORE: f32 = 10.0;
+const MPN_PREFIX_SCORE: f32 = 6.0;
+const MPN_BASE_SCORE: f32 = 5.0;
+const MPN_FUZZY_SCORE: f32 = 4.0;
+/// A part number shorter than this is never taken as the base of a longer
+/// query, or "TPS" would match every TI part.
+const MIN_BASE_MPN: usize = 4;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexQuery {
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub manufacturer: Option<String>,
+    /// Search cached distributor hits too; on by default.
+    #[serde(default)]
+    pub include_cached: Option<bool>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "source", rename_all = "camelCase")]
+pub enum IndexedPart {
+    Library { entry: LibraryEntry },
+    Cached { hit: TrustedPartHit },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexHit {
+    pub score: f32,
+    #[serde(flatten)]
+    pub part: IndexedPart,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Counts over every match, before the category and manufacturer filters,
+/// so the other choices stay visible once one is picked.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Facets {
+    pub categories: Vec<FacetCount>,
+    pub manufacturers: Vec<FacetCount>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexResults {
+    /// Matches after filtering; `hits` holds the best `limit` of them.
+    pub total: usize,
+    pub hits: Vec<IndexHit>,
+    pub facets: Facets,
+    pub elapsed_micros: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    parts: Vec<IndexedPart>,
+    categories: Vec<u32>,
+    manufacturers: Vec<Option<u32>>,
+    category_names: Vec<String>,
+    manufacturer_names: Vec<String>,
+    /// Normalized part number and part, sorted so prefixes are ranges.
+    mpns: Vec<(String, u32)>,
+    /// Sorted vocabulary and, for each word, the parts and weights.
+    words: Vec<String>,
+    postings: Vec<Vec<(u32, f32)>>,
+}
+
+impl SearchIndex {
+    /// Library entries rank ahead of cached hits with the same score.
+    pub fn build(entries: Vec<LibraryEntry>, hits: Vec<TrustedPartHit>) -> Self {
+        let mut index = Self::default();
+        let mut category_ids: HashMap<String, u32> = HashMap::new();
+        let mut manufacturer_ids: HashMap<String, u32> = HashMap::new();
+        let mut postings: HashMap<String, Vec<(u32, f32)>> = HashMap::new();
+
+        let parts = entries
+            .into_iter()
+            .map(|entry| IndexedPart::Library { entry })
+            .chain(hits.into_iter().map(|hit| IndexedPart::Cached { hit }));
+        for (id, part) in parts.enumerate() {
+            let id = id as u32;
+            let fields = part.fields();
+
+            let category = intern(
+                &mut category_ids,
+                &mut index.category_names,
+                fields.category,
+            );
+            index.categories.push(category);
+            index.manufacturers.push(
+                fields
+                    .manufacturer
+                    .filter(|name| !name.trim().is_empty())
+                    .map(|name| intern(&mut manufacturer_ids, &mut index.manufacturer_names, name)),
+            );
+
+            if let Some(mpn) = fields.mpn.map(normalize_mpn).filter(|mpn| !mpn.is_empty()) {
+                index.mpns.push((mpn, id));
+            }
+
+            let mut weights: HashMap<String, f32> = HashMap::new();
+            for (text, weight) in &fields.words {
+                for word in tokenize(text) {
+                    let best = weights.entry(word).or_insert(0.0);
+                    *best = best.max(*weight);
+                }
+            }
+            for (word, weight) in weights {
+                postings.entry(word).or_default().push((id, weight));
+            }
+            index.parts.push(part);
+        }
+
+        index.mpns.sort();
+        let mut vocabulary: Vec<(String, Vec<(u32, f32)>)> = postings.into_iter().collect();
+        vocabulary.sort_by(|a, b| a.0.cmp(&b.0));
+        (index.words, index.postings) = vocabulary.into_iter().unzip();
+        index
+    }
+
+    pub fn search(&self, query: &IndexQuery) -> IndexResults {
+        let started = Instant::now();
+        let count = self.parts.len();
+        let terms: Vec<&str> = query.text.split_whitespace().collect();
+
+        let mut scores = vec![0.0f32; count];
+        let mut matched = vec![0u16; count];
+        let mut term_scores = Scores::new(count);
+        let mut word_scores = Scores::new(count);
+        let mut word_sums = vec![0.0f32; count];
+        let mut word_counts = vec![0u16; count];
+        let mut word_touched: Vec<u32> = Vec::new();
+
+        for term in &terms {
+            self.match_mpn(term, &mut term_scores);
+
+            // Every word of the term (e.g. "step" and "down" of "step-down")
+            // has to match for the word route to count.
+            let words = tokenize(term);
+            for word in &words {
+                self.match_word(word, &mut word_scores);
+                word_scores.drain(|part, score| {
+                    if word_counts[part] == 0 {
+                        word_touched.push(part as u32);
+                    }
+                    word_counts[part] += 1;
+                    word_sums[part] += score;
+                });
+            }
+            for part in word_touched.drain(..) {
+                let part = part as usize;
+                if usize::from(word_counts[part]) == words.len() {
+                    term_scores.add(part, word_sums[part]);
+                }
+                word_counts[part] = 0;
+                word_sums[part] = 0.0;
+            }
+
+            term_scores.drain(|part, score| {
+                scores[part] += score;
+                matched[part] += 1;
+            });
+        }
+
+        let include_cached = query.include_cached.unwrap_or(true);
+        let category = query
+            .category
+            .as_deref()
+            .map(|name| lookup(&self.category_names, name));
+        let manufacturer = query
+            .manufacturer
+            .as_deref()
+            .map(|name| lookup(&self.manufacturer_names, name));
+
+        let mut category_counts = vec![0usize; self.category_names.len()];
+        let mut manufacturer_counts = vec![0usize; self.manufacturer_names.len()];
+        let mut results: Vec<u32> = Vec::new();
+        for part in 0..count {
+            if usize::from(matched[part]) != terms.len()
+                || (!include_cached && matches!(self.parts[part], IndexedPart::Cached { .. }))
+            {
+                continue;
+            }
+            category_counts[self.categories[part] as usize] += 1;
+            if let Some(id) = self.manufacturers[part] {
+                manufacturer_counts[id as usize] += 1;
+            }
+            let wanted = category.is_none_or(|id| id == Some(self.categories[part]))
+                && manufacturer.is_none_or(|id| id.is_some() && id == self.manufacturers[part]);
+            if wanted {
+                results.push(part as u32);
+            }
+        }
+
+        let total = results.len();
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let order = |a: &u32, b: &u32| {
+            scores[*b as usize]
+                .total_cmp(&scores[*a as usize])
+                .then(a.cmp(b))
+        };
+        if results.len() > limit {
+            results.select_nth_unstable_by(limit, order);
+            results.truncate(limit);
+        }
+        results.sort_unstable_by(order);
+
+        IndexResults {
+            total,
+            hits: results
+                .into_iter()
+                .map(|part| IndexHit {
+                    score: (scores[part as usize] * 100.0).round() / 100.0,
+                    part: self.parts[part as usize].clone(),
+                })
+                .collect(),
+            facets: Facets {
+                categories: facet(&self.category_names, &category_counts),
+                manufacturers: facet(&self.manufacturer_names, &manufacturer_counts),
+            },
+            elapsed_micros: started.elapsed().as_micros() as u64,
+        }
+    }
+
+    fn match_mpn(&self, term: &str, scores: &mut Scores) {
+        let wanted = normalize_mpn(term);
+        if wanted.len() < 2 {
+            return;
+        }
+
+        // Longer part numbers starting with the term, closest length first.
+        for (mpn, part) in self.mpn_range(&wanted) {
+            let score = if *mpn == wanted {
+                MPN_EXACT_SCORE
+            } else {
+                MPN_PREFIX_SCORE + 3.0 * wanted.len() as f32 / mpn.len() as f32
+            };
+            scores.add(*part as usize, score);
+        }
+        // Base part numbers the term extends with a suffix.
+        for end in (MIN_BASE_MPN..wanted.len()).rev() {
+            let base = &wanted[..end];
+            for (mpn, part) in self.mpn_range(base).filter(|(mpn, _)| mpn == base) {
+                scores.add(
+                    *part as usize,
+                    MPN_BASE_SCORE + 3.0 * mpn.len() as f32 / wanted.len() as f32,
+                );
+            }
+        }
+        if !scores.is_empty() || wanted.len() < 5 {
+            return;
+        }
+
+        // Typos, assuming the first two characters are right. Candidates
+        // are compared at the term's length so a typo in a prefix counts.
+        let max_edits = if wanted.len() < 8 { 1 } else { 2 };
+        for (mpn, part) in self.mpn_range(&wanted[..2]) {
+            let end = floor_char_boundary(mpn, wanted.len());
+            let edits = edit_distance(&wanted, &mpn[..end], max_edits)
+                .or_else(|| edit_distance(&wanted, mpn, max_edits));
+            if let Some(edits) = edits {
+                scores.add(*part as usize, MPN_FUZZY_SCORE - edits as f32);
+            }
+        }
+    }
+
+    fn match_word(&self, word: &str, scores: &mut Scores) {
+        let start = self.words.partition_point(|known| known.as_str() < word);
+        for (known, postings) in self.words[start..]
+            .iter()
+            .zip(&self.postings[start..])
+            .take_while(|(known, _)| known.starts_with(word))
+        {
+            // Short prefixes ("s") would match half the vocabulary.
+            if known != word && word.chars().count() < 2 {
+                continue;
+            }
+            let closeness = if known == word {
+                1.0
+            } else {
+                0.5 + 0.4 * word.len() as f32 / known.len() as f32
+            };
+            for (part, weight) in postings {
+                scores.add(*part as usize, weight * closeness);
+            }
+        }
+        if !scores.is_empty() || word.chars().count() < 4 {
+            return;
+        }
+
+        let first = &word[..floor_char_boundary(word, 1)];
+        let start = self.words.partition_point(|known| known.as_str() < first);
+        for (known, postings) in self.words[start..]
+            .iter()
+            .zip(&self.postings[start..])
+            .take_while(|(known, _)| known.starts_with(first))
+        {
+            if known.len().abs_diff(word.len()) > 1 || edit_distance(word, known, 1).is_none() {
+                continue;
+            }
+            for (part, weight) in postings {
+                scores.add(*part as usize, weight * 0.4);
+            }
+        }
+    }
+
+    fn mpn_range<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a (String, u32)> + 'a {
+        let start = self.mpns.partition_point(|(mpn, _)| mpn.as_str() < prefix);
+        self.mpns[start..]
+            .iter()
+            .take_while(move |(mpn, _)| mpn.starts_with(prefix))
+    }
+}
+
+struct Fields<'a> {
+    category: &'a str,
+    manufacturer: Option<&'a str>,
+    mpn: Option<&'a str>,
+    words: Vec<(String, f32)>,
+}
+
+impl IndexedPart {
+    fn fields(&self) -> Fields<'_> {
+        match self {
+            IndexedPart::Library { entry } => {
+                let mut words = vec![
+                    (entry.label.clone(), LABEL_WEIGHT),
+                    (entry.category.clone(), CATEGORY_WEIGHT),
+                ];
+                let optional = [
+                    (&entry.manufacturer, MANUFACTURER_WEIGHT),
+                    (&entry.package, PACKAGE_WEIGHT),
+                    (&entry.description, TEXT_WEIGHT),
+                ];
+                for (text, weight) in optional {
+                    words.extend(text.clone().map(|text| (text, weight)));
+                }
+                for port in &entry.ports {
+                    words.push((format!("{} {}", port.name, port.kind), TEXT_WEIGHT));
+                }
+                Fields {
+                    category: &entry.category,
+                    manufacturer: entry.manufacturer.as_deref(),
+                    // Bundled parts are often named by their part number.
+                    mpn: entry.mpn.as_deref().or(Some(&entry.label)),
+                    words,
+                }
+            }
+            IndexedPart::Cached { hit } => {
+                let mut words = vec![
+                    (hit.mpn.clone(), LABEL_WEIGHT),
+                    (hit.category_hint.clone(), CATEGORY_WEIGHT),
+                ];
+                words.extend(
+                    hit.manufacturer
+                        .clone()
+                        .map(|name| (name, MANUFACTURER_WEIGHT)),
+                );
+                words.extend(hit.description.clone().map(|text| (text, TEXT_WEIGHT)));
+                for value in hit.attributes.values() {
+                    words.extend(
+                        attribute_words(value)
+                            .into_iter()
+                            .map(|word| (word, TEXT_WEIGHT)),
+                    );
+                }
+                Fields {
+                    category: &hit.category_hint,
+                    manufacturer: hit.manufacturer.as_deref(),
+                    mpn: Some(&hit.mpn),
+                    words,
+                }
+            }
+        }
+    }
+}
+
+/// Best score per part for one term, with the parts it touched so it can
+/// be reset without clearing every slot.
+struct Scores {
+    best: Vec<f32>,
+    touched: Vec<u32>,
+}
+
+impl Scores {
+    fn new(count: usize) -> Self {
+        Self {
+            best: vec![0.0; count],
+            touched: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, part: usize, score: f32) {
+        if self.best[part] == 0.0 {
+            self.touched.push(part as u32);
+        }
+        self.best[part] = self.best[part].max(score);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.touched.is_empty()
+    }
+
+    fn drain(&mut self, mut f: impl FnMut(usize, f32)) {
+        for part in self.touched.drain(..) {
+            let part = part as usize;
+            f(part, self.best[part]);
+            self.best[part] = 0.0;
+        }
+    }
+}
+
+type KeyedIndex = (u64, Arc<SearchIndex>);
+
+/// The index for `key`, rebuilt by `build` only when the key changed.
+pub fn cached_index(
+    key: u64,
+    build: impl FnOnce() -> Result<SearchIndex, String>,
+) -> Result<Arc<SearchIndex>, String> {
+    static INDEX: OnceLock<Mutex<Option<KeyedIndex>>> = OnceLock::new();
+    let mut cached = INDEX
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|_| "Search index is poisoned.".to_string())?;
+    match cached.as_ref() {
+        Some((cached_key, index)) if *cached_key == key => Ok(index.clone()),
+        _ => {
+            let index = Arc::new(build()?);
+            *cached = Some((key, index.clone()));
+            Ok(index)
+        }
+    }
+}
+
+/// Bumped by library edits made in the app, which rewrite entry files in
+/// place without touching their folder.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Forces the next search to rebuild the index.
+pub fn invalidate() {
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Changes whenever a file is added to, removed from or renamed into one of
+/// `paths` (folders or single files), or the library is edited in the app.
+/// Only the paths themselves are stat-ed, so the cost does not grow with the
+/// number of parts; a file rewritten in place by another program shows up
+/// once its folder next changes.
+pub fn fingerprint(paths: &[PathBuf]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    GENERATION.load(Ordering::Relaxed).hash(&mut hasher);
+    for path in paths {
+        path.hash(&mut hasher);
+        let Ok(meta) = fs::metadata(path) else {
+            continue;
+        };
+        meta.len().hash(&mut hasher);
+        meta.modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn intern(ids: &mut HashMap<String, u32>, names: &mut Vec<String>, name: &str) -> u32 {
+    let name = name.trim();
+    *ids.entry(name.to_lowercase()).or_insert_with(|| {
+        names.push(name.to_string());
+        names.len() as u32 - 1
+    })
+}
+
+/// `Some(None)` filters on a name that matches nothing.
+fn lookup(names: &[String], name: &str) -> Option<u32> {
+    names
+        .iter()
+        .position(|known| known.eq_ignore_ascii_case(name.trim()))
+        .map(|id| id as u32)
+}
+
+fn facet(names: &[String], counts: &[usize]) -> Vec<FacetCount> {
+    let mut facets: Vec<FacetCount> = names
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| **count > 0)
+        .map(|(value, count)| FacetCount {
+            value: value.clone(),
+            count: *count,
+        })
+        .collect();
+    facets.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    facets
+}
+
+/// "3.3V" stays one word, so parameters are searchable as written.
+fn attribute_words(value: &AttributeValue) -> Vec<String> {
+    match value {
+        AttributeValue::Range { min, max, unit } => {
+            vec![format!("{}{}", min, unit), format!("{}{}", max, unit)]
+        }
+        AttributeValue::Quantity { value, unit } => vec![format!("{}{}", value, unit)],
+        AttributeValue::Text { value } => vec![value.clone()],
+        AttributeValue::List { values } => values.clone(),
+    }
+}
+
+/// Upper-case letters and digits only: "tps62130-rgtr" -> "TPS62130RGTR".
+pub fn normalize_mpn(mpn: &str) -> String {
+    mpn.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|ch| ch.to_ascii_uppercase())
+        .collect()
+}
+
+/// Lower-case words split on anything but letters, digits and dots inside
+/// numbers, so "3.3V" and "Step-Down" give `["3.3v"]` and `["step", "down"]`.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|ch: char| !(ch.is_alphanumeric() || ch == '.'))
+        .map(|word| word.trim_matches('.'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    (0..=index.min(text.len()))
+        .rev()
+        .find(|index| text.is_char_boundary(*index))
+        .unwrap_or(0)
+}
+
+/// Levenshtein distance of `a` and `b` when it is at most `max`.
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, left) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, right) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left != right);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > max {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    Some(previous[b.len()]).filter(|distance| *distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::LibraryRoot;
+
+    fn hit(mpn: &str, manufacturer: &str, category: &str, description: &str) -> TrustedPartHit {
+        TrustedPartHit {
+            mpn: mpn.to_string(),
+            manufacturer: Some(manufacturer.to_string()),
+            description: Some(description.to_string()),
+            lifecycle_status: None,
+            category_hint: category.to_string(),
+            category_confidence: 1.0,
+            attributes: Default::default(),
+            offers: Vec::new(),
+            from_cache: true,
+            cache_age_seconds: None,
+        }
+    }
+
+    fn mpns(results: &IndexResults) -> Vec<String> {
+        results
+            .hits
+            .iter()
+            .map(|hit| match &hit.part {
+                IndexedPart::Library { entry } => entry.label.clone(),
+                IndexedPart::Cached { hit } => hit.mpn.clone(),
+            })
+            .collect()
+    }
+
+    fn search(index: &SearchIndex, text: &str) -> IndexResults {
+        index.search(&IndexQuery {
+            text: text.to_string(),
+            ..IndexQuery::default()
+        })
+    }
+
+    #[test]
+    fn matches_part_numbers_loosely_and_counts_facets() {
+        let entry = LibraryEntry {
+            id: "rp2040".to_string(),
+            label: "RP2040".to_string(),
+            category: "MCU".to_string(),
+            manufacturer: Some("Raspberry Pi".to_string()),
+            mpn: None,
+            package: Some("QFN-56".to_string()),
+            tdp_w: 0.3,
+            ports: Vec::new(),
+            datasheet_url: None,
+            description: None,
+            updated_at: None,
+            root: LibraryRoot::Builtin,
+            overrides: None,
+        };
+        let index = SearchIndex::build(
+            vec![entry],
+            vec![
+                hit(
+                    "TPS62130RGTR",
+                    "Texas Instruments",
+                    "PMIC",
+                    "IC REG BUCK ADJ 3A 16VQFN",
+                ),
+                hit(
+                    "TPS62130",
+                    "Texas Instruments",
+                    "PMIC",
+                    "3-17V 3A Step-Down Converter",
+                ),
+                hit(
+                    "STM32G474RET6",
+                    "STMicroelectronics",
+                    "MCU",
+                    "IC MCU 32BIT 512KB FLASH 64LQFP",
+                ),
+                hit(
+                    "W25Q128JVSIQ",
+                    "Winbond",
+                    "Memory",
+                    "IC FLASH 128MBIT SPI 8SOIC",
+                ),
+            ],
+        );
+        assert_eq!(search(&index, "").total, 5);
+
+        assert_eq!(search(&index, "tps62130-rgtr").hits.len(), 2);
+        assert_eq!(mpns(&search(&index, "tps62130-rgtr"))[0], "TPS62130RGTR");
+        assert_eq!(
+            mpns(&search(&index, "TPS6213")),
+            ["TPS62130", "TPS62130RGTR"]
+        );
+        assert_eq!(mpns(&search(&index, "STM32G474RTE6")), ["STM32G474RET6"]);
+        assert_eq!(mpns(&search(&index, "rp2040")), ["RP2040"]);
+        assert_eq!(mpns(&search(&index, "step-down texas")), ["TPS62130"]);
+        assert_eq!(mpns(&search(&index, "winbnd 8soic")), ["W25Q128JVSIQ"]);
+        assert_eq!(mpns(&search(&index, "512kb stm")), ["STM32G474RET6"]);
+        assert!(search(&index, "tps62130 winbond").hits.is_empty());
+
+        let flash = index.search(&IndexQuery {
+            text: "flash".to_string(),
+            category: Some("memory".to_string()),
+            ..IndexQuery::default()
+        });
+        assert_eq!(flash.total, 1);
+        assert_eq!(
+            flash.facets.categories,
+            [
+                FacetCount {
+                    value: "MCU".to_string(),
+                    count: 1
+                },
+                FacetCount {
+                    value: "Memory".to_string(),
+                    count: 1
+                }
+            ]
+        );
+
+        let library_only = index.search(&IndexQuery {
+            include_cached: Some(false),
+            ..IndexQuery::default()
+        });
+        assert_eq!(library_only.total, 1);
+        let json = serde_json::to_value(&library_only.hits[0]).unwrap();
+        assert_eq!(json["source"], "library");
+        assert_eq!(json["entry"]["tdpW"], 0.3);
+    }
+
+    #[test]
+    fn fingerprint_follows_folders_and_library_edits() {
+        let dir = std::env::temp_dir().join(format!("aha-index-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let sources = [dir.clone(), dir.join("taxonomy.json")];
+
+        let before = fingerprint(&sources);
+        fs::write(dir.join("taxonomy.json"), "{}").unwrap();
+        let written = fingerprint(&sources);
+        assert_ne!(before, written);
+        invalidate();
+        assert_ne!(written, fingerprint(&sources));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    /// Run with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn searches_100k_parts_within_budget() {
+        const PARTS: usize = 100_000;
+        let dir = std::env::temp_dir().join(format!("aha-index-bench-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let families = [
+            ("TPS", "Texas Instruments", "PMIC", "IC REG BUCK ADJ 3A"),
+            (
+                "STM32G",
+                "STMicroelectronics",
+                "MCU",
+                "IC MCU 32BIT FLASH LQFP",
+            ),
+            ("W25Q", "Winbond", "Memory", "IC FLASH SPI SOIC"),
+            ("GRM", "Murata", "Capacitor", "CAP CER 100NF 0402 X7R"),
+            ("LM", "onsemi", "Analog", "IC OPAMP GP 2 CIRCUIT"),
+        ];
+        let hits: Vec<TrustedPartHit> = (0..PARTS)
+            .map(|n| {
+                let (prefix, manufacturer, category, description) = families[n % families.len()];
+                let mpn = format!("{}{}R{}", prefix, n, n % 7);
+                fs::write(dir.join(format!("{}.json", mpn)), "{}").unwrap();
+                hit(
+                    &mpn,
+                    manufacturer,
+                    category,
+                    &format!("{} {}", description, n % 97),
+                )
+            })
+            .collect();
+        let sources = [dir.clone()];
+        let index = SearchIndex::build(Vec::new(), hits);
+        cached_index(fingerprint(&sources), || Ok(index)).unwrap();
+
+        for text in [
+            "TPS62130",
+            "stm32g 1234",
+            "winbnd flash",
+            "100nf 0402",
+            "lm358",
+            "",
+        ] {
+            let started = Instant::now();
+            let index = cached_index(fingerprint(&sources), || Err("rebuilt".to_string())).unwrap();
+            let results = search(&index, text);
+            let elapsed = started.elapsed();
+            assert!(
+                elapsed.as_millis() < 20,
+                "'{}' took {:?} for {} hits",
+                text,
+                elapsed,
+                results.total
+            );
+        }
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
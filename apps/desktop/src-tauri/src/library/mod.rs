@@ -0,0 +1,576 @@
+//! Offline component library. Every part is a JSON file named after its id
+//! in one of several roots, read in precedence order: the parts bundled in
+//! `builtin.json`, the user's personal library, a shared team folder and the
+//! open project's `library` folder. A part in a later root replaces the one
+//! with the same id in an earlier root, so editing a bundled part saves a
+//! personal copy of it.
+
+mod index;
+
+pub use index::{IndexQuery, IndexResults, SearchIndex};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
+use tauri::AppHandle;
+
+use crate::parts::{self, Taxonomy};
+use crate::sourcing;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LibraryRoot {
+    Builtin,
+    #[default]
+    Personal,
+    Team,
+    Project,
+}
+
+impl LibraryRoot {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Builtin => "built-in",
+            Self::Personal => "personal",
+            Self::Team => "team",
+            Self::Project => "project",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryPort {
+    pub name: String,
+    /// Rail or interface type, e.g. `power`, `i2c`, `pcie`.
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryEntry {
+    /// File name without `.json`. New entries get one derived from the label.
+    #[serde(default)]
+    pub id: String,
+    pub label: String,
+    pub category: String,
+    #[serde(default)]
+    pub manufacturer: Option<String>,
+    #[serde(default)]
+    pub mpn: Option<String>,
+    #[serde(default)]
+    pub package: Option<String>,
+    #[serde(default)]
+    pub tdp_w: f64,
+    #[serde(default)]
+    pub ports: Vec<LibraryPort>,
+    #[serde(default)]
+    pub datasheet_url: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<u64>,
+    /// Root the entry was read from.
+    #[serde(default, skip_deserializing)]
+    pub root: LibraryRoot,
+    /// Root of the entry with the same id that this one replaces.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<LibraryRoot>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryRootInfo {
+    pub root: LibraryRoot,
+    /// `None` for the bundled parts.
+    pub path: Option<String>,
+    pub entries: usize,
+}
+
+/// A part file that couldn't be read; the rest of the library still loads.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryProblem {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryListing {
+    pub roots: Vec<LibraryRootInfo>,
+    /// Merged entries, sorted by category and label.
+    pub entries: Vec<LibraryEntry>,
+    pub problems: Vec<LibraryProblem>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibrarySettings {
+    #[serde(default)]
+    team_root: Option<String>,
+}
+
+/// The writable roots that exist for this session; the bundled parts are
+/// always present.
+#[derive(Debug, Clone)]
+pub struct Library {
+    dirs: Vec<(LibraryRoot, PathBuf)>,
+}
+
+impl Library {
+    pub fn new(personal: PathBuf, team: Option<PathBuf>, project: Option<PathBuf>) -> Self {
+        let mut dirs = vec![(LibraryRoot::Personal, personal)];
+        dirs.extend(team.map(|dir| (LibraryRoot::Team, dir)));
+        dirs.extend(project.map(|dir| (LibraryRoot::Project, dir)));
+        Self { dirs }
+    }
+
+    pub fn list(&self) -> LibraryListing {
+        let builtin = builtin_entries();
+        let mut roots = vec![LibraryRootInfo {
+            root: LibraryRoot::Builtin,
+            path: None,
+            entries: builtin.len(),
+        }];
+        let mut problems = Vec::new();
+        let mut merged: BTreeMap<String, LibraryEntry> = builtin
+            .iter()
+            .map(|entry| (entry.id.clone(), entry.clone()))
+            .collect();
+
+        for (root, dir) in &self.dirs {
+            let entries = read_dir(*root, dir, &mut problems);
+            roots.push(LibraryRootInfo {
+                root: *root,
+                path: Some(dir.to_string_lossy().to_string()),
+                entries: entries.len(),
+            });
+            for mut entry in entries {
+                entry.overrides = merged.get(&entry.id).map(|previous| previous.root);
+                merged.insert(entry.id.clone(), entry);
+            }
+        }
+
+        let mut entries: Vec<LibraryEntry> = merged.into_values().collect();
+        entries.sort_by(|a, b| {
+            a.category
+                .to_lowercase()
+                .cmp(&b.category.to_lowercase())
+                .then_with(|| a.label.to_lowercase().cmp(&b.label.to_lowercase()))
+        });
+        LibraryListing {
+            roots,
+            entries,
+            problems,
+        }
+    }
+
+    /// Writes `entry` to `root`, replacing the entry with the same id there.
+    /// An empty id is derived from the label and made unique.
+    pub fn save(&self, entry: LibraryEntry, root: LibraryRoot) -> Result<LibraryEntry, String> {
+        let dir = self.dir(root)?;
+        let mut entry = entry.validated()?;
+        entry.id = if entry.id.trim().is_empty() {
+            let taken: Vec<String> = self.list().entries.into_iter().map(|e| e.id).collect();
+            unique_id(&slug(&entry.label), &taken)
+        } else {
+            entry_id(&entry.id)?
+        };
+        entry.updated_at = Some(crate::unix_now());
+        entry.root = root;
+        entry.overrides = None;
+
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {} library: {}", root.name(), e))?;
+        let contents = serde_json::to_string_pretty(&entry)
+            .map_err(|e| format!("Failed to serialize library entry: {}", e))?;
+        fs::write(dir.join(format!("{}.json", entry.id)), contents)
+            .map_err(|e| format!("Failed to write library entry: {}", e))?;
+        index::invalidate();
+        Ok(entry)
+    }
+
+    pub fn delete(&self, id: &str, root: LibraryRoot) -> Result<(), String> {
+        let dir = self.dir(root)?;
+        let path = dir.join(format!("{}.json", entry_id(id)?));
+        if !path.exists() {
+            return Err(format!("No {} library entry named '{}'.", root.name(), id));
+        }
+        fs::remove_file(path).map_err(|e| format!("Failed to delete library entry: {}", e))?;
+        index::invalidate();
+        Ok(())
+    }
+
+    fn dir(&self, root: LibraryRoot) -> Result<&Path, String> {
+        if root == LibraryRoot::Builtin {
+            return Err(
+                "Built-in library entries are read-only; save a personal copy instead.".to_string(),
+            );
+        }
+        self.dirs
+            .iter()
+            .find(|(candidate, _)| *candidate == root)
+            .map(|(_, dir)| dir.as_path())
+            .ok_or_else(|| match root {
+                LibraryRoot::Team => "No team library folder is configured.".to_string(),
+                _ => "Save the workspace first to use its project library.".to_string(),
+            })
+    }
+}
+
+impl LibraryEntry {
+    fn validated(mut self) -> Result<Self, String> {
+        self.label = self.label.trim().to_string();
+        self.category = self.category.trim().to_string();
+        if self.label.is_empty() || self.category.is_empty() {
+            return Err("Library entries need a label and a category.".to_string());
+        }
+        if !self.tdp_w.is_finite() || self.tdp_w < 0.0 {
+            return Err(format!("TDP of {} must be zero or more watts.", self.label));
+        }
+        for field in [
+            &mut self.manufacturer,
+            &mut self.mpn,
+            &mut self.package,
+            &mut self.datasheet_url,
+            &mut self.description,
+        ] {
+            *field = field
+                .take()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty());
+        }
+
+        let mut names: Vec<String> = Vec::new();
+        for port in &mut self.ports {
+            port.name = port.name.trim().to_string();
+            port.kind = port.kind.trim().to_lowercase();
+            if port.name.is_empty() {
+                return Err(format!("Ports of {} need a name.", self.label));
+            }
+            if names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&port.name))
+            {
+                return Err(format!("{} has two ports named {}.", self.label, port.name));
+            }
+            names.push(port.name.clone());
+        }
+        Ok(self)
+    }
+}
+
+/// Every part in the library, in precedence order.
+#[tauri::command]
+pub fn list_library(
+    app: AppHandle,
+    workspace_path: Option<String>,
+) -> Result<LibraryListing, String> {
+    Ok(library(&app, workspace_path.as_deref())?.list())
+}
+
+/// Ranked search over the library and, unless `includeCached` is off, every
+/// part in the distributor search cache. The index is rebuilt only when one
+/// of their folders changes or the library is edited; `elapsed_micros`
+/// covers that check as well as the search.
+#[tauri::command]
+pub fn search_library(
+    app: AppHandle,
+    query: IndexQuery,
+    workspace_path: Option<String>,
+) -> Result<IndexResults, String> {
+    let started = Instant::now();
+    let library = library(&app, workspace_path.as_deref())?;
+    let cache = sourcing::part_search_cache(&app)?;
+    let taxonomy_path = sourcing::taxonomy_path(&app)?;
+
+    let mut sources: Vec<PathBuf> = library.dirs.iter().map(|(_, dir)| dir.clone()).collect();
+    sources.push(cache.dir().to_path_buf());
+    sources.push(taxonomy_path.clone());
+    let index = index::cached_index(index::fingerprint(&sources), || {
+        let mut hits = cache.cached_hits();
+        let extensions = Taxonomy::load_extensions(&taxonomy_path)?;
+        if !extensions.categories.is_empty() {
+            parts::classify_hits(&mut hits, &Taxonomy::builtin().extended(&extensions));
+        }
+        Ok(SearchIndex::build(library.list().entries, hits))
+    })?;
+    let mut results = index.search(&query);
+    results.elapsed_micros = started.elapsed().as_micros() as u64;
+    Ok(results)
+}
+
+/// Adds or replaces an entry in `root`, the personal library by default.
+#[tauri::command]
+pub fn save_library_entry(
+    app: AppHandle,
+    entry: LibraryEntry,
+    root: Option<LibraryRoot>,
+    workspace_path: Option<String>,
+) -> Result<LibraryListing, String> {
+    let library = library(&app, workspace_path.as_deref())?;
+    library.save(entry, root.unwrap_or_default())?;
+    Ok(library.list())
+}
+
+#[tauri::command]
+pub fn delete_library_entry(
+    app: AppHandle,
+    id: String,
+    root: LibraryRoot,
+    workspace_path: Option<String>,
+) -> Result<LibraryListing, String> {
+    let library = library(&app, workspace_path.as_deref())?;
+    library.delete(&id, root)?;
+    Ok(library.list())
+}
+
+/// Points the team library at a shared folder, or detaches it.
+#[tauri::command]
+pub fn set_team_library_root(
+    app: AppHandle,
+    path: Option<String>,
+    workspace_path: Option<String>,
+) -> Result<LibraryListing, String> {
+    let team_root = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    if let Some(path) = &team_root {
+        if !Path::new(path).is_dir() {
+            return Err(format!("Team library folder {} does not exist.", path));
+        }
+    }
+
+    let settings_path = settings_path(&app)?;
+    if let Some(dir) = settings_path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create library directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(&LibrarySettings { team_root })
+        .map_err(|e| format!("Failed to serialize library settings: {}", e))?;
+    fs::write(&settings_path, contents)
+        .map_err(|e| format!("Failed to write library settings: {}", e))?;
+    Ok(library(&app, workspace_path.as_deref())?.list())
+}
+
+/// The library for `workspace_path`, whose folder holds the project root.
+pub fn library(app: &AppHandle, workspace_path: Option<&str>) -> Result<Library, String> {
+    let base = crate::app_data_subdir(app, "library")?;
+    let settings = load_settings(&settings_path(app)?)?;
+    let project = workspace_path
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .and_then(|path| Path::new(path).parent())
+        .map(|dir| dir.join("library"));
+    Ok(Library::new(
+        base.join("personal"),
+        settings.team_root.map(PathBuf::from),
+        project,
+    ))
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_subdir(app, "library")?.join("settings.json"))
+}
+
+fn load_settings(path: &Path) -> Result<LibrarySettings, String> {
+    if !path.exists() {
+        return Ok(LibrarySettings::default());
+    }
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read library settings: {}", e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse library settings: {}", e))
+}
+
+fn builtin_entries() -> &'static [LibraryEntry] {
+    static BUILTIN: OnceLock<Vec<LibraryEntry>> = OnceLock::new();
+    BUILTIN.get_or_init(|| {
+        let mut entries: Vec<LibraryEntry> =
+            serde_json::from_str(include_str!("builtin.json")).expect("built-in library");
+        for entry in &mut entries {
+            entry.root = LibraryRoot::Builtin;
+        }
+        entries
+    })
+}
+
+/// Part files of one root; a missing folder is an empty library.
+fn read_dir(
+    root: LibraryRoot,
+    dir: &Path,
+    problems: &mut Vec<LibraryProblem>,
+) -> Vec<LibraryEntry> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            // An id the app can't save or delete under would leave the entry
+            // stuck, so such files are reported rather than loaded.
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            let entry = entry_id(&stem)
+                .map_err(|e| format!("{} Rename the file to '{}.json'.", e, slug(&stem)))
+                .and_then(|id| {
+                    let text = fs::read_to_string(&path)
+                        .map_err(|e| format!("Failed to read library entry: {}", e))?;
+                    let mut entry = serde_json::from_str::<LibraryEntry>(&text)
+                        .map_err(|e| format!("Invalid library entry: {}", e))?
+                        .validated()?;
+                    entry.id = id;
+                    entry.root = root;
+                    Ok(entry)
+                });
+            match entry {
+                Ok(entry) => Some(entry),
+                Err(message) => {
+                    problems.push(LibraryProblem {
+                        path: path.to_string_lossy().to_string(),
+                        message,
+                    });
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Ids double as file names, so only slug characters are allowed.
+fn entry_id(id: &str) -> Result<String, String> {
+    let id = id.trim();
+    let valid = !id.is_empty()
+        && id.len() <= 96
+        && !id.starts_with('-')
+        && id
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-');
+    if valid {
+        Ok(id.to_string())
+    } else {
+        Err(format!(
+            "Library entry id '{}' may only use lower-case letters, digits and dashes.",
+            id
+        ))
+    }
+}
+
+/// "Jetson Orin NX (16GB)" -> "jetson-orin-nx-16gb".
+fn slug(label: &str) -> String {
+    let mut slug = String::new();
+    for ch in label.chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.truncate(80);
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "part".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn unique_id(base: &str, taken: &[String]) -> String {
+    (1..)
+        .map(|n| match n {
+            1 => base.to_string(),
+            _ => format!("{}-{}", base, n),
+        })
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(listing: &'a LibraryListing, id: &str) -> &'a LibraryEntry {
+        listing.entries.iter().find(|entry| entry.id == id).unwrap()
+    }
+
+    #[test]
+    fn later_roots_override_bundled_parts() {
+        let dir = std::env::temp_dir().join(format!("aha-library-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let library = Library::new(dir.join("personal"), Some(dir.join("team")), None);
+
+        let listing = library.list();
+        let bundled = builtin_entries().len();
+        assert_eq!(listing.entries.len(), bundled);
+        let rp2040 = find(&listing, "rp2040").clone();
+        assert_eq!(rp2040.root, LibraryRoot::Builtin);
+        assert!(rp2040.ports.iter().any(|port| port.kind == "usb"));
+
+        library
+            .save(
+                LibraryEntry {
+                    tdp_w: 0.25,
+                    ..rp2040.clone()
+                },
+                LibraryRoot::Personal,
+            )
+            .unwrap();
+        let copy = library
+            .save(
+                LibraryEntry {
+                    id: String::new(),
+                    manufacturer: Some("  ".to_string()),
+                    ..rp2040.clone()
+                },
+                LibraryRoot::Team,
+            )
+            .unwrap();
+        assert_eq!(copy.id, "rp2040-2");
+        assert_eq!(copy.manufacturer, None);
+
+        let listing = library.list();
+        assert_eq!(listing.entries.len(), bundled + 1);
+        let edited = find(&listing, "rp2040");
+        assert_eq!(
+            (edited.root, edited.overrides, edited.tdp_w),
+            (LibraryRoot::Personal, Some(LibraryRoot::Builtin), 0.25)
+        );
+
+        assert!(library.save(rp2040.clone(), LibraryRoot::Builtin).is_err());
+        assert!(library.save(rp2040.clone(), LibraryRoot::Project).is_err());
+        assert!(library
+            .save(
+                LibraryEntry {
+                    id: "../escape".to_string(),
+                    ..rp2040.clone()
+                },
+                LibraryRoot::Personal
+            )
+            .is_err());
+
+        fs::write(dir.join("personal").join("broken.json"), "{").unwrap();
+        let contents = serde_json::to_string(&rp2040).unwrap();
+        fs::write(dir.join("team").join("TPS62130 Buck.json"), contents).unwrap();
+        library.delete("rp2040", LibraryRoot::Personal).unwrap();
+        let listing = library.list();
+        assert_eq!(find(&listing, "rp2040").tdp_w, 0.3);
+        assert_eq!(listing.problems.len(), 2);
+        assert!(listing.problems[1]
+            .message
+            .contains("Rename the file to 'tps62130-buck.json'"));
+        assert!(library.delete("rp2040", LibraryRoot::Personal).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,351 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::findings::{self, Finding, Severity};
+use crate::parts::TrustedPartHit;
+
+/// Node data keys the result is stored under, next to `mpn`.
+const STATUS_KEY: &str = "lifecycle_status";
+const CHECKED_AT_KEY: &str = "lifecycle_checked_at";
+
+/// Manufacturer lifecycle stage, ordered from safest to most at risk.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LifecycleStatus {
+    Unknown,
+    Active,
+    #[serde(rename = "NRND")]
+    Nrnd,
+    LastTimeBuy,
+    Obsolete,
+}
+
+impl LifecycleStatus {
+    /// Maps the free-text status of any distributor API, e.g. "Not
+    /// Recommended for New Designs" or DigiKey's "Last Time Buy".
+    pub fn normalize(raw: &str) -> Self {
+        let text = raw.trim().to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| text.contains(needle));
+        if text.is_empty() {
+            Self::Unknown
+        } else if has(&[
+            "not recommended",
+            "not for new design",
+            "nrnd",
+            "discontinued at",
+        ]) {
+            Self::Nrnd
+        } else if has(&["obsolete", "discontinued", "inactive", "no longer"]) {
+            Self::Obsolete
+        } else if has(&[
+            "last time buy",
+            "last-time buy",
+            "ltb",
+            "end of life",
+            "eol",
+        ]) {
+            Self::LastTimeBuy
+        } else if has(&[
+            "active",
+            "production",
+            "new product",
+            "preliminary",
+            "preview",
+        ]) {
+            Self::Active
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn is_at_risk(self) -> bool {
+        self >= Self::Nrnd
+    }
+
+    fn stored(value: Option<&Value>) -> Option<Self> {
+        serde_json::from_value(value?.clone()).ok()
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PartLifecycle {
+    pub node_id: String,
+    pub label: Option<String>,
+    pub mpn: String,
+    pub status: LifecycleStatus,
+    /// What the provider reported before normalization.
+    pub raw_status: Option<String>,
+    /// Status stored by the previous check, if any.
+    pub previous_status: Option<LifecycleStatus>,
+    /// Set when the lookup failed; the stored status is then left as is.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleReport {
+    pub status: &'static str,
+    /// Unix seconds, also stored on every checked node.
+    pub checked_at: u64,
+    pub parts: Vec<PartLifecycle>,
+    /// Parts whose status got worse since the last check and is now at risk.
+    pub newly_at_risk: Vec<PartLifecycle>,
+    pub findings: Vec<Finding>,
+    /// The workspace with the new statuses, for the frontend to load and save.
+    pub workspace_json: String,
+}
+
+/// Distinct MPNs of the workspace nodes, in node order.
+pub fn workspace_mpns(workspace: &Value) -> Vec<String> {
+    let mut mpns: Vec<String> = Vec::new();
+    let nodes = workspace.get("nodes").and_then(Value::as_array);
+    for data in nodes
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node.get("data")?.as_object())
+    {
+        let Some(mpn) = mpn_of(data) else {
+            continue;
+        };
+        if !mpns.iter().any(|known| known.eq_ignore_ascii_case(&mpn)) {
+            mpns.push(mpn);
+        }
+    }
+    mpns
+}
+
+/// Stores each node's status from `lookups` (keyed by upper-case MPN) in the
+/// workspace and compares it with the previous check.
+pub fn record(
+    workspace: &mut Value,
+    lookups: &HashMap<String, Result<Vec<TrustedPartHit>, String>>,
+    checked_at: u64,
+) -> Result<LifecycleReport, String> {
+    let mut parts = Vec::new();
+    let nodes = workspace
+        .get_mut("nodes")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| "Workspace has no nodes array.".to_string())?;
+
+    for node in nodes {
+        let node_id = node
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let Some(data) = node.get_mut("data").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        let Some(mpn) = mpn_of(data) else {
+            continue;
+        };
+        let label = data
+            .get("label")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let previous_status = LifecycleStatus::stored(data.get(STATUS_KEY));
+
+        let mut part = PartLifecycle {
+            node_id,
+            label,
+            mpn: mpn.clone(),
+            status: previous_status.unwrap_or(LifecycleStatus::Unknown),
+            raw_status: None,
+            previous_status,
+            error: None,
+        };
+        match lookups.get(&mpn.to_uppercase()) {
+            Some(Ok(hits)) => {
+                let manufacturer = data.get("manufacturer").and_then(Value::as_str);
+                part.raw_status =
+                    pick_hit(hits, manufacturer).and_then(|hit| hit.lifecycle_status.clone());
+                // No hit or no status says nothing about the part, so the
+                // last known status stands.
+                if let Some(raw_status) = part.raw_status.as_deref() {
+                    part.status = LifecycleStatus::normalize(raw_status);
+                    data.insert(STATUS_KEY.to_string(), json!(part.status));
+                    data.insert(CHECKED_AT_KEY.to_string(), json!(checked_at));
+                }
+            }
+            Some(Err(error)) => part.error = Some(error.clone()),
+            None => part.error = Some("Part was not looked up.".to_string()),
+        }
+        parts.push(part);
+    }
+
+    let newly_at_risk: Vec<PartLifecycle> = parts
+        .iter()
+        .filter(|part| {
+            part.error.is_none()
+                && part.status.is_at_risk()
+                && part
+                    .previous_status
+                    .is_none_or(|previous| part.status > previous)
+        })
+        .cloned()
+        .collect();
+    let findings = lifecycle_findings(&parts);
+
+    Ok(LifecycleReport {
+        status: findings::overall_status(&findings),
+        checked_at,
+        newly_at_risk,
+        findings,
+        workspace_json: serde_json::to_string_pretty(workspace)
+            .map_err(|e| format!("Failed to serialize workspace: {}", e))?,
+        parts,
+    })
+}
+
+fn mpn_of(data: &serde_json::Map<String, Value>) -> Option<String> {
+    data.get("mpn")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|mpn| !mpn.is_empty())
+        .map(str::to_string)
+}
+
+/// The hit from the node's manufacturer when several makers share an MPN.
+fn pick_hit<'a>(
+    hits: &'a [TrustedPartHit],
+    manufacturer: Option<&str>,
+) -> Option<&'a TrustedPartHit> {
+    let manufacturer = manufacturer.map(str::to_lowercase).unwrap_or_default();
+    hits.iter()
+        .find(|hit| {
+            !manufacturer.is_empty()
+                && hit
+                    .manufacturer
+                    .as_deref()
+                    .is_some_and(|maker| maker.to_lowercase().contains(&manufacturer))
+        })
+        .or_else(|| hits.iter().find(|hit| hit.lifecycle_status.is_some()))
+        .or_else(|| hits.first())
+}
+
+fn lifecycle_findings(parts: &[PartLifecycle]) -> Vec<Finding> {
+    parts
+        .iter()
+        .filter_map(|part| {
+            let name = part.label.as_deref().unwrap_or(&part.node_id);
+            let (code, severity, message) = match (part.status, &part.error) {
+                (_, Some(error)) => (
+                    "lifecycle-check-failed",
+                    Severity::Warning,
+                    format!(
+                        "Lifecycle of {} ({}) could not be checked: {}",
+                        name, part.mpn, error
+                    ),
+                ),
+                (LifecycleStatus::Obsolete, _) => (
+                    "part-obsolete",
+                    Severity::Error,
+                    format!("{} ({}) is obsolete.", name, part.mpn),
+                ),
+                (LifecycleStatus::LastTimeBuy, _) => (
+                    "part-last-time-buy",
+                    Severity::Warning,
+                    format!("{} ({}) is in its last-time-buy window.", name, part.mpn),
+                ),
+                (LifecycleStatus::Nrnd, _) => (
+                    "part-nrnd",
+                    Severity::Warning,
+                    format!(
+                        "{} ({}) is not recommended for new designs.",
+                        name, part.mpn
+                    ),
+                ),
+                _ => return None,
+            };
+            Some(Finding::new(code, severity, message).with_nodes(vec![part.node_id.clone()]))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(mpn: &str, status: &str) -> TrustedPartHit {
+        serde_json::from_value(json!({
+            "mpn": mpn,
+            "manufacturer": "Texas Instruments",
+            "description": null,
+            "lifecycleStatus": status,
+            "categoryHint": "Component",
+            "offers": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn stores_statuses_and_reports_new_risks() {
+        assert_eq!(
+            LifecycleStatus::normalize("Not Recommended for New Designs"),
+            LifecycleStatus::Nrnd
+        );
+        assert_eq!(
+            LifecycleStatus::normalize("End of Life"),
+            LifecycleStatus::LastTimeBuy
+        );
+        assert_eq!(
+            LifecycleStatus::normalize("Production"),
+            LifecycleStatus::Active
+        );
+
+        let mut workspace = json!({
+            "version": 1,
+            "nodes": [
+                {"id": "u1", "position": {"x": 0, "y": 0}, "data": {"label": "Buck", "mpn": "TPS62130RGTR", "lifecycle_status": "Active"}},
+                {"id": "u2", "data": {"label": "LDO", "mpn": "LM1117", "lifecycle_status": "Obsolete"}},
+                {"id": "u3", "data": {"label": "MCU", "mpn": "stm32f405rgt6"}},
+                {"id": "u4", "data": {"label": "Blank"}},
+                {"id": "u5", "data": {"label": "Op-amp", "mpn": "LM358", "lifecycle_status": "Obsolete"}}
+            ],
+            "edges": []
+        });
+        assert_eq!(
+            workspace_mpns(&workspace),
+            ["TPS62130RGTR", "LM1117", "stm32f405rgt6", "LM358"]
+        );
+
+        let lookups = HashMap::from([
+            (
+                "TPS62130RGTR".to_string(),
+                Ok(vec![hit("TPS62130RGTR", "NRND")]),
+            ),
+            ("LM1117".to_string(), Ok(vec![hit("LM1117", "Obsolete")])),
+            ("STM32F405RGT6".to_string(), Err("offline".to_string())),
+            // Out of stock everywhere, so no distributor lists it.
+            ("LM358".to_string(), Ok(Vec::new())),
+        ]);
+        let report = record(&mut workspace, &lookups, 1_700_000_000).unwrap();
+
+        assert_eq!(report.status, "error");
+        let statuses: Vec<_> = report.parts.iter().map(|part| part.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                LifecycleStatus::Nrnd,
+                LifecycleStatus::Obsolete,
+                LifecycleStatus::Unknown,
+                LifecycleStatus::Obsolete
+            ]
+        );
+        // LM1117 was already known to be obsolete.
+        assert_eq!(report.newly_at_risk.len(), 1);
+        assert_eq!(report.newly_at_risk[0].node_id, "u1");
+        assert!(report.parts[2].error.is_some());
+
+        let saved: Value = serde_json::from_str(&report.workspace_json).unwrap();
+        assert_eq!(saved["nodes"][0]["data"]["lifecycle_status"], "NRND");
+        assert_eq!(
+            saved["nodes"][0]["data"]["lifecycle_checked_at"],
+            1_700_000_000
+        );
+        assert_eq!(saved["nodes"][0]["position"]["x"], 0);
+        assert!(saved["nodes"][2]["data"].get("lifecycle_status").is_none());
+        assert_eq!(saved["nodes"][4]["data"]["lifecycle_status"], "Obsolete");
+    }
+}
@@ -0,0 +1,148 @@
+//! OpenAI-compatible LLM requests made on the frontend's behalf, so the API
+//! key stays in the credential vault. The credential also stores the base
+//! URL the key was entered for, and the key is only ever sent there.
+
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use crate::credentials;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Ids of the models the endpoint serves (OpenAI `data` or Ollama `models`).
+#[tauri::command]
+pub async fn list_llm_models(
+    app: AppHandle,
+    base_url: String,
+    credential: Option<String>,
+) -> Result<Vec<String>, String> {
+    let (base_url, api_key) = authorize(&app, &base_url, credential.as_deref())?;
+    let request = client()?.get(format!("{}/models", base_url));
+    let response = send(request, api_key.as_deref()).await?;
+
+    let listed = |key: &str, id: &str| -> Option<Vec<String>> {
+        let models = response.get(key)?.as_array()?;
+        Some(
+            models
+                .iter()
+                .filter_map(|model| model.get(id)?.as_str().map(str::to_string))
+                .collect(),
+        )
+    };
+    Ok(listed("data", "id")
+        .or_else(|| listed("models", "name"))
+        .unwrap_or_default())
+}
+
+/// POSTs `request` to `/chat/completions` and returns the response body.
+#[tauri::command]
+pub async fn llm_chat_completion(
+    app: AppHandle,
+    base_url: String,
+    credential: Option<String>,
+    request: Value,
+) -> Result<Value, String> {
+    let (base_url, api_key) = authorize(&app, &base_url, credential.as_deref())?;
+    let request = client()?
+        .post(format!("{}/chat/completions", base_url))
+        .json(&request);
+    send(request, api_key.as_deref()).await
+}
+
+/// The normalized base URL and, with a credential, its API key.
+fn authorize(
+    app: &AppHandle,
+    base_url: &str,
+    credential: Option<&str>,
+) -> Result<(String, Option<String>), String> {
+    let base_url = normalize_base_url(base_url)?;
+    let api_key = match credential {
+        Some(name) => Some(stored_key(
+            &credentials::credential_fields(app, name)?,
+            &base_url,
+        )?),
+        None => None,
+    };
+    Ok((base_url, api_key))
+}
+
+/// The stored `apiKey`, if it was saved for `base_url`.
+fn stored_key(fields: &BTreeMap<String, String>, base_url: &str) -> Result<String, String> {
+    let saved_for = fields
+        .get("baseUrl")
+        .map(|url| normalize_base_url(url))
+        .transpose()?;
+    if saved_for.as_deref() != Some(base_url) {
+        return Err(format!(
+            "The saved API key is not for {}. Enter the key for this endpoint.",
+            base_url
+        ));
+    }
+    fields
+        .get("apiKey")
+        .cloned()
+        .ok_or_else(|| "The saved LLM credential has no API key.".to_string())
+}
+
+fn normalize_base_url(base_url: &str) -> Result<String, String> {
+    let base_url = base_url.trim().trim_end_matches('/');
+    if !base_url.starts_with("https://") && !base_url.starts_with("http://") {
+        return Err(format!("LLM base URL must be an http(s) URL: {}", base_url));
+    }
+    Ok(base_url.to_string())
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to initialize HTTP client: {}", e))
+}
+
+async fn send(request: reqwest::RequestBuilder, api_key: Option<&str>) -> Result<Value, String> {
+    let request = match api_key {
+        Some(key) => request.bearer_auth(key),
+        None => request,
+    };
+    let response = request
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("LLM request failed: {}", e.without_url()))?;
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    if !status.is_success() {
+        let message = body
+            .pointer("/error/message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("API request failed with status {}", status.as_u16()));
+        return Err(message);
+    }
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_key_is_only_released_for_its_base_url() {
+        let fields = BTreeMap::from([
+            ("apiKey".to_string(), "sk-test".to_string()),
+            (
+                "baseUrl".to_string(),
+                "https://api.openai.com/v1/".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            stored_key(&fields, "https://api.openai.com/v1").unwrap(),
+            "sk-test"
+        );
+        assert!(stored_key(&fields, "https://collector.example.com/v1").is_err());
+        assert!(stored_key(&BTreeMap::new(), "https://api.openai.com/v1").is_err());
+        assert!(normalize_base_url("file:///etc").is_err());
+    }
+}
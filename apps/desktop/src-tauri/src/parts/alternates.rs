@@ -0,0 +1,372 @@
+//! Alternate part suggestions: candidates from the reference part's category
+//! are compared on the parameters extracted from their descriptions, then
+//! ranked by how close they are, whether they can be bought and what they
+//! cost.
+
+use serde::Serialize;
+
+use super::attributes::{
+    AttributeValue, DATA_WIDTH, FLASH, INTERFACES, MEMORY_DENSITY, OPERATING_TEMPERATURE, PACKAGE,
+    PIN_COUNT, RAM, SUPPLY_VOLTAGE,
+};
+use super::category::FALLBACK_CATEGORY;
+use super::{Taxonomy, TrustedPartHit};
+use crate::lifecycle::LifecycleStatus;
+
+/// Compared attributes and their weight in the similarity score. Footprint
+/// and supply decide whether a part drops in; the rest is nice to have.
+const COMPARED: &[(&str, f64)] = &[
+    (PACKAGE, 3.0),
+    (PIN_COUNT, 3.0),
+    (SUPPLY_VOLTAGE, 2.0),
+    (INTERFACES, 2.0),
+    (DATA_WIDTH, 1.0),
+    (FLASH, 1.0),
+    (RAM, 1.0),
+    (MEMORY_DENSITY, 1.0),
+    (OPERATING_TEMPERATURE, 1.0),
+];
+
+const SIMILARITY_WEIGHT: f64 = 0.6;
+const AVAILABILITY_WEIGHT: f64 = 0.25;
+const PRICE_WEIGHT: f64 = 0.15;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlternatesReport {
+    /// Keywords the candidates were searched with.
+    pub search_token: String,
+    pub quantity: u64,
+    pub reference: AlternateRow,
+    /// Best match first.
+    pub alternates: Vec<AlternateRow>,
+}
+
+/// One column of the comparison table.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlternateRow {
+    pub mpn: String,
+    pub manufacturer: Option<String>,
+    pub category: String,
+    pub lifecycle_status: LifecycleStatus,
+    /// Same package and pin count as the reference.
+    pub pin_compatible: bool,
+    /// Parameter match with the reference, 0.0–1.0.
+    pub similarity: f64,
+    /// Overall rank score, 0.0–1.0.
+    pub score: f64,
+    pub total_stock: u64,
+    /// Cheapest offer for `quantity` pieces, by extended price.
+    pub best_distributor: Option<String>,
+    pub unit_price: Option<f64>,
+    pub extended_price: Option<f64>,
+    pub currency: Option<String>,
+    pub comparison: Vec<AttributeComparison>,
+    /// The full hit, for swapping it into a node.
+    pub hit: TrustedPartHit,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeComparison {
+    pub attribute: &'static str,
+    pub reference: Option<AttributeValue>,
+    pub candidate: Option<AttributeValue>,
+    /// 1.0 for a match; `None` when either side doesn't state the value.
+    pub score: Option<f64>,
+}
+
+/// Keywords for finding parts like `reference`: its strongest category
+/// keywords, package family and interfaces.
+pub fn alternates_search_token(reference: &TrustedPartHit, taxonomy: &Taxonomy) -> String {
+    let description = reference.description.as_deref().unwrap_or_default();
+    let mut words = taxonomy.keywords_in(&reference.category_hint, description);
+    words.truncate(2);
+    if words.is_empty() && reference.category_hint != FALLBACK_CATEGORY {
+        words.push(reference.category_hint.clone());
+    }
+    if let Some(AttributeValue::Text { value }) = reference.attributes.get(PACKAGE) {
+        words.push(value.clone());
+    }
+    if let Some(AttributeValue::List { values }) = reference.attributes.get(INTERFACES) {
+        words.extend(values.iter().take(2).cloned());
+    }
+    // "rs 485" from the taxonomy and the "RS-485" interface are one word.
+    let mut seen = Vec::new();
+    words.retain(|word| {
+        let key: String = word
+            .chars()
+            .filter(|ch| ch.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        let fresh = !seen.contains(&key);
+        seen.push(key);
+        fresh
+    });
+    words.join(" ")
+}
+
+/// Ranks `candidates` as replacements for `reference` at a build quantity.
+/// The reference itself and parts of other categories are left out.
+pub fn rank_alternates(
+    reference: &TrustedPartHit,
+    candidates: Vec<TrustedPartHit>,
+    quantity: u64,
+    search_token: String,
+) -> AlternatesReport {
+    let quantity = quantity.max(1);
+    let same_category = |hit: &TrustedPartHit| {
+        reference.category_hint == FALLBACK_CATEGORY
+            || hit
+                .category_hint
+                .eq_ignore_ascii_case(&reference.category_hint)
+    };
+    let mut alternates: Vec<AlternateRow> = candidates
+        .into_iter()
+        .filter(|hit| !hit.mpn.eq_ignore_ascii_case(&reference.mpn) && same_category(hit))
+        .map(|hit| row(reference, hit, quantity))
+        .collect();
+
+    // Prices only compare within one currency.
+    let prices: Vec<(f64, Option<String>)> = alternates
+        .iter()
+        .filter_map(|row| Some((row.extended_price?, row.currency.clone())))
+        .collect();
+    for row in &mut alternates {
+        let price = row.extended_price.map_or(0.0, |price| {
+            let low = prices
+                .iter()
+                .filter(|(_, currency)| *currency == row.currency)
+                .map(|(low, _)| *low)
+                .fold(price, f64::min);
+            if price > 0.0 {
+                low / price
+            } else {
+                1.0
+            }
+        });
+        let availability = (row.total_stock as f64 / quantity as f64).min(1.0);
+        let mut score = SIMILARITY_WEIGHT * row.similarity
+            + AVAILABILITY_WEIGHT * availability
+            + PRICE_WEIGHT * price;
+        if row.lifecycle_status.is_at_risk() {
+            score /= 2.0;
+        }
+        row.score = round(score);
+    }
+    alternates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.total_stock.cmp(&a.total_stock))
+    });
+
+    AlternatesReport {
+        search_token,
+        quantity,
+        reference: row(reference, reference.clone(), quantity),
+        alternates,
+    }
+}
+
+fn row(reference: &TrustedPartHit, hit: TrustedPartHit, quantity: u64) -> AlternateRow {
+    let comparison: Vec<AttributeComparison> = COMPARED
+        .iter()
+        .filter(|(key, _)| {
+            reference.attributes.contains_key(*key) || hit.attributes.contains_key(*key)
+        })
+        .map(|(key, _)| {
+            let expected = reference.attributes.get(*key);
+            let actual = hit.attributes.get(*key);
+            AttributeComparison {
+                attribute: key,
+                reference: expected.cloned(),
+                candidate: actual.cloned(),
+                score: expected.zip(actual).map(|(a, b)| round(closeness(a, b))),
+            }
+        })
+        .collect();
+
+    // Unknown values count against the candidate: a footprint nobody
+    // confirmed isn't a match.
+    let (matched, total) = COMPARED
+        .iter()
+        .filter(|(key, _)| reference.attributes.contains_key(*key))
+        .fold((0.0, 0.0), |(matched, total), (key, weight)| {
+            let score = comparison
+                .iter()
+                .find(|entry| entry.attribute == *key)
+                .and_then(|entry| entry.score)
+                .unwrap_or(0.0);
+            (matched + weight * score, total + weight)
+        });
+    let similarity = if total > 0.0 {
+        round(matched / total)
+    } else {
+        0.0
+    };
+    let same = |key: &str| {
+        let expected = reference.attributes.get(key);
+        expected.is_some() && expected == hit.attributes.get(key)
+    };
+
+    let best = hit
+        .offers
+        .iter()
+        .filter_map(|offer| Some((offer, offer.quote(quantity).ok()?)))
+        .min_by(|a, b| a.1.extended_price.total_cmp(&b.1.extended_price));
+
+    AlternateRow {
+        mpn: hit.mpn.clone(),
+        manufacturer: hit.manufacturer.clone(),
+        category: hit.category_hint.clone(),
+        lifecycle_status: hit
+            .lifecycle_status
+            .as_deref()
+            .map(LifecycleStatus::normalize)
+            .unwrap_or(LifecycleStatus::Unknown),
+        pin_compatible: same(PACKAGE) && same(PIN_COUNT),
+        similarity,
+        score: 0.0,
+        total_stock: hit.offers.iter().filter_map(|offer| offer.stock).sum(),
+        best_distributor: best.as_ref().map(|(offer, _)| offer.distributor.clone()),
+        unit_price: best.as_ref().map(|(_, quote)| quote.unit_price),
+        extended_price: best.as_ref().map(|(_, quote)| quote.extended_price),
+        currency: best.as_ref().and_then(|(_, quote)| quote.currency.clone()),
+        comparison,
+        hit,
+    }
+}
+
+/// How well `candidate` stands in for `reference`, 0.0–1.0.
+fn closeness(reference: &AttributeValue, candidate: &AttributeValue) -> f64 {
+    match (reference, candidate) {
+        (AttributeValue::Text { value: a }, AttributeValue::Text { value: b }) => {
+            f64::from(u8::from(a.eq_ignore_ascii_case(b)))
+        }
+        (AttributeValue::Quantity { value: a, .. }, AttributeValue::Quantity { value: b, .. }) => {
+            if a == b {
+                1.0
+            } else {
+                // Near values still say something, but never match outright.
+                0.5 * a.min(*b) / a.max(*b)
+            }
+        }
+        // The candidate's range has to cover what the reference supports.
+        (
+            AttributeValue::Range {
+                min: a_min,
+                max: a_max,
+                ..
+            },
+            AttributeValue::Range {
+                min: b_min,
+                max: b_max,
+                ..
+            },
+        ) => {
+            let span = a_max - a_min;
+            let overlap = (a_max.min(*b_max) - a_min.max(*b_min)).max(0.0);
+            if b_min <= a_min && b_max >= a_max {
+                1.0
+            } else if span > 0.0 {
+                overlap / span
+            } else {
+                0.0
+            }
+        }
+        (AttributeValue::List { values: a }, AttributeValue::List { values: b }) => {
+            if a.is_empty() {
+                1.0
+            } else {
+                a.iter().filter(|value| b.contains(value)).count() as f64 / a.len() as f64
+            }
+        }
+        _ => 0.0,
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parts::attributes::extract_attributes;
+    use serde_json::json;
+
+    fn hit(mpn: &str, description: &str, stock: u64, price: f64) -> TrustedPartHit {
+        let mut hit: TrustedPartHit = serde_json::from_value(json!({
+            "mpn": mpn,
+            "manufacturer": "Maker",
+            "description": description,
+            "lifecycleStatus": "Active",
+            "categoryHint": "Interface",
+            "offers": [{
+                "distributor": "DigiKey",
+                "stock": stock,
+                "currency": "USD",
+                "unitPrice": price,
+                "priceBreaks": [{"quantity": 1, "unitPrice": price, "currency": "USD"}]
+            }]
+        }))
+        .unwrap();
+        hit.attributes = extract_attributes(description);
+        hit
+    }
+
+    #[test]
+    fn ranks_drop_in_replacements_first() {
+        let reference = hit(
+            "MAX3485ESA",
+            "RS-485 Interface IC 3.3V RS-485/RS-422 Transceiver, 3 V to 3.6 V, SOIC-8",
+            0,
+            2.0,
+        );
+        let token = alternates_search_token(&reference, Taxonomy::builtin());
+        assert_eq!(token, "rs 422 rs 485 SOIC");
+
+        let drop_in = hit(
+            "THVD1450DR",
+            "RS-485 Transceiver, 2.7 V to 3.6 V, SOIC-8",
+            5000,
+            1.5,
+        );
+        let other_package = hit(
+            "SN65HVD72DGKR",
+            "RS-485 Transceiver, 3 V to 3.6 V, VSSOP-8",
+            9000,
+            0.8,
+        );
+        let mut wrong_category = hit("LM1117", "LDO 3.3V SOT-223", 100, 0.2);
+        wrong_category.category_hint = "PMIC".to_string();
+
+        let report = rank_alternates(
+            &reference,
+            vec![other_package, reference.clone(), wrong_category, drop_in],
+            100,
+            token,
+        );
+        let mpns: Vec<&str> = report
+            .alternates
+            .iter()
+            .map(|row| row.mpn.as_str())
+            .collect();
+        assert_eq!(mpns, ["THVD1450DR", "SN65HVD72DGKR"]);
+
+        let best = &report.alternates[0];
+        assert!(best.pin_compatible);
+        assert_eq!(best.extended_price, Some(150.0));
+        // RS-422 isn't mentioned, so interfaces only half match.
+        let interfaces = best
+            .comparison
+            .iter()
+            .find(|entry| entry.attribute == INTERFACES)
+            .unwrap();
+        assert_eq!(interfaces.score, Some(0.5));
+        assert!(!report.alternates[1].pin_compatible);
+        assert!(best.similarity > report.alternates[1].similarity);
+        assert_eq!(report.reference.similarity, 1.0);
+    }
+}
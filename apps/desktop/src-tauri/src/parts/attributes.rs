@@ -0,0 +1,358 @@
+//! Typed parameters recovered from distributor descriptions such as
+//! "IC MCU 32BIT 512KB FLASH 100LQFP", which is often all a search returns.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+pub const SUPPLY_VOLTAGE: &str = "supplyVoltage";
+pub const OPERATING_TEMPERATURE: &str = "operatingTemperature";
+pub const PACKAGE: &str = "package";
+pub const PIN_COUNT: &str = "pinCount";
+pub const DATA_WIDTH: &str = "dataWidth";
+pub const FLASH: &str = "flash";
+pub const RAM: &str = "ram";
+/// Capacity of a memory IC, in bits as datasheets state it.
+pub const MEMORY_DENSITY: &str = "memoryDensity";
+pub const INTERFACES: &str = "interfaces";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AttributeValue {
+    Range { min: f64, max: f64, unit: String },
+    Quantity { value: f64, unit: String },
+    Text { value: String },
+    List { values: Vec<String> },
+}
+
+pub type PartAttributes = BTreeMap<String, AttributeValue>;
+
+/// Package families, longest spelling first so `VQFN` isn't read as `QFN`.
+const PACKAGES: &str = "HTSSOP|TSSOP|VSSOP|SSOP|MSOP|TSOP|SOIC|SOP|LQFP|TQFP|VQFP|QFP|VFQFN|VQFN|WQFN|UQFN|QFN|TDFN|UDFN|DFN|WSON|VSON|USON|SON|TFBGA|LFBGA|UFBGA|FBGA|BGA|WLCSP|CSP|LGA|PDIP|DIP|PLCC";
+
+/// Canonical interface name and the spellings that indicate it.
+const INTERFACE_PATTERNS: &[(&str, &str)] = &[
+    ("I2C", r"\bI[2²]C\b|\bIIC\b|\bTWI\b"),
+    ("SPI", r"\bSPI\b"),
+    ("QSPI", r"\bQ(?:UAD)?[- ]?SPI\b|\bSPI/QUAD\b|\bQUAD I/O\b"),
+    ("UART", r"\bU(?:S)?ART\b"),
+    ("USB", r"\bUSB\b"),
+    ("CAN", r"(?-i:\bCAN(?:[- ]?FD)?\b)|\bCANBUS\b"),
+    ("LIN", r"(?-i:\bLIN\b)"),
+    ("Ethernet", r"\bETHERNET\b|\bR?GMII\b|\bRMII\b|\b10/100\b"),
+    ("SDIO", r"\bSDIO\b|\bSD/MMC\b|\beMMC\b"),
+    ("I2S", r"\bI[2²]S\b"),
+    ("RS-232", r"\bRS-?232\b"),
+    ("RS-422", r"\bRS-?422\b"),
+    ("RS-485", r"\bRS-?485\b"),
+    ("JTAG", r"\bJTAG\b"),
+    ("SWD", r"\bSWD\b"),
+    ("MIPI", r"\bMIPI\b|\bCSI-?2\b|\bDSI\b"),
+    ("PCIe", r"\bPCI-?E(?:XPRESS)?\b|\bPCI EXPRESS\b"),
+    ("HDMI", r"\bHDMI\b"),
+    ("1-Wire", r"\b1-?WIRE\b"),
+    ("SMBus", r"\bSMBUS\b"),
+    ("Parallel", r"\bPAR(?:ALLEL)?\b"),
+    ("Wi-Fi", r"\bWI-?FI\b|\b802\.11"),
+    ("Bluetooth", r"\bBLUETOOTH\b|\bBLE\b"),
+    ("802.15.4", r"\bZIGBEE\b|\b802\.15\.4\b"),
+    ("LoRa", r"\bLORA(?:WAN)?\b"),
+];
+
+/// Words that make a description a memory IC, where a bare size is the
+/// device capacity rather than embedded flash or RAM.
+const MEMORY_IC_WORDS: &[&str] = &[
+    "FLASH", "DRAM", "SDRAM", "SRAM", "EEPROM", "FRAM", "MRAM", "NOR", "NAND", "DDR", "DDR3",
+    "DDR4", "LPDDR4", "PSRAM",
+];
+
+struct Patterns {
+    voltage_range: Regex,
+    temperature_range: Regex,
+    package_family: Regex,
+    package_pins_first: Regex,
+    package_pins_last: Regex,
+    small_outline: Regex,
+    data_width: Regex,
+    memory_size: Regex,
+    interfaces: Vec<(&'static str, Regex)>,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let compile = |pattern: &str| Regex::new(pattern).expect("attribute pattern");
+        Patterns {
+            voltage_range: compile(
+                r"(?i)(\d+(?:\.\d+)?)\s*V?\s*(?:~|to|-|–|\.\.)\s*(\d+(?:\.\d+)?)\s*V\b",
+            ),
+            temperature_range: compile(
+                r"(?i)([-−]?\d+(?:\.\d+)?)\s*°?\s*C?\s*(?:~|to|\.\.)\s*\+?(\d+(?:\.\d+)?)\s*°?\s*C\b",
+            ),
+            package_family: compile(&format!(r"(?i)\b({})\b", PACKAGES)),
+            // No space allowed before the family, so "3x3 QFN" has no pin count.
+            package_pins_first: compile(&format!(r"(?i)\b(\d{{1,4}})-?({})\b", PACKAGES)),
+            package_pins_last: compile(&format!(r"(?i)\b({})[- ]?(\d{{1,4}})\b", PACKAGES)),
+            small_outline: compile(
+                r"(?i)\b(T?SOT|SC|TO|SOD|DO)-?(\d{2,3}[A-Z]{0,2})(?:-(\d{1,2}))?\b",
+            ),
+            data_width: compile(r"(?i)\b(4|8|16|32|64)[- ]?BITS?\b"),
+            memory_size: compile(
+                r"(?i)\b(\d+(?:\.\d+)?)\s*([KMGT])(BYTES?|BITS?|B)\b(?:\s+(\w+))?",
+            ),
+            interfaces: INTERFACE_PATTERNS
+                .iter()
+                .map(|(name, pattern)| (*name, compile(&format!("(?i){}", pattern))))
+                .collect(),
+        }
+    })
+}
+
+/// Parses whatever parameters `description` states. Nothing is guessed: an
+/// attribute is only present when the text spells it out.
+pub fn extract_attributes(description: &str) -> PartAttributes {
+    let patterns = patterns();
+    let mut attributes = PartAttributes::new();
+
+    if let Some(captures) = patterns.voltage_range.captures(description) {
+        if let (Ok(a), Ok(b)) = (captures[1].parse::<f64>(), captures[2].parse::<f64>()) {
+            attributes.insert(SUPPLY_VOLTAGE.to_string(), range(a, b, "V"));
+        }
+    }
+
+    if let Some(captures) = patterns.temperature_range.captures(description) {
+        let low = captures[1].replace('−', "-").parse::<f64>();
+        if let (Ok(a), Ok(b)) = (low, captures[2].parse::<f64>()) {
+            attributes.insert(OPERATING_TEMPERATURE.to_string(), range(a, b, "°C"));
+        }
+    }
+
+    let package = if let Some(captures) = patterns.small_outline.captures(description) {
+        let name = format!(
+            "{}-{}",
+            captures[1].to_uppercase(),
+            captures[2].to_uppercase()
+        );
+        Some((name, captures.get(3).map(|pins| pins.as_str().to_string())))
+    } else if let Some(captures) = patterns.package_pins_first.captures(description) {
+        Some((captures[2].to_uppercase(), Some(captures[1].to_string())))
+    } else if let Some(captures) = patterns.package_pins_last.captures(description) {
+        Some((captures[1].to_uppercase(), Some(captures[2].to_string())))
+    } else {
+        patterns
+            .package_family
+            .find(description)
+            .map(|found| (found.as_str().to_uppercase(), None))
+    };
+    if let Some((name, pins)) = package {
+        attributes.insert(PACKAGE.to_string(), AttributeValue::Text { value: name });
+        if let Some(pins) = pins.and_then(|pins| pins.parse::<f64>().ok()) {
+            attributes.insert(PIN_COUNT.to_string(), quantity(pins, "pins"));
+        }
+    }
+
+    if let Some(captures) = patterns.data_width.captures(description) {
+        if let Ok(bits) = captures[1].parse::<f64>() {
+            attributes.insert(DATA_WIDTH.to_string(), quantity(bits, "bit"));
+        }
+    }
+
+    extract_memory(description, &mut attributes);
+
+    let interfaces: Vec<String> = patterns
+        .interfaces
+        .iter()
+        .filter(|(_, pattern)| pattern.is_match(description))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    if !interfaces.is_empty() {
+        attributes.insert(
+            INTERFACES.to_string(),
+            AttributeValue::List { values: interfaces },
+        );
+    }
+
+    attributes
+}
+
+/// Sizes followed by FLASH/ROM or RAM belong to a microcontroller; in a
+/// memory IC the first unlabelled size is the device capacity.
+fn extract_memory(description: &str, attributes: &mut PartAttributes) {
+    let upper = description.to_uppercase();
+    let is_memory_ic = upper
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .any(|word| MEMORY_IC_WORDS.contains(&word))
+        && !upper.contains("MCU")
+        && !upper.contains("MICROCONTROLLER");
+
+    for captures in patterns().memory_size.captures_iter(description) {
+        let Ok(amount) = captures[1].parse::<f64>() else {
+            continue;
+        };
+        let multiplier = match captures[2].to_ascii_uppercase().as_str() {
+            "K" => 1024.0,
+            "M" => 1024.0 * 1024.0,
+            "G" => 1024.0 * 1024.0 * 1024.0,
+            _ => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        };
+        // "KB"/"kB" are bytes; "Kb"/"KBIT" are bits.
+        let unit = &captures[3];
+        let bits = unit.to_ascii_uppercase().starts_with("BIT") || unit == "b";
+        let bytes = amount * multiplier / if bits { 8.0 } else { 1.0 };
+        let label = captures
+            .get(4)
+            .map(|word| word.as_str().to_uppercase())
+            .unwrap_or_default();
+
+        let key = match label.as_str() {
+            "FLASH" | "ROM" | "EEPROM" if !is_memory_ic => FLASH,
+            "RAM" | "SRAM" if !is_memory_ic => RAM,
+            _ if is_memory_ic => MEMORY_DENSITY,
+            _ => continue,
+        };
+        if attributes.contains_key(key) {
+            continue;
+        }
+        let value = if key == MEMORY_DENSITY {
+            quantity(bytes * 8.0, "bit")
+        } else {
+            quantity(bytes, "B")
+        };
+        attributes.insert(key.to_string(), value);
+    }
+}
+
+fn range(a: f64, b: f64, unit: &str) -> AttributeValue {
+    AttributeValue::Range {
+        min: a.min(b),
+        max: a.max(b),
+        unit: unit.to_string(),
+    }
+}
+
+fn quantity(value: f64, unit: &str) -> AttributeValue {
+    AttributeValue::Quantity {
+        value,
+        unit: unit.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(attributes: &PartAttributes, key: &str) -> Option<String> {
+        match attributes.get(key)? {
+            AttributeValue::Text { value } => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    fn number(attributes: &PartAttributes, key: &str) -> Option<f64> {
+        match attributes.get(key)? {
+            AttributeValue::Quantity { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn bounds(attributes: &PartAttributes, key: &str) -> Option<(f64, f64)> {
+        match attributes.get(key)? {
+            AttributeValue::Range { min, max, .. } => Some((*min, *max)),
+            _ => None,
+        }
+    }
+
+    fn interfaces(attributes: &PartAttributes) -> Vec<String> {
+        match attributes.get(INTERFACES) {
+            Some(AttributeValue::List { values }) => values.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Descriptions as returned by DigiKey, Mouser and TrustedParts.
+    #[test]
+    fn parses_distributor_description_corpus() {
+        let mcu = extract_attributes("IC MCU 32BIT 512KB FLASH 100LQFP");
+        assert_eq!(text(&mcu, PACKAGE).as_deref(), Some("LQFP"));
+        assert_eq!(number(&mcu, PIN_COUNT), Some(100.0));
+        assert_eq!(number(&mcu, DATA_WIDTH), Some(32.0));
+        assert_eq!(number(&mcu, FLASH), Some(512.0 * 1024.0));
+        assert!(!mcu.contains_key(MEMORY_DENSITY));
+
+        let stm32 = extract_attributes(
+            "ARM Microcontrollers - MCU 32-Bit MCU, 1MB Flash, 192KB RAM, 1.8V-3.6V, LQFP-64, -40 to 85C",
+        );
+        assert_eq!(number(&stm32, FLASH), Some(1024.0 * 1024.0));
+        assert_eq!(number(&stm32, RAM), Some(192.0 * 1024.0));
+        assert_eq!(bounds(&stm32, SUPPLY_VOLTAGE), Some((1.8, 3.6)));
+        assert_eq!(bounds(&stm32, OPERATING_TEMPERATURE), Some((-40.0, 85.0)));
+        assert_eq!(text(&stm32, PACKAGE).as_deref(), Some("LQFP"));
+        assert_eq!(number(&stm32, PIN_COUNT), Some(64.0));
+
+        let nor = extract_attributes("IC FLASH 128MBIT SPI/QUAD 8SOIC");
+        assert_eq!(number(&nor, MEMORY_DENSITY), Some(128.0 * 1024.0 * 1024.0));
+        assert_eq!(interfaces(&nor), ["SPI", "QSPI"]);
+        assert_eq!(text(&nor, PACKAGE).as_deref(), Some("SOIC"));
+        assert_eq!(number(&nor, PIN_COUNT), Some(8.0));
+
+        let dram = extract_attributes("IC DRAM 4GBIT PAR 96FBGA");
+        assert_eq!(
+            number(&dram, MEMORY_DENSITY),
+            Some(4.0 * 1024.0 * 1024.0 * 1024.0)
+        );
+        assert_eq!(interfaces(&dram), ["Parallel"]);
+        assert_eq!(text(&dram, PACKAGE).as_deref(), Some("FBGA"));
+
+        let buck = extract_attributes("3-17V 3A Step-Down Converter in 3x3 QFN Package");
+        assert_eq!(bounds(&buck, SUPPLY_VOLTAGE), Some((3.0, 17.0)));
+        assert_eq!(text(&buck, PACKAGE).as_deref(), Some("QFN"));
+        assert!(!buck.contains_key(PIN_COUNT));
+
+        let regulator = extract_attributes("IC REG BUCK ADJUSTABLE 3A 16VQFN");
+        assert_eq!(text(&regulator, PACKAGE).as_deref(), Some("VQFN"));
+        assert_eq!(number(&regulator, PIN_COUNT), Some(16.0));
+
+        let imu = extract_attributes("IMU ACCEL/GYRO I2C/SPI 14LGA");
+        assert_eq!(interfaces(&imu), ["I2C", "SPI"]);
+        assert_eq!(number(&imu, PIN_COUNT), Some(14.0));
+
+        let temp =
+            extract_attributes("Temperature Sensor Digital, Local -40°C ~ 125°C 12 b 6-WSON (2x2)");
+        assert_eq!(bounds(&temp, OPERATING_TEMPERATURE), Some((-40.0, 125.0)));
+        assert_eq!(text(&temp, PACKAGE).as_deref(), Some("WSON"));
+        assert_eq!(number(&temp, PIN_COUNT), Some(6.0));
+
+        let ldo = extract_attributes("IC REG LINEAR 3.3V 1A SOT223-4");
+        assert_eq!(text(&ldo, PACKAGE).as_deref(), Some("SOT-223"));
+        assert_eq!(number(&ldo, PIN_COUNT), Some(4.0));
+        assert!(!ldo.contains_key(SUPPLY_VOLTAGE));
+
+        let transceiver = extract_attributes(
+            "RS-485 Interface IC 3.3V RS-485/RS-422 Transceiver, 2.7 V to 3.6 V, SOIC-8",
+        );
+        assert_eq!(interfaces(&transceiver), ["RS-422", "RS-485"]);
+        assert_eq!(bounds(&transceiver, SUPPLY_VOLTAGE), Some((2.7, 3.6)));
+        assert_eq!(number(&transceiver, PIN_COUNT), Some(8.0));
+
+        let can =
+            extract_attributes("CAN Interface IC 5V CAN FD Transceiver -40°C to +150°C SOIC-8");
+        assert_eq!(interfaces(&can), ["CAN"]);
+        assert_eq!(bounds(&can, OPERATING_TEMPERATURE), Some((-40.0, 150.0)));
+
+        let module = extract_attributes(
+            "Bluetooth, WiFi 802.11b/g/n Transceiver Module 2.4GHz ~ 2.5GHz Integrated, Trace Surface Mount",
+        );
+        assert_eq!(interfaces(&module), ["Wi-Fi", "Bluetooth"]);
+        assert!(!module.contains_key(PACKAGE));
+
+        let eeprom = extract_attributes("IC EEPROM 256KBIT I2C 1MHZ 8TSSOP");
+        assert_eq!(number(&eeprom, MEMORY_DENSITY), Some(256.0 * 1024.0));
+        assert_eq!(interfaces(&eeprom), ["I2C"]);
+        assert_eq!(text(&eeprom, PACKAGE).as_deref(), Some("TSSOP"));
+
+        // "can", "program" and "line" must not read as interfaces.
+        let prose = extract_attributes("Programmable oscillator, can drive one line");
+        assert!(interfaces(&prose).is_empty());
+    }
+}
@@ -0,0 +1,519 @@
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{
+    merge_hits, PartQuery, PartsProvider, ProviderCapabilities, ProviderScope, TrustedPartHit,
+};
+use crate::sim_cache::CacheStats;
+
+/// Bump when the stored hit shape changes incompatibly.
+const CACHE_FORMAT_VERSION: u32 = 5;
+/// Results younger than this are served without touching the network.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+/// Past the TTL, results are still served for this long while a background
+/// request refreshes them.
+pub const DEFAULT_STALE_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static WRITE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// Everything that changes what a provider returns, except the result limit:
+/// an entry serves any query asking for no more than it holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheKey {
+    provider: String,
+    scope: ProviderScope,
+    /// `search` or `mpn`.
+    kind: String,
+    query: String,
+    country: String,
+    exact_match: bool,
+    in_stock_only: bool,
+}
+
+impl CacheKey {
+    fn new(provider: &dyn PartsProvider, request: &Request, query: &PartQuery) -> Self {
+        let (kind, token) = match request {
+            Request::Search => ("search", query.search_token.as_str()),
+            Request::Mpn(mpn) => ("mpn", mpn.as_str()),
+        };
+        Self {
+            provider: provider.capabilities().id.to_string(),
+            scope: provider.scope(),
+            kind: kind.to_string(),
+            query: normalize_query(token),
+            country: query.country_code(),
+            exact_match: query.exact_match.unwrap_or(false),
+            in_stock_only: query.in_stock_only.unwrap_or(true),
+        }
+    }
+
+    fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(CACHE_FORMAT_VERSION.to_le_bytes());
+        hasher.update(serde_json::to_vec(self).unwrap_or_default());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    version: u32,
+    key: CacheKey,
+    /// Unix seconds.
+    stored_at: u64,
+    /// Result limit the response was fetched with.
+    max_results: usize,
+    hits: Vec<TrustedPartHit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freshness {
+    Fresh,
+    Stale,
+    Expired,
+}
+
+/// On-disk store of provider responses, one JSON file per query.
+#[derive(Debug, Clone)]
+pub struct PartSearchCache {
+    dir: PathBuf,
+    ttl: Duration,
+    stale_window: Duration,
+}
+
+impl PartSearchCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            ttl: DEFAULT_TTL,
+            stale_window: DEFAULT_STALE_WINDOW,
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let (entries, total_bytes) = self
+            .entry_paths()
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .fold((0, 0), |(count, bytes), meta| {
+                (count + 1, bytes + meta.len())
+            });
+
+        CacheStats {
+            directory: self.dir.to_string_lossy().to_string(),
+            entries,
+            total_bytes,
+            hits: HITS.load(Ordering::Relaxed),
+            misses: MISSES.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Removes entries for `provider` and/or `search_token` (matched after
+    /// normalization); with neither, empties the cache.
+    pub fn invalidate(
+        &self,
+        provider: Option<&str>,
+        search_token: Option<&str>,
+    ) -> Result<CacheStats, String> {
+        let query = search_token.map(normalize_query);
+        for path in self.entry_paths() {
+            let matches = match (provider, &query) {
+                (None, None) => true,
+                _ => read_entry(&path).is_none_or(|entry| {
+                    provider.is_none_or(|provider| entry.key.provider == provider)
+                        && query.as_ref().is_none_or(|query| entry.key.query == *query)
+                }),
+            };
+            if matches {
+                fs::remove_file(&path).map_err(|e| {
+                    format!("Failed to remove cache entry {}: {}", path.display(), e)
+                })?;
+            }
+        }
+        Ok(self.stats())
+    }
+
+    /// Cached exact lookup of `mpn` from `provider`, for batch lookups that
+    /// bypass [`CachedProvider`]. Only entries within the TTL are returned
+    /// unless `allow_stale` (e.g. because the live request failed).
+    pub fn get_mpn(
+        &self,
+        provider: &dyn PartsProvider,
+        mpn: &str,
+        query: &PartQuery,
+        allow_stale: bool,
+    ) -> Option<Vec<TrustedPartHit>> {
+        let key = CacheKey::new(provider, &Request::Mpn(mpn.to_string()), query);
+        match self.get(&key, query) {
+            Some((hits, Freshness::Fresh)) => {
+                HITS.fetch_add(1, Ordering::Relaxed);
+                Some(hits)
+            }
+            Some((hits, _)) if allow_stale => Some(hits),
+            _ => {
+                if !allow_stale {
+                    MISSES.fetch_add(1, Ordering::Relaxed);
+                }
+                None
+            }
+        }
+    }
+
+    pub fn put_mpn(
+        &self,
+        provider: &dyn PartsProvider,
+        mpn: &str,
+        query: &PartQuery,
+        hits: &[TrustedPartHit],
+    ) -> Result<(), String> {
+        let key = CacheKey::new(provider, &Request::Mpn(mpn.to_string()), query);
+        self.put(&key, query, hits)
+    }
+
+    /// Every part the cache holds, however old, merged across entries. The
+    /// newest entry's description and parameters win.
+    pub fn cached_hits(&self) -> Vec<TrustedPartHit> {
+        let mut entries: Vec<CacheEntry> = self
+            .entry_paths()
+            .iter()
+            .filter_map(|path| read_entry(path))
+            .filter(|entry| entry.version == CACHE_FORMAT_VERSION)
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.stored_at));
+        let now = crate::unix_now();
+        merge_hits(entries.into_iter().flat_map(|entry| {
+            let age = now.saturating_sub(entry.stored_at);
+            entry.hits.into_iter().map(move |mut hit| {
+                hit.from_cache = true;
+                hit.cache_age_seconds = Some(age);
+                hit
+            })
+        }))
+    }
+
+    /// Directory the entries are stored in.
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    /// The entry for `key`, cut to `query`'s result limit. An entry fetched
+    /// with a smaller limit that may have left results out counts as
+    /// expired, so it is only served when a live request fails.
+    fn get(&self, key: &CacheKey, query: &PartQuery) -> Option<(Vec<TrustedPartHit>, Freshness)> {
+        let entry = read_entry(&self.entry_path(key))?;
+        if entry.version != CACHE_FORMAT_VERSION || entry.key != *key {
+            return None;
+        }
+        let wanted = query.max_results();
+        let complete = entry.max_results >= wanted || entry.hits.len() < entry.max_results;
+        let age = Duration::from_secs(crate::unix_now().saturating_sub(entry.stored_at));
+        let freshness = if !complete {
+            Freshness::Expired
+        } else if age < self.ttl {
+            Freshness::Fresh
+        } else if age < self.ttl + self.stale_window {
+            Freshness::Stale
+        } else {
+            Freshness::Expired
+        };
+        let hits = entry
+            .hits
+            .into_iter()
+            .take(wanted)
+            .map(|mut hit| {
+                hit.from_cache = true;
+                hit.cache_age_seconds = Some(age.as_secs());
+                hit
+            })
+            .collect();
+        Some((hits, freshness))
+    }
+
+    fn put(
+        &self,
+        key: &CacheKey,
+        query: &PartQuery,
+        hits: &[TrustedPartHit],
+    ) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create part search cache directory: {}", e))?;
+        let entry = CacheEntry {
+            version: CACHE_FORMAT_VERSION,
+            key: key.clone(),
+            stored_at: crate::unix_now(),
+            max_results: query.max_results(),
+            hits: hits.to_vec(),
+        };
+        let contents = serde_json::to_vec(&entry)
+            .map_err(|e| format!("Failed to serialize part search cache entry: {}", e))?;
+        // Write-then-rename so a concurrent reader never sees a partial entry.
+        let final_path = self.entry_path(key);
+        let temp_path = final_path.with_extension(format!(
+            "tmp{}-{}",
+            std::process::id(),
+            WRITE_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp_path, contents)
+            .and_then(|_| fs::rename(&temp_path, &final_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp_path);
+                format!("Failed to write part search cache entry: {}", e)
+            })
+    }
+
+    fn entry_path(&self, key: &CacheKey) -> PathBuf {
+        self.dir.join(format!("{}.json", key.digest()))
+    }
+
+    fn entry_paths(&self) -> Vec<PathBuf> {
+        fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Wraps a provider with [`PartSearchCache`]:
+///
+/// - fresh entries are returned without a request;
+/// - stale entries are returned immediately and refreshed in the background;
+/// - when the provider fails (e.g. offline), any cached entry, however old,
+///   is returned instead of the error.
+///
+/// A query with `refresh` skips the first two steps.
+pub struct CachedProvider {
+    inner: Arc<dyn PartsProvider>,
+    cache: Arc<PartSearchCache>,
+}
+
+#[derive(Debug, Clone)]
+enum Request {
+    Search,
+    Mpn(String),
+}
+
+impl CachedProvider {
+    pub fn new(inner: Arc<dyn PartsProvider>, cache: Arc<PartSearchCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    async fn fetch(
+        &self,
+        request: Request,
+        query: &PartQuery,
+    ) -> Result<Vec<TrustedPartHit>, String> {
+        let key = CacheKey::new(&*self.inner, &request, query);
+        if !query.refresh.unwrap_or(false) {
+            match self.cache.get(&key, query) {
+                Some((hits, Freshness::Fresh)) => {
+                    HITS.fetch_add(1, Ordering::Relaxed);
+                    return Ok(hits);
+                }
+                Some((hits, Freshness::Stale)) => {
+                    HITS.fetch_add(1, Ordering::Relaxed);
+                    let inner = self.inner.clone();
+                    let cache = self.cache.clone();
+                    let query = query.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Ok(hits) = send(&*inner, &request, &query).await {
+                            let _ = cache.put(&key, &query, &hits);
+                        }
+                    });
+                    return Ok(hits);
+                }
+                _ => {}
+            }
+        }
+        MISSES.fetch_add(1, Ordering::Relaxed);
+
+        match send(&*self.inner, &request, query).await {
+            Ok(hits) => {
+                let _ = self.cache.put(&key, query, &hits);
+                Ok(hits)
+            }
+            Err(error) => match self.cache.get(&key, query) {
+                Some((hits, _)) => Ok(hits),
+                None => Err(error),
+            },
+        }
+    }
+}
+
+async fn send(
+    provider: &dyn PartsProvider,
+    request: &Request,
+    query: &PartQuery,
+) -> Result<Vec<TrustedPartHit>, String> {
+    match request {
+        Request::Search => provider.search(query).await,
+        Request::Mpn(mpn) => provider.lookup_mpn(mpn, query).await,
+    }
+}
+
+impl PartsProvider for CachedProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn scope(&self) -> ProviderScope {
+        self.inner.scope()
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(self.fetch(Request::Search, query))
+    }
+
+    fn lookup_mpn<'a>(
+        &'a self,
+        mpn: &'a str,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(self.fetch(Request::Mpn(mpn.to_string()), query))
+    }
+}
+
+/// Case- and whitespace-insensitive form of a search token, so "tps 62130 "
+/// and "TPS 62130" share an entry.
+fn normalize_query(token: &str) -> String {
+    token
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn read_entry(path: &std::path::Path) -> Option<CacheEntry> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    struct Flaky {
+        account: &'static str,
+        calls: AtomicUsize,
+        fail_after: usize,
+    }
+
+    impl PartsProvider for Flaky {
+        fn capabilities(&self) -> ProviderCapabilities {
+            super::super::trustedparts::CAPABILITIES
+        }
+
+        fn scope(&self) -> ProviderScope {
+            ProviderScope::new("https://parts.example.com", self.account)
+        }
+
+        fn search<'a>(
+            &'a self,
+            query: &'a PartQuery,
+        ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+            Box::pin(async move {
+                let call = self.calls.fetch_add(1, Ordering::SeqCst);
+                if call >= self.fail_after {
+                    return Err("offline".to_string());
+                }
+                Ok(vec![TrustedPartHit {
+                    mpn: format!("{}-{}", query.search_token, call),
+                    manufacturer: None,
+                    description: None,
+                    lifecycle_status: None,
+                    category_hint: "Component".to_string(),
+                    category_confidence: 0.0,
+                    attributes: Default::default(),
+                    offers: Vec::new(),
+                    from_cache: false,
+                    cache_age_seconds: None,
+                }])
+            })
+        }
+
+        fn lookup_mpn<'a>(
+            &'a self,
+            _mpn: &'a str,
+            query: &'a PartQuery,
+        ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+            self.search(query)
+        }
+    }
+
+    #[test]
+    fn serves_fresh_entries_and_falls_back_when_offline() {
+        let dir = std::env::temp_dir().join(format!("aha_part_cache_test_{}", std::process::id()));
+        let cache = Arc::new(PartSearchCache::new(dir.clone()));
+        let _ = fs::remove_dir_all(&dir);
+        let flaky = |account: &'static str, fail_after: usize| -> Arc<dyn PartsProvider> {
+            Arc::new(Flaky {
+                account,
+                calls: AtomicUsize::new(0),
+                fail_after,
+            })
+        };
+        let provider = flaky("acme", 1);
+        let query = |token: &str, refresh: bool| PartQuery {
+            search_token: token.to_string(),
+            refresh: Some(refresh),
+            max_results: Some(1),
+            ..PartQuery::default()
+        };
+
+        let cached = CachedProvider::new(provider, cache.clone());
+        let first =
+            tauri::async_runtime::block_on(cached.search(&query("TPS62130", false))).unwrap();
+        assert!(!first[0].from_cache);
+
+        let second =
+            tauri::async_runtime::block_on(cached.search(&query("  tps62130 ", false))).unwrap();
+        assert_eq!(second[0].mpn, "TPS62130-0");
+        assert!(second[0].from_cache);
+        assert!(second[0].cache_age_seconds.is_some());
+
+        // A forced refresh hits the (now failing) provider and falls back.
+        let offline =
+            tauri::async_runtime::block_on(cached.search(&query("TPS62130", true))).unwrap();
+        assert!(offline[0].from_cache);
+        assert!(tauri::async_runtime::block_on(cached.search(&query("LM317", false))).is_err());
+
+        // Another account doesn't see the entry; neither does a query asking
+        // for more results than it was fetched with.
+        let other = CachedProvider::new(flaky("globex", 1), cache.clone());
+        let own = tauri::async_runtime::block_on(other.search(&query("TPS62130", false))).unwrap();
+        assert!(!own[0].from_cache);
+        let wider = CachedProvider::new(flaky("acme", 1), cache.clone());
+        let more = PartQuery {
+            max_results: Some(10),
+            ..query("TPS62130", false)
+        };
+        let refetched = tauri::async_runtime::block_on(wider.search(&more)).unwrap();
+        assert!(!refetched[0].from_cache);
+        // That entry came back short of its limit, so it serves any limit.
+        let reused = tauri::async_runtime::block_on(wider.search(&more)).unwrap();
+        assert!(reused[0].from_cache);
+        assert!(
+            tauri::async_runtime::block_on(wider.search(&query("TPS62130", false))).unwrap()[0]
+                .from_cache
+        );
+
+        assert_eq!(cache.invalidate(None, Some("tps62130")).unwrap().entries, 0);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
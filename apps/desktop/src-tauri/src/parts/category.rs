@@ -0,0 +1,285 @@
+//! Scored part classifier. Every category of a [`Taxonomy`] collects the
+//! weights of the keywords found in the description and the part number
+//! prefixes the MPN starts with; the highest total wins.
+//!
+//! The built-in taxonomy lives in `taxonomy.json`. Users extend it with a
+//! file of the same shape: unknown categories are added, known ones gain or
+//! re-weight keywords and prefixes.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Reported when no category scores at all.
+pub const FALLBACK_CATEGORY: &str = "Component";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Taxonomy {
+    #[serde(default)]
+    pub categories: Vec<CategoryRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryRule {
+    pub name: String,
+    /// Word or phrase -> weight, matched on whole words of the description.
+    #[serde(default)]
+    pub keywords: BTreeMap<String, f64>,
+    /// MPN prefix -> weight, matched case-insensitively.
+    #[serde(default)]
+    pub mpn_prefixes: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryGuess {
+    pub category: String,
+    /// Share of the total evidence behind `category`, 0.0–1.0. Weak or
+    /// contested evidence stays well below 1.
+    pub confidence: f64,
+}
+
+impl Taxonomy {
+    pub fn builtin() -> &'static Taxonomy {
+        static BUILTIN: OnceLock<Taxonomy> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            serde_json::from_str(include_str!("taxonomy.json")).expect("built-in taxonomy")
+        })
+    }
+
+    /// User extensions stored at `path`, or none.
+    pub fn load_extensions(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read taxonomy: {}", e))?;
+        let taxonomy: Self = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse taxonomy: {}", e))?;
+        taxonomy.validated()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create taxonomy directory: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize taxonomy: {}", e))?;
+        fs::write(path, contents).map_err(|e| format!("Failed to write taxonomy: {}", e))
+    }
+
+    pub fn validated(mut self) -> Result<Self, String> {
+        for rule in &mut self.categories {
+            rule.name = rule.name.trim().to_string();
+            if rule.name.is_empty() {
+                return Err("Taxonomy category names cannot be empty.".to_string());
+            }
+            let mut weights = rule.keywords.values().chain(rule.mpn_prefixes.values());
+            if weights.any(|weight| !weight.is_finite()) {
+                return Err(format!(
+                    "Taxonomy weights for {} must be numbers.",
+                    rule.name
+                ));
+            }
+        }
+        Ok(self)
+    }
+
+    /// This taxonomy with `extensions` applied; categories match by name
+    /// ignoring case.
+    pub fn extended(&self, extensions: &Taxonomy) -> Taxonomy {
+        let mut merged = self.clone();
+        for extension in &extensions.categories {
+            match merged
+                .categories
+                .iter_mut()
+                .find(|rule| rule.name.eq_ignore_ascii_case(&extension.name))
+            {
+                Some(rule) => {
+                    rule.keywords.extend(extension.keywords.clone());
+                    rule.mpn_prefixes.extend(extension.mpn_prefixes.clone());
+                }
+                None => merged.categories.push(extension.clone()),
+            }
+        }
+        merged
+    }
+
+    pub fn classify(&self, mpn: &str, description: Option<&str>) -> CategoryGuess {
+        let tokens = tokenize(description.unwrap_or_default());
+        let mpn = mpn.trim().to_uppercase();
+
+        let scores: Vec<(&str, f64)> = self
+            .categories
+            .iter()
+            .map(|rule| {
+                let keywords: f64 = rule
+                    .keywords
+                    .iter()
+                    .filter(|(keyword, _)| contains_phrase(&tokens, &tokenize(keyword)))
+                    .map(|(_, weight)| weight)
+                    .sum();
+                // Only the longest matching prefix counts, so "PIC18" doesn't
+                // also collect "PIC1".
+                let prefix = rule
+                    .mpn_prefixes
+                    .iter()
+                    .filter(|(prefix, _)| mpn.starts_with(&prefix.to_uppercase()))
+                    .max_by_key(|(prefix, _)| prefix.len())
+                    .map(|(_, weight)| *weight)
+                    .unwrap_or(0.0);
+                (rule.name.as_str(), keywords + prefix)
+            })
+            .collect();
+
+        let total: f64 = scores.iter().map(|(_, score)| score.max(0.0)).sum();
+        let best = scores
+            .iter()
+            .fold(None::<(&str, f64)>, |best, &(name, score)| match best {
+                Some((_, top)) if top >= score => best,
+                _ if score > 0.0 => Some((name, score)),
+                _ => best,
+            });
+
+        match best {
+            Some((name, score)) => CategoryGuess {
+                category: name.to_string(),
+                // The +1 keeps a single weak keyword from reading as certain.
+                confidence: (score / (total + 1.0) * 100.0).round() / 100.0,
+            },
+            None => CategoryGuess {
+                category: FALLBACK_CATEGORY.to_string(),
+                confidence: 0.0,
+            },
+        }
+    }
+
+    /// Keywords of `category` found in `description`, strongest first.
+    pub fn keywords_in(&self, category: &str, description: &str) -> Vec<String> {
+        let tokens = tokenize(description);
+        let Some(rule) = self
+            .categories
+            .iter()
+            .find(|rule| rule.name.eq_ignore_ascii_case(category))
+        else {
+            return Vec::new();
+        };
+        let mut found: Vec<(&String, f64)> = rule
+            .keywords
+            .iter()
+            .filter(|(keyword, _)| contains_phrase(&tokens, &tokenize(keyword)))
+            .map(|(keyword, weight)| (keyword, *weight))
+            .collect();
+        found.sort_by(|a, b| b.1.total_cmp(&a.1));
+        found
+            .into_iter()
+            .map(|(keyword, _)| keyword.clone())
+            .collect()
+    }
+}
+
+/// Classifies with the built-in taxonomy.
+pub fn infer_category(mpn: &str, description: Option<&str>) -> CategoryGuess {
+    Taxonomy::builtin().classify(mpn, description)
+}
+
+/// Lower-case alphanumeric words, so "Step-Down" is `["step", "down"]` and
+/// "program" never contains "ram".
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn contains_phrase(tokens: &[String], phrase: &[String]) -> bool {
+    !phrase.is_empty() && tokens.windows(phrase.len()).any(|window| window == phrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(mpn: &str, description: &str) -> String {
+        infer_category(mpn, Some(description)).category
+    }
+
+    #[test]
+    fn scores_keywords_on_word_boundaries() {
+        assert_eq!(category("QPA4501", "RF power amplifier regulator"), "RF");
+        assert_eq!(
+            category("SIT8008", "MEMS oscillator, programmable, 3.3V"),
+            "Crystal"
+        );
+        assert_eq!(
+            category("TPS62130RGTR", "IC REG BUCK ADJUSTABLE 3A 16VQFN"),
+            "PMIC"
+        );
+        assert_eq!(category("STM32F405RGT6", "IC 32BIT 1MB 64LQFP"), "MCU");
+        assert_eq!(
+            category("SN65HVD230DR", "IC TRANSCEIVER HALF 1/1 8SOIC CAN"),
+            "Interface"
+        );
+        assert_eq!(
+            category(
+                "USB4105-GF-A",
+                "USB-C (USB TYPE-C) Receptacle Connector 16 Position"
+            ),
+            "Connector"
+        );
+        assert_eq!(
+            category("GRM188R71C104KA01D", "CAP CER 0.1UF 16V X7R 0603"),
+            "Passive"
+        );
+        assert_eq!(
+            category("CSD95490Q5MC", "Synchronous Buck NexFET Smart Power Stage"),
+            "Power Stage"
+        );
+        assert_eq!(
+            category("ABM8-25.000MHZ-B2-T", "CRYSTAL 25MHZ 18PF SMD"),
+            "Crystal"
+        );
+        assert_eq!(
+            category("W25Q128JVSIQ", "IC FLASH 128MBIT SPI/QUAD 8SOIC"),
+            "Memory"
+        );
+
+        let unknown = infer_category("XYZ123", Some("Widget"));
+        assert_eq!(unknown.category, FALLBACK_CATEGORY);
+        assert_eq!(unknown.confidence, 0.0);
+
+        let strong = infer_category("STM32F405RGT6", Some("ARM Cortex-M4 MCU 1MB Flash"));
+        let weak = infer_category("", Some("power converter"));
+        assert!(strong.confidence > weak.confidence);
+    }
+
+    #[test]
+    fn user_extensions_add_and_reweight_categories() {
+        let extensions: Taxonomy = serde_json::from_value(serde_json::json!({
+            "categories": [
+                {"name": "Motor Driver", "keywords": {"stepper": 5}, "mpnPrefixes": {"TMC": 5}},
+                {"name": "pmic", "keywords": {"e-fuse": 4}}
+            ]
+        }))
+        .unwrap();
+        let taxonomy = Taxonomy::builtin().extended(&extensions);
+
+        let stepper = taxonomy.classify("TMC2209-LA", Some("Stepper motor driver"));
+        assert_eq!(stepper.category, "Motor Driver");
+        assert_eq!(
+            taxonomy
+                .classify("TPS25940", Some("e-fuse with reverse current blocking"))
+                .category,
+            "PMIC"
+        );
+        assert_eq!(
+            taxonomy.categories.len(),
+            Taxonomy::builtin().categories.len() + 1
+        );
+    }
+}
@@ -0,0 +1,289 @@
+//! User-maintained exchange rates, so offers quoted in different currencies
+//! can be compared and summed in one reporting currency.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::pricing::normalize_breaks;
+use super::{PriceBreak, TrustedPartHit, TrustedPartOffer};
+
+/// `rates[code]` is how many units of `code` one unit of `base` buys, e.g.
+/// `EUR: 0.92` with a USD base.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeRates {
+    pub base: String,
+    pub rates: BTreeMap<String, f64>,
+    /// Unix seconds of the last edit or import.
+    #[serde(default)]
+    pub updated_at: Option<u64>,
+}
+
+impl Default for ExchangeRates {
+    fn default() -> Self {
+        Self {
+            base: "USD".to_string(),
+            rates: BTreeMap::from([("USD".to_string(), 1.0)]),
+            updated_at: None,
+        }
+    }
+}
+
+impl ExchangeRates {
+    /// The stored table, or the default when none was saved yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read exchange rates: {}", e))?;
+        let rates: Self = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse exchange rates: {}", e))?;
+        rates.validated()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create exchange rate directory: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize exchange rates: {}", e))?;
+        fs::write(path, contents).map_err(|e| format!("Failed to write exchange rates: {}", e))
+    }
+
+    /// Upper-cases codes and checks every rate, pinning the base to 1.
+    pub fn validated(mut self) -> Result<Self, String> {
+        self.base = currency_code(&self.base)?;
+        let mut rates = BTreeMap::new();
+        for (code, rate) in self.rates {
+            let code = currency_code(&code)?;
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err(format!("Exchange rate for {} must be positive.", code));
+            }
+            rates.insert(code, rate);
+        }
+        rates.insert(self.base.clone(), 1.0);
+        self.rates = rates;
+        Ok(self)
+    }
+
+    /// Merges `currency,rate` lines (relative to [`Self::base`]) into the
+    /// table. A header row, blank lines and `#` comments are skipped. Returns
+    /// the number of rates imported.
+    pub fn import_csv(&mut self, csv: &str) -> Result<usize, String> {
+        let mut imported = Vec::new();
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line
+                .split([',', ';', '\t'])
+                .map(|field| field.trim().trim_matches('"'))
+                .collect();
+            let [code, rate, ..] = fields.as_slice() else {
+                return Err(format!("Line {}: expected currency,rate.", index + 1));
+            };
+            let Ok(rate) = rate.parse::<f64>() else {
+                if imported.is_empty() && index == 0 {
+                    continue; // header
+                }
+                return Err(format!("Line {}: invalid rate '{}'.", index + 1, rate));
+            };
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err(format!("Line {}: rate must be positive.", index + 1));
+            }
+            let code = currency_code(code).map_err(|e| format!("Line {}: {}", index + 1, e))?;
+            imported.push((code, rate));
+        }
+        if imported.is_empty() {
+            return Err("No exchange rates found in CSV.".to_string());
+        }
+
+        let count = imported.len();
+        self.rates.extend(imported);
+        self.rates.insert(self.base.clone(), 1.0);
+        self.touch();
+        Ok(count)
+    }
+
+    pub fn touch(&mut self) {
+        self.updated_at = Some(crate::unix_now());
+    }
+
+    /// `amount` of `from` in `to`, or `None` when either rate is unknown.
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Option<f64> {
+        let from = from.trim().to_uppercase();
+        let to = to.trim().to_uppercase();
+        if from == to {
+            return Some(amount);
+        }
+        Some(amount / self.rates.get(&from)? * self.rates.get(&to)?)
+    }
+
+    /// Re-prices `offer` in `target`, leaving only breaks in `target`. The
+    /// distributor's own `target` breaks are used when it lists any;
+    /// otherwise the breaks in the offer's currency are converted, keeping
+    /// what was quoted in `original_currency`/`original_unit_price`. Offers
+    /// in an unknown or missing currency are left as they are.
+    pub fn convert_offer(&self, offer: &mut TrustedPartOffer, target: &str) {
+        let target = target.trim().to_uppercase();
+        let in_target = |entry: &PriceBreak| entry.currency.as_deref() == Some(target.as_str());
+        if offer.price_breaks.iter().any(in_target) {
+            offer.price_breaks.retain(in_target);
+            normalize_breaks(&mut offer.price_breaks);
+            offer.unit_price = offer.price_breaks.first().map(|entry| entry.unit_price);
+            offer.currency = Some(target);
+            return;
+        }
+
+        let Some(from) = offer.currency.clone() else {
+            return;
+        };
+        if from == target || self.convert(1.0, &from, &target).is_none() {
+            return;
+        }
+        offer.original_currency = Some(from.clone());
+        offer.original_unit_price = offer.unit_price;
+        offer.unit_price = offer
+            .unit_price
+            .and_then(|price| self.convert(price, &from, &target));
+        offer
+            .price_breaks
+            .retain(|entry| entry.currency.as_deref() == Some(from.as_str()));
+        for entry in &mut offer.price_breaks {
+            entry.unit_price = self
+                .convert(entry.unit_price, &from, &target)
+                .unwrap_or(entry.unit_price);
+            entry.currency = Some(target.clone());
+        }
+        normalize_breaks(&mut offer.price_breaks);
+        offer.currency = Some(target);
+    }
+
+    pub fn convert_hits(&self, hits: &mut [TrustedPartHit], target: &str) {
+        for offer in hits.iter_mut().flat_map(|hit| hit.offers.iter_mut()) {
+            self.convert_offer(offer, target);
+        }
+    }
+}
+
+fn currency_code(code: &str) -> Result<String, String> {
+    let code = code.trim().to_uppercase();
+    if code.len() != 3 || !code.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        return Err(format!("'{}' is not an ISO 4217 currency code.", code));
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_csv_and_converts_offers_keeping_the_original() {
+        let mut rates = ExchangeRates::default();
+        let count = rates
+            .import_csv("currency,rate\n# ECB, 2026-10-16\nEUR,0.8\ngbp;0.5\n")
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(rates.rates["GBP"], 0.5);
+        assert!(rates.import_csv("EUR,abc").is_err());
+
+        let mut offer = TrustedPartOffer {
+            distributor: "Farnell".to_string(),
+            sku: None,
+            stock: Some(100),
+            moq: None,
+            order_multiple: None,
+            currency: Some("EUR".to_string()),
+            unit_price: Some(2.0),
+            original_currency: None,
+            original_unit_price: None,
+            price_breaks: vec![
+                PriceBreak {
+                    quantity: 1,
+                    unit_price: 2.0,
+                    currency: Some("EUR".to_string()),
+                },
+                PriceBreak {
+                    quantity: 1,
+                    unit_price: 3.0,
+                    currency: Some("CHF".to_string()),
+                },
+            ],
+            buy_url: None,
+            datasheet_url: None,
+        };
+        rates.convert_offer(&mut offer, "gbp");
+
+        assert_eq!(offer.currency.as_deref(), Some("GBP"));
+        assert_eq!(offer.unit_price, Some(1.25));
+        assert_eq!(offer.original_currency.as_deref(), Some("EUR"));
+        assert_eq!(offer.original_unit_price, Some(2.0));
+        assert_eq!(offer.price_breaks[0].unit_price, 1.25);
+        // Breaks in a third currency would leave the offer mixed.
+        assert_eq!(offer.price_breaks.len(), 1);
+
+        // No CHF rate: left untouched.
+        let mut swiss = offer.clone();
+        swiss.currency = Some("CHF".to_string());
+        rates.convert_offer(&mut swiss, "USD");
+        assert_eq!(swiss.currency.as_deref(), Some("CHF"));
+    }
+
+    #[test]
+    fn prefers_the_distributors_own_breaks_in_the_target_currency() {
+        let price = |quantity: u64, unit_price: f64, currency: &str| PriceBreak {
+            quantity,
+            unit_price,
+            currency: Some(currency.to_string()),
+        };
+        let mut rates = ExchangeRates::default();
+        rates.import_csv("EUR,0.8\nGBP,0.5\n").unwrap();
+        // A `Prices` map listing USD and EUR: the offer is quoted in USD.
+        let offer = TrustedPartOffer {
+            distributor: "Mouser".to_string(),
+            sku: None,
+            stock: Some(100),
+            moq: None,
+            order_multiple: None,
+            currency: Some("USD".to_string()),
+            unit_price: Some(1.0),
+            original_currency: None,
+            original_unit_price: None,
+            price_breaks: vec![
+                price(1, 0.95, "EUR"),
+                price(10, 0.75, "EUR"),
+                price(1, 1.0, "USD"),
+                price(10, 0.8, "USD"),
+            ],
+            buy_url: None,
+            datasheet_url: None,
+        };
+
+        let mut euro = offer.clone();
+        rates.convert_offer(&mut euro, "EUR");
+        assert_eq!(euro.currency.as_deref(), Some("EUR"));
+        assert_eq!(euro.unit_price, Some(0.95));
+        assert_eq!(euro.original_currency, None);
+        assert_eq!(
+            euro.price_breaks,
+            [price(1, 0.95, "EUR"), price(10, 0.75, "EUR")]
+        );
+        assert_eq!(euro.quote(10).unwrap().extended_price, 7.5);
+
+        // No GBP breaks: only the USD ones are converted.
+        let mut sterling = offer;
+        rates.convert_offer(&mut sterling, "GBP");
+        assert_eq!(sterling.unit_price, Some(0.5));
+        assert_eq!(sterling.original_currency.as_deref(), Some("USD"));
+        assert_eq!(
+            sterling.price_breaks,
+            [price(1, 0.5, "GBP"), price(10, 0.4, "GBP")]
+        );
+    }
+}
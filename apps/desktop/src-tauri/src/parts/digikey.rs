@@ -0,0 +1,269 @@
+use futures_util::future::BoxFuture;
+use serde_json::{json, Value};
+
+use super::json::{get_ci, value_to_string};
+use super::trustedparts::{parse_offer, parse_part_candidate};
+use super::{
+    merge_hits, post_json, read_json, resolve_endpoint, sort_offers, PartQuery, PartsProvider,
+    ProviderCapabilities, ProviderScope, TrustedPartHit,
+};
+
+pub const CAPABILITIES: ProviderCapabilities = ProviderCapabilities {
+    id: "digikey",
+    name: "DigiKey",
+    keyword_search: true,
+    mpn_lookup: true,
+    in_stock_filter: true,
+    country_filter: true,
+    multi_distributor: false,
+};
+const API_URL: &str = "https://api.digikey.com";
+const ENDPOINT_ENV: &str = "AHA_DIGIKEY_API_URL";
+
+/// DigiKey Product Information API v4 with the OAuth2 client-credentials
+/// flow. A token is requested per call; they are valid for ten minutes and
+/// searches are infrequent.
+pub struct DigiKey {
+    client: reqwest::Client,
+    client_id: String,
+    client_secret: String,
+    endpoint: String,
+}
+
+impl DigiKey {
+    pub fn new(
+        client: reqwest::Client,
+        client_id: &str,
+        client_secret: &str,
+        endpoint: Option<&str>,
+    ) -> Result<Self, String> {
+        if client_id.trim().is_empty() {
+            return Err("DigiKey Client ID is required.".to_string());
+        }
+        if client_secret.trim().is_empty() {
+            return Err("DigiKey Client Secret is required.".to_string());
+        }
+        Ok(Self {
+            client,
+            client_id: client_id.trim().to_string(),
+            client_secret: client_secret.trim().to_string(),
+            endpoint: resolve_endpoint(endpoint, ENDPOINT_ENV, API_URL)?,
+        })
+    }
+
+    async fn access_token(&self) -> Result<String, String> {
+        let request = self
+            .client
+            .post(format!("{}/v1/oauth2/token", self.endpoint))
+            .header("Accept", "application/json")
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("grant_type", "client_credentials"),
+            ]);
+        let json = read_json("DigiKey", request).await?;
+        json.get("access_token")
+            .and_then(value_to_string)
+            .ok_or_else(|| "DigiKey token response has no access_token.".to_string())
+    }
+
+    fn locale_headers(query: &PartQuery) -> [(&'static str, String); 2] {
+        [
+            ("X-DIGIKEY-Locale-Site", query.country_code()),
+            (
+                "X-DIGIKEY-Locale-Currency",
+                currency_for(&query.country_code()).to_string(),
+            ),
+        ]
+    }
+}
+
+impl PartsProvider for DigiKey {
+    fn capabilities(&self) -> ProviderCapabilities {
+        CAPABILITIES
+    }
+
+    fn scope(&self) -> ProviderScope {
+        ProviderScope::new(&self.endpoint, &self.client_id)
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(async move {
+            let token = self.access_token().await?;
+            let bearer = format!("Bearer {}", token);
+            let locale = Self::locale_headers(query);
+            let mut payload = json!({
+                "Keywords": query.search_token.trim(),
+                "Limit": query.max_results().min(50),
+                "Offset": 0,
+            });
+            if query.in_stock_only.unwrap_or(true) {
+                payload["FilterOptionsRequest"] = json!({ "SearchOptions": ["InStock"] });
+            }
+            let json = post_json(
+                &self.client,
+                "DigiKey",
+                &format!("{}/products/v4/search/keyword", self.endpoint),
+                &payload,
+                &[
+                    ("Authorization", bearer.as_str()),
+                    ("X-DIGIKEY-Client-Id", self.client_id.as_str()),
+                    (locale[0].0, locale[0].1.as_str()),
+                    (locale[1].0, locale[1].1.as_str()),
+                ],
+            )
+            .await?;
+            Ok(parse_products(json.get("Products")))
+        })
+    }
+
+    fn lookup_mpn<'a>(
+        &'a self,
+        mpn: &'a str,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(async move {
+            let token = self.access_token().await?;
+            let mut request = self
+                .client
+                .get(format!(
+                    "{}/products/v4/search/{}/productdetails",
+                    self.endpoint,
+                    urlencode(mpn.trim())
+                ))
+                .header("Accept", "application/json")
+                .bearer_auth(token)
+                .header("X-DIGIKEY-Client-Id", &self.client_id);
+            for (name, value) in Self::locale_headers(query) {
+                request = request.header(name, value);
+            }
+            let json = read_json("DigiKey", request).await?;
+            let mut hits =
+                parse_products(json.get("Product").map(|product| json!([product])).as_ref());
+            hits.retain(|hit| hit.mpn.eq_ignore_ascii_case(mpn.trim()));
+            Ok(hits)
+        })
+    }
+}
+
+/// Each packaging variation (cut tape, reel, ...) becomes its own offer.
+fn parse_products(products: Option<&Value>) -> Vec<TrustedPartHit> {
+    let hits = products
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .filter_map(|product| {
+            let mut hit = parse_part_candidate(product, Some("DigiKey"))?;
+            let product_url = get_ci(product, &["ProductUrl"]).and_then(value_to_string);
+            let datasheet = get_ci(product, &["DatasheetUrl"]).and_then(value_to_string);
+            let variations: Vec<_> = get_ci(product, &["ProductVariations"])
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_object)
+                .filter_map(|variation| parse_offer(variation, datasheet.clone()))
+                .map(|mut offer| {
+                    offer.distributor = "DigiKey".to_string();
+                    if offer.buy_url.is_none() {
+                        offer.buy_url = product_url.clone();
+                    }
+                    offer
+                })
+                .collect();
+            if !variations.is_empty() {
+                hit.offers = variations;
+                sort_offers(&mut hit.offers);
+            }
+            Some(hit)
+        });
+    merge_hits(hits)
+}
+
+fn currency_for(country_code: &str) -> &'static str {
+    match country_code {
+        "US" => "USD",
+        "CA" => "CAD",
+        "GB" | "UK" => "GBP",
+        "JP" => "JPY",
+        "CN" => "CNY",
+        "AU" => "AUD",
+        "CH" => "CHF",
+        "DE" | "FR" | "IT" | "ES" | "NL" | "BE" | "AT" | "IE" | "FI" | "PT" => "EUR",
+        _ => "USD",
+    }
+}
+
+fn urlencode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_packaging_variation_becomes_an_offer() {
+        let hits = parse_products(Some(&json!([{
+            "ManufacturerProductNumber": "STM32F405RGT6",
+            "Manufacturer": {"Id": 497, "Name": "STMicroelectronics"},
+            "Description": {
+                "ProductDescription": "IC MCU 32BIT 1MB FLASH 64LQFP",
+                "DetailedDescription": "ARM Cortex-M4 STM32F4 Microcontroller IC"
+            },
+            "QuantityAvailable": 3120,
+            "ProductUrl": "https://www.digikey.com/en/products/detail/stm32f405rgt6/2747117",
+            "DatasheetUrl": "https://www.st.com/resource/en/datasheet/stm32f405rg.pdf",
+            "ProductStatus": {"Id": 0, "Status": "Active"},
+            "ProductVariations": [
+                {
+                    "DigiKeyProductNumber": "497-11767-ND",
+                    "QuantityAvailableforPackageType": 3120,
+                    "MinimumOrderQuantity": 1,
+                    "StandardPricing": [{"BreakQuantity": 1, "UnitPrice": 11.53, "TotalPrice": 11.53}]
+                },
+                {
+                    "DigiKeyProductNumber": "497-STM32F405RGT6TR-ND",
+                    "QuantityAvailableforPackageType": 0,
+                    "MinimumOrderQuantity": 960,
+                    "StandardPricing": [{"BreakQuantity": 960, "UnitPrice": 7.41, "TotalPrice": 7113.6}]
+                }
+            ]
+        }])));
+
+        assert_eq!(hits.len(), 1);
+        let hit = &hits[0];
+        assert_eq!(hit.manufacturer.as_deref(), Some("STMicroelectronics"));
+        assert_eq!(
+            hit.description.as_deref(),
+            Some("IC MCU 32BIT 1MB FLASH 64LQFP")
+        );
+        assert_eq!(hit.lifecycle_status.as_deref(), Some("Active"));
+        assert_eq!(hit.offers.len(), 2);
+        assert_eq!(hit.offers[0].sku.as_deref(), Some("497-11767-ND"));
+        assert_eq!(hit.offers[0].stock, Some(3120));
+        assert_eq!(hit.offers[1].moq, Some(960));
+        assert_eq!(hit.offers[1].unit_price, Some(7.41));
+        assert!(hit.offers[1]
+            .buy_url
+            .as_deref()
+            .unwrap()
+            .contains("digikey.com"));
+    }
+
+    #[test]
+    fn encodes_part_numbers_for_the_path() {
+        assert_eq!(urlencode("LM317T/NOPB"), "LM317T%2FNOPB");
+    }
+}
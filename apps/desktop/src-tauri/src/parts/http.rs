@@ -0,0 +1,375 @@
+//! Outbound HTTP shared by every parts provider: a token-bucket rate limit
+//! per provider, retries with exponential backoff and jitter for throttling
+//! and transient failures, and one request for identical queries that are
+//! already in flight.
+
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Attempts per request, the first included.
+const MAX_ATTEMPTS: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+/// Longer `Retry-After` waits are reported instead of slept through, so a
+/// search never hangs for minutes.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Requests per second and burst size, from each provider's published
+/// quota where there is one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub per_second: f64,
+    pub burst: f64,
+}
+
+const DEFAULT_LIMIT: RateLimit = RateLimit {
+    per_second: 5.0,
+    burst: 5.0,
+};
+
+const PROVIDER_LIMITS: &[(&str, RateLimit)] = &[
+    (
+        "TrustedParts",
+        RateLimit {
+            per_second: 5.0,
+            burst: 10.0,
+        },
+    ),
+    // 30 calls a minute.
+    (
+        "Mouser",
+        RateLimit {
+            per_second: 0.5,
+            burst: 5.0,
+        },
+    ),
+    // 120 calls a minute.
+    (
+        "DigiKey",
+        RateLimit {
+            per_second: 2.0,
+            burst: 10.0,
+        },
+    ),
+];
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+type InFlight = Shared<BoxFuture<'static, Result<HttpResponse, String>>>;
+
+fn in_flight() -> &'static Mutex<HashMap<String, InFlight>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashMap<String, InFlight>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(Default::default)
+}
+
+fn buckets() -> &'static Mutex<HashMap<String, TokenBucket>> {
+    static BUCKETS: OnceLock<Mutex<HashMap<String, TokenBucket>>> = OnceLock::new();
+    BUCKETS.get_or_init(Default::default)
+}
+
+/// Sends `request` on behalf of `provider`. Identical requests (method, URL,
+/// headers and body) that are already in flight share its response.
+pub async fn send(
+    provider: &str,
+    request: reqwest::RequestBuilder,
+) -> Result<HttpResponse, String> {
+    let (client, request) = request.build_split();
+    let request =
+        request.map_err(|e| format!("{} API request failed: {}", provider, e.without_url()))?;
+    let key = request_key(provider, &request);
+
+    let shared = {
+        let mut requests = in_flight()
+            .lock()
+            .map_err(|_| "HTTP request table is poisoned.".to_string())?;
+        requests
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let provider = provider.to_string();
+                async move {
+                    let response = execute(client, provider, request).await;
+                    // Whoever polls the request to completion retires it, so
+                    // a dropped first caller can't leave a finished response
+                    // behind for later requests.
+                    if let Ok(mut in_flight) = in_flight().lock() {
+                        in_flight.remove(&key);
+                    }
+                    response
+                }
+                .boxed()
+                .shared()
+            })
+            .clone()
+    };
+    shared.await
+}
+
+async fn execute(
+    client: reqwest::Client,
+    provider: String,
+    request: reqwest::Request,
+) -> Result<HttpResponse, String> {
+    let mut attempt = 1;
+    loop {
+        let wait = reserve(&provider, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        // JSON and form bodies are plain bytes, so this only fails for
+        // streams, which no provider sends.
+        let current = request
+            .try_clone()
+            .ok_or_else(|| format!("{} API request failed: body can't be resent.", provider))?;
+        let last = attempt >= MAX_ATTEMPTS;
+
+        let delay = match client.execute(current).await {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after);
+                if !is_retryable(status) || last {
+                    let body = response.text().await.map_err(|e| {
+                        format!(
+                            "Failed to read {} API response body: {}",
+                            provider,
+                            e.without_url()
+                        )
+                    })?;
+                    return Ok(HttpResponse { status, body });
+                }
+                match retry_after {
+                    Some(wait) if wait > MAX_RETRY_AFTER => {
+                        return Err(format!(
+                            "{} API is rate limited; retry after {} s.",
+                            provider,
+                            wait.as_secs()
+                        ));
+                    }
+                    Some(wait) => wait,
+                    None => backoff(attempt),
+                }
+            }
+            Err(error) if !last && (error.is_connect() || error.is_timeout()) => backoff(attempt),
+            // Without the URL: Mouser authenticates in the query string.
+            Err(error) => {
+                return Err(format!(
+                    "{} API request failed: {}",
+                    provider,
+                    error.without_url()
+                ))
+            }
+        };
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Throttling and transient server errors; anything else is final.
+fn is_retryable(status: u16) -> bool {
+    matches!(status, 429 | 502 | 503 | 504)
+}
+
+/// `BASE_BACKOFF * 2^(attempt-1)`, capped, scaled by a random 50–100% so
+/// clients that were throttled together don't come back together.
+fn backoff(attempt: u32) -> Duration {
+    let ceiling = BASE_BACKOFF
+        .saturating_mul(1 << (attempt - 1).min(16))
+        .min(MAX_BACKOFF);
+    ceiling.mul_f64(0.5 + jitter() / 2.0)
+}
+
+/// Uniform-ish in [0, 1) without pulling in an RNG crate.
+fn jitter() -> f64 {
+    // Every `RandomState` is seeded differently; the clock adds more.
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Delta-seconds form only; HTTP dates fall back to backoff.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+fn request_key(provider: &str, request: &reqwest::Request) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(provider.as_bytes());
+    hasher.update(request.method().as_str().as_bytes());
+    hasher.update(request.url().as_str().as_bytes());
+    let mut headers: Vec<_> = request.headers().iter().collect();
+    headers.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+    for (name, value) in headers {
+        hasher.update(name.as_str().as_bytes());
+        hasher.update(value.as_bytes());
+    }
+    if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+        hasher.update(body);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn rate_limit(provider: &str) -> RateLimit {
+    PROVIDER_LIMITS
+        .iter()
+        .find(|(name, _)| *name == provider)
+        .map(|(_, limit)| *limit)
+        .unwrap_or(DEFAULT_LIMIT)
+}
+
+/// Takes a token from `provider`'s bucket and returns how long to wait
+/// before using it.
+fn reserve(provider: &str, now: Instant) -> Duration {
+    let Ok(mut buckets) = buckets().lock() else {
+        return Duration::ZERO;
+    };
+    buckets
+        .entry(provider.to_string())
+        .or_insert_with(|| TokenBucket::new(rate_limit(provider), now))
+        .reserve(now)
+}
+
+/// Tokens may go negative: each caller reserves the next free slot, so
+/// waiters are served in order without polling.
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst,
+            updated: now,
+        }
+    }
+
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.per_second).min(self.limit.burst);
+        self.updated = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.limit.per_second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_spaces_requests_after_the_burst() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(
+            RateLimit {
+                per_second: 2.0,
+                burst: 2.0,
+            },
+            start,
+        );
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(500));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(1000));
+        // Two seconds later the queue has drained and one token is back.
+        assert_eq!(
+            bucket.reserve(start + Duration::from_secs(2)),
+            Duration::ZERO
+        );
+
+        assert_eq!(parse_retry_after(" 2 "), Some(Duration::from_secs(2)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2026 07:28:00 GMT"), None);
+        for attempt in 1..=6 {
+            let delay = backoff(attempt);
+            let ceiling = (BASE_BACKOFF * (1 << (attempt - 1))).min(MAX_BACKOFF);
+            assert!(delay >= ceiling / 2 && delay <= ceiling, "{delay:?}");
+        }
+    }
+
+    #[test]
+    fn connection_errors_do_not_echo_the_url() {
+        let request = reqwest::Client::new().post("http://127.0.0.1:1/search?apiKey=leaky-secret");
+        let error = tauri::async_runtime::block_on(send("LeakTest", request)).unwrap_err();
+        assert!(error.starts_with("LeakTest API request failed"), "{error}");
+        assert!(!error.contains("leaky-secret"), "{error}");
+    }
+
+    #[test]
+    fn dropped_first_caller_does_not_pin_its_response() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/search", listener.local_addr().unwrap());
+        let served = Arc::new(AtomicUsize::new(0));
+        let counter = served.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut request = Vec::new();
+                let mut chunk = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut chunk) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => request.extend_from_slice(&chunk[..read]),
+                    }
+                }
+                let count = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                std::thread::sleep(Duration::from_millis(200));
+                let body = count.to_string();
+                let _ = stream.write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                    .as_bytes(),
+                );
+            }
+        });
+
+        let get = || send("InFlightTest", reqwest::Client::new().get(&url));
+        tauri::async_runtime::block_on(async {
+            // The first caller gives up while its request is in flight.
+            let abandoned = tokio::time::timeout(Duration::from_millis(50), get()).await;
+            assert!(abandoned.is_err());
+
+            // A caller arriving meanwhile still shares that request...
+            assert_eq!(get().await.unwrap().body, "1");
+            // ...but once it has finished, the next one goes out again.
+            assert_eq!(get().await.unwrap().body, "2");
+        });
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,77 @@
+//! Lenient accessors for distributor JSON, whose field names and value
+//! types vary between APIs and even between endpoints of the same API.
+
+use serde_json::{Map, Value};
+
+pub fn get_ci<'a>(object: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    object.iter().find_map(|(key, value)| {
+        if keys
+            .iter()
+            .any(|candidate| key.eq_ignore_ascii_case(candidate))
+        {
+            Some(value)
+        } else {
+            None
+        }
+    })
+}
+
+pub fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => {
+            let trimmed = text.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        }
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        Value::Object(object) => get_ci(
+            object,
+            &[
+                "Name",
+                "name",
+                "CompanyName",
+                "DisplayName",
+                "Manufacturer",
+                "Label",
+            ],
+        )
+        .and_then(value_to_string),
+        _ => None,
+    }
+}
+
+pub fn value_to_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(text) => {
+            let cleaned: String = text.chars().filter(|ch| ch.is_ascii_digit()).collect();
+            cleaned.parse::<u64>().ok()
+        }
+        _ => None,
+    }
+}
+
+pub fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => {
+            let cleaned: String = text
+                .chars()
+                .filter(|ch| ch.is_ascii_digit() || *ch == '.' || *ch == '-')
+                .collect();
+            cleaned.parse::<f64>().ok()
+        }
+        _ => None,
+    }
+}
+
+/// First message-like field of a JSON error body.
+pub fn extract_api_error_message(body: &str) -> Option<String> {
+    let parsed: Value = serde_json::from_str(body).ok()?;
+    let object = parsed.as_object()?;
+    get_ci(object, &["message", "error", "detail", "details"]).and_then(value_to_string)
+}
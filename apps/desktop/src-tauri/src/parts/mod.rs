@@ -0,0 +1,720 @@
+//! Component sourcing across distributor APIs.
+//!
+//! Every backend implements [`PartsProvider`] and normalizes its response into
+//! [`TrustedPartHit`]s (the TrustedParts shape predates the other providers
+//! and is what the frontend consumes), so results from several providers can
+//! be merged per manufacturer part number.
+
+mod alternates;
+mod attributes;
+mod cache;
+mod category;
+mod currency;
+mod digikey;
+mod http;
+mod json;
+mod mouser;
+mod pricing;
+mod trustedparts;
+
+use futures_util::future::{join_all, BoxFuture};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub use alternates::{alternates_search_token, rank_alternates, AlternatesReport};
+pub use attributes::{AttributeValue, PartAttributes};
+pub use cache::{CachedProvider, PartSearchCache};
+pub use category::{CategoryGuess, Taxonomy};
+pub use currency::ExchangeRates;
+pub use pricing::{rank_hits, OfferQuote, PriceBreak};
+pub use trustedparts::TrustedParts;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const DEFAULT_MAX_RESULTS: usize = 20;
+const MAX_RESULTS_LIMIT: usize = 100;
+
+/// Provider-neutral search parameters.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartQuery {
+    /// Unused by MPN lookups.
+    #[serde(default)]
+    pub search_token: String,
+    #[serde(default)]
+    pub country_code: Option<String>,
+    #[serde(default)]
+    pub exact_match: Option<bool>,
+    #[serde(default)]
+    pub in_stock_only: Option<bool>,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// Skip cached results and ask the provider again.
+    #[serde(default)]
+    pub refresh: Option<bool>,
+    /// Convert offer prices into this currency (ISO 4217).
+    #[serde(default)]
+    pub reporting_currency: Option<String>,
+    /// How to order offers and hits; defaults to [`Ranking::Stock`].
+    #[serde(default)]
+    pub ranking: Option<Ranking>,
+    /// Build quantity [`Ranking::LandedCost`] prices offers at.
+    #[serde(default)]
+    pub quantity: Option<u64>,
+}
+
+/// Order of search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Ranking {
+    /// Largest distributor stock first.
+    #[default]
+    Stock,
+    /// Cheapest total for the build quantity first, after MOQ, order
+    /// multiples and price breaks; offers that can't fill the order from
+    /// stock go last.
+    LandedCost,
+}
+
+impl PartQuery {
+    pub fn country_code(&self) -> String {
+        self.country_code
+            .as_deref()
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+            .unwrap_or("US")
+            .to_uppercase()
+    }
+
+    pub fn max_results(&self) -> usize {
+        self.max_results
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT)
+    }
+
+    pub fn quantity(&self) -> u64 {
+        self.quantity.unwrap_or(1).max(1)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPartOffer {
+    pub distributor: String,
+    pub sku: Option<String>,
+    pub stock: Option<u64>,
+    pub moq: Option<u64>,
+    /// Orders must be a whole number of this many pieces (reels, trays).
+    #[serde(default)]
+    pub order_multiple: Option<u64>,
+    /// Currency of `unit_price`.
+    pub currency: Option<String>,
+    /// Price at the smallest break in `currency`.
+    pub unit_price: Option<f64>,
+    /// What the distributor quoted, when prices were converted into a
+    /// reporting currency.
+    #[serde(default)]
+    pub original_currency: Option<String>,
+    #[serde(default)]
+    pub original_unit_price: Option<f64>,
+    /// Every quantity break, possibly in several currencies.
+    #[serde(default)]
+    pub price_breaks: Vec<PriceBreak>,
+    pub buy_url: Option<String>,
+    pub datasheet_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPartHit {
+    pub mpn: String,
+    pub manufacturer: Option<String>,
+    pub description: Option<String>,
+    pub lifecycle_status: Option<String>,
+    pub category_hint: String,
+    /// How sure the classifier is of `category_hint`, 0.0–1.0.
+    #[serde(default)]
+    pub category_confidence: f64,
+    /// Parameters parsed from `description`, keyed as in [`attributes`].
+    #[serde(default)]
+    pub attributes: PartAttributes,
+    pub offers: Vec<TrustedPartOffer>,
+    /// Served from the local search cache rather than a live request.
+    #[serde(default)]
+    pub from_cache: bool,
+    /// Seconds since a cached hit was fetched.
+    #[serde(default)]
+    pub cache_age_seconds: Option<u64>,
+}
+
+/// What a provider supports, so the frontend can hide options it would
+/// ignore.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCapabilities {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub keyword_search: bool,
+    pub mpn_lookup: bool,
+    pub in_stock_filter: bool,
+    pub country_filter: bool,
+    /// Returns offers from several distributors rather than its own stock.
+    pub multi_distributor: bool,
+}
+
+/// The resolved endpoint of a provider and a digest of the account it
+/// queries as (the digest keeps secrets out of the search cache).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderScope {
+    pub endpoint: String,
+    pub account: String,
+}
+
+impl ProviderScope {
+    pub fn new(endpoint: &str, account: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            account: format!("{:x}", Sha256::digest(account.as_bytes())),
+        }
+    }
+}
+
+/// A sourcing backend. Futures are boxed so providers can be used as trait
+/// objects in an aggregated search.
+pub trait PartsProvider: Send + Sync {
+    fn capabilities(&self) -> ProviderCapabilities;
+
+    /// Where requests go and as whom, so cached responses are never shared
+    /// between endpoints or accounts.
+    fn scope(&self) -> ProviderScope;
+
+    /// Keyword or partial part number search.
+    fn search<'a>(
+        &'a self,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>>;
+
+    /// Exact manufacturer part number lookup; `query` supplies the country
+    /// and stock filters.
+    fn lookup_mpn<'a>(
+        &'a self,
+        mpn: &'a str,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>>;
+}
+
+/// Provider selection and credentials as sent by the frontend. Secrets can
+/// instead come from the vault: `credential` names a stored credential whose
+/// fields (`companyId`, `apiKey`, `clientId`, `clientSecret`) fill in what
+/// was left empty. With a credential, `endpoint` is only taken from the
+/// credential itself, so the frontend can't send stored secrets elsewhere.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "provider", rename_all = "camelCase")]
+pub enum ProviderSettings {
+    #[serde(rename_all = "camelCase")]
+    TrustedParts {
+        #[serde(default)]
+        company_id: String,
+        #[serde(default)]
+        api_key: String,
+        #[serde(default)]
+        credential: Option<String>,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Mouser {
+        #[serde(default)]
+        api_key: String,
+        #[serde(default)]
+        credential: Option<String>,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    DigiKey {
+        #[serde(default)]
+        client_id: String,
+        #[serde(default)]
+        client_secret: String,
+        #[serde(default)]
+        credential: Option<String>,
+        #[serde(default)]
+        endpoint: Option<String>,
+    },
+}
+
+impl ProviderSettings {
+    pub fn credential(&self) -> Option<&str> {
+        match self {
+            Self::TrustedParts { credential, .. }
+            | Self::Mouser { credential, .. }
+            | Self::DigiKey { credential, .. } => credential.as_deref(),
+        }
+    }
+
+    /// These settings with empty secrets and the endpoint taken from a
+    /// stored credential.
+    pub fn with_credential(mut self, fields: &BTreeMap<String, String>) -> Self {
+        match &mut self {
+            Self::TrustedParts {
+                company_id,
+                api_key,
+                endpoint,
+                ..
+            } => {
+                fill_secret(company_id, fields, "companyId");
+                fill_secret(api_key, fields, "apiKey");
+                *endpoint = fields.get("endpoint").cloned();
+            }
+            Self::Mouser {
+                api_key, endpoint, ..
+            } => {
+                fill_secret(api_key, fields, "apiKey");
+                *endpoint = fields.get("endpoint").cloned();
+            }
+            Self::DigiKey {
+                client_id,
+                client_secret,
+                endpoint,
+                ..
+            } => {
+                fill_secret(client_id, fields, "clientId");
+                fill_secret(client_secret, fields, "clientSecret");
+                *endpoint = fields.get("endpoint").cloned();
+            }
+        }
+        self
+    }
+
+    pub fn build(&self, client: reqwest::Client) -> Result<Arc<dyn PartsProvider>, String> {
+        Ok(match self {
+            Self::TrustedParts {
+                company_id,
+                api_key,
+                endpoint,
+                ..
+            } => Arc::new(TrustedParts::new(
+                client,
+                company_id,
+                api_key,
+                endpoint.as_deref(),
+            )?),
+            Self::Mouser {
+                api_key, endpoint, ..
+            } => Arc::new(mouser::Mouser::new(client, api_key, endpoint.as_deref())?),
+            Self::DigiKey {
+                client_id,
+                client_secret,
+                endpoint,
+                ..
+            } => Arc::new(digikey::DigiKey::new(
+                client,
+                client_id,
+                client_secret,
+                endpoint.as_deref(),
+            )?),
+        })
+    }
+}
+
+/// Sets `value` from `fields[field]` unless the caller already sent one.
+pub fn fill_secret(value: &mut String, fields: &BTreeMap<String, String>, field: &str) {
+    if value.trim().is_empty() {
+        if let Some(secret) = fields.get(field) {
+            *value = secret.clone();
+        }
+    }
+}
+
+/// Capabilities of every provider the app can talk to.
+pub fn available_providers() -> Vec<ProviderCapabilities> {
+    vec![
+        trustedparts::CAPABILITIES,
+        mouser::CAPABILITIES,
+        digikey::CAPABILITIES,
+    ]
+}
+
+/// Base URL for a provider: the explicit setting, then the environment
+/// variable (for a corporate proxy or a local stub), then the public API.
+/// Credentials go out over https only; plain http is allowed for loopback
+/// stubs.
+fn resolve_endpoint(
+    configured: Option<&str>,
+    env_key: &str,
+    default: &str,
+) -> Result<String, String> {
+    let from_env = std::env::var(env_key).ok();
+    let endpoint = [configured, from_env.as_deref()]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|endpoint| !endpoint.is_empty())
+        .unwrap_or(default);
+    let secure = match endpoint.strip_prefix("http://") {
+        Some(rest) => is_loopback(rest),
+        None => endpoint.starts_with("https://"),
+    };
+    if !secure {
+        return Err(format!(
+            "Parts API endpoint must be an https URL: {}",
+            endpoint
+        ));
+    }
+    Ok(endpoint.trim_end_matches('/').to_string())
+}
+
+/// Whether the authority at the start of `rest` is this machine.
+fn is_loopback(rest: &str) -> bool {
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if authority.contains('@') {
+        return false;
+    }
+    let host = match authority.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+pub fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to initialize HTTP client: {}", e))
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderOutcome {
+    pub provider: &'static str,
+    pub hit_count: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregatedSearch {
+    pub hits: Vec<TrustedPartHit>,
+    pub providers: Vec<ProviderOutcome>,
+}
+
+/// One part number of a batch lookup. `hits` is empty both when the part
+/// wasn't found and when `error` is set.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MpnLookupResult {
+    pub mpn: String,
+    pub hits: Vec<TrustedPartHit>,
+    pub error: Option<String>,
+}
+
+/// What to ask each provider in an aggregated search.
+pub enum SearchKind<'a> {
+    Keyword,
+    Mpn(&'a str),
+}
+
+/// Queries all providers concurrently and merges their hits per part. A
+/// failing provider is reported in its outcome; the call only fails when
+/// every provider did.
+pub async fn aggregate(
+    providers: &[Arc<dyn PartsProvider>],
+    query: &PartQuery,
+    kind: SearchKind<'_>,
+    rates: Option<&ExchangeRates>,
+) -> Result<AggregatedSearch, String> {
+    if providers.is_empty() {
+        return Err("No parts provider is configured.".to_string());
+    }
+
+    let responses = join_all(providers.iter().map(|provider| match kind {
+        SearchKind::Keyword => provider.search(query),
+        SearchKind::Mpn(mpn) => provider.lookup_mpn(mpn, query),
+    }))
+    .await;
+
+    let mut outcomes = Vec::new();
+    let mut hits = Vec::new();
+    let mut errors = Vec::new();
+    for (provider, response) in providers.iter().zip(responses) {
+        let id = provider.capabilities().id;
+        match response {
+            Ok(found) => {
+                outcomes.push(ProviderOutcome {
+                    provider: id,
+                    hit_count: found.len(),
+                    error: None,
+                });
+                hits.extend(found);
+            }
+            Err(error) => {
+                errors.push(format!("{}: {}", provider.capabilities().name, error));
+                outcomes.push(ProviderOutcome {
+                    provider: id,
+                    hit_count: 0,
+                    error: Some(error),
+                });
+            }
+        }
+    }
+    if errors.len() == providers.len() {
+        return Err(errors.join("\n"));
+    }
+
+    let mut hits = merge_hits(hits);
+    select_hits(&mut hits, query, rates);
+    Ok(AggregatedSearch {
+        hits,
+        providers: outcomes,
+    })
+}
+
+/// Converts prices into the query's reporting currency with `rates`, ranks
+/// and keeps the first `max_results`. Conversion comes first so landed costs
+/// are compared in one currency before anything is dropped.
+pub fn select_hits(
+    hits: &mut Vec<TrustedPartHit>,
+    query: &PartQuery,
+    rates: Option<&ExchangeRates>,
+) {
+    if let (Some(rates), Some(currency)) = (rates, &query.reporting_currency) {
+        rates.convert_hits(hits, currency);
+    }
+    rank_hits(hits, query);
+    hits.truncate(query.max_results());
+}
+
+/// Re-classifies hits with `taxonomy`, e.g. one carrying user extensions.
+pub fn classify_hits(hits: &mut [TrustedPartHit], taxonomy: &Taxonomy) {
+    for hit in hits {
+        let guess = taxonomy.classify(&hit.mpn, hit.description.as_deref());
+        hit.category_hint = guess.category;
+        hit.category_confidence = guess.confidence;
+    }
+}
+
+/// Merges hits that describe the same manufacturer part, combining their
+/// offers, and orders the result by best stock.
+pub fn merge_hits(hits: impl IntoIterator<Item = TrustedPartHit>) -> Vec<TrustedPartHit> {
+    let mut merged: BTreeMap<String, TrustedPartHit> = BTreeMap::new();
+    for part in hits {
+        let key = format!(
+            "{}::{}",
+            part.manufacturer
+                .as_deref()
+                .unwrap_or_default()
+                .to_lowercase(),
+            part.mpn.to_lowercase()
+        );
+
+        merged
+            .entry(key)
+            .and_modify(|existing| {
+                merge_offers(existing, &part);
+                if existing.description.is_none() {
+                    existing.description = part.description.clone();
+                }
+                if existing.lifecycle_status.is_none() {
+                    existing.lifecycle_status = part.lifecycle_status.clone();
+                }
+                for (key, value) in &part.attributes {
+                    existing
+                        .attributes
+                        .entry(key.clone())
+                        .or_insert_with(|| value.clone());
+                }
+            })
+            .or_insert(part);
+    }
+
+    let mut results: Vec<TrustedPartHit> = merged.into_values().collect();
+    results.sort_by(|a, b| {
+        let a_stock = a
+            .offers
+            .iter()
+            .filter_map(|offer| offer.stock)
+            .max()
+            .unwrap_or(0);
+        let b_stock = b
+            .offers
+            .iter()
+            .filter_map(|offer| offer.stock)
+            .max()
+            .unwrap_or(0);
+        b_stock.cmp(&a_stock).then(a.mpn.cmp(&b.mpn))
+    });
+
+    results
+}
+
+fn merge_offers(existing: &mut TrustedPartHit, incoming: &TrustedPartHit) {
+    let offer_key = |offer: &TrustedPartOffer| {
+        format!(
+            "{}::{}::{}",
+            offer.distributor.to_lowercase(),
+            offer.sku.as_deref().unwrap_or_default().to_lowercase(),
+            offer.unit_price.unwrap_or(-1.0)
+        )
+    };
+    let mut known: BTreeSet<String> = existing.offers.iter().map(offer_key).collect();
+
+    for offer in &incoming.offers {
+        if known.insert(offer_key(offer)) {
+            existing.offers.push(offer.clone());
+        }
+    }
+
+    sort_offers(&mut existing.offers);
+}
+
+fn sort_offers(offers: &mut [TrustedPartOffer]) {
+    offers.sort_by(|a, b| {
+        b.stock
+            .unwrap_or(0)
+            .cmp(&a.stock.unwrap_or(0))
+            .then_with(|| a.distributor.cmp(&b.distributor))
+    });
+}
+
+/// POSTs `payload` as JSON and parses the response, turning HTTP failures
+/// into the API's own error message where it sends one.
+async fn post_json(
+    client: &reqwest::Client,
+    provider: &str,
+    url: &str,
+    payload: &Value,
+    headers: &[(&str, &str)],
+) -> Result<Value, String> {
+    let mut request = client
+        .post(url)
+        .header("Accept", "application/json")
+        .json(payload);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    read_json(provider, request).await
+}
+
+/// Sends through the shared [`http`] layer, which rate-limits and retries.
+async fn read_json(provider: &str, request: reqwest::RequestBuilder) -> Result<Value, String> {
+    let response = http::send(provider, request).await?;
+    if !(200..300).contains(&response.status) {
+        let details = json::extract_api_error_message(&response.body)
+            .unwrap_or_else(|| format!("HTTP {} returned by {} API.", response.status, provider));
+        return Err(details);
+    }
+
+    serde_json::from_str(&response.body)
+        .map_err(|e| format!("{} API returned invalid JSON: {}", provider, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(manufacturer: &str, mpn: &str, distributor: &str, stock: u64) -> TrustedPartHit {
+        TrustedPartHit {
+            mpn: mpn.to_string(),
+            manufacturer: Some(manufacturer.to_string()),
+            description: None,
+            lifecycle_status: None,
+            category_hint: "Component".to_string(),
+            category_confidence: 0.0,
+            attributes: PartAttributes::new(),
+            offers: vec![TrustedPartOffer {
+                distributor: distributor.to_string(),
+                sku: Some(format!("{}-{}", distributor, mpn)),
+                stock: Some(stock),
+                moq: None,
+                order_multiple: None,
+                currency: Some("USD".to_string()),
+                unit_price: Some(1.0),
+                original_currency: None,
+                original_unit_price: None,
+                price_breaks: Vec::new(),
+                buy_url: None,
+                datasheet_url: None,
+            }],
+            from_cache: false,
+            cache_age_seconds: None,
+        }
+    }
+
+    #[test]
+    fn merges_offers_for_the_same_part_across_providers() {
+        let merged = merge_hits(vec![
+            hit("Texas Instruments", "TPS62130", "Mouser", 50),
+            hit("ST", "STM32F405", "DigiKey", 10),
+            hit("texas instruments", "tps62130", "DigiKey", 900),
+            hit("Texas Instruments", "TPS62130", "Mouser", 50),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].mpn, "TPS62130");
+        let distributors: Vec<&str> = merged[0]
+            .offers
+            .iter()
+            .map(|offer| offer.distributor.as_str())
+            .collect();
+        assert_eq!(distributors, vec!["DigiKey", "Mouser"]);
+    }
+
+    #[test]
+    fn stored_credentials_only_go_to_their_own_endpoint() {
+        let settings: ProviderSettings = serde_json::from_value(serde_json::json!({
+            "provider": "mouser",
+            "credential": "mouser",
+            "endpoint": "https://attacker.example.com",
+        }))
+        .unwrap();
+        let fields = BTreeMap::from([("apiKey".to_string(), "secret".to_string())]);
+        let ProviderSettings::Mouser { endpoint, .. } = settings.with_credential(&fields) else {
+            unreachable!();
+        };
+        assert_eq!(endpoint, None);
+
+        let resolve = |endpoint: &str| resolve_endpoint(Some(endpoint), "AHA_UNSET_ENDPOINT", "");
+        assert!(resolve("https://proxy.example.com/").is_ok());
+        assert!(resolve("http://127.0.0.1:8080/v2").is_ok());
+        assert!(resolve("http://[::1]:8080").is_ok());
+        assert!(resolve("http://localhost").is_ok());
+        assert!(resolve("http://parts.example.com").is_err());
+        assert!(resolve("http://127.0.0.1@parts.example.com").is_err());
+    }
+
+    #[test]
+    fn converts_prices_before_ranking_and_truncating() {
+        let mut imported = hit("Rohm", "BD9E301", "Chip1Stop", 500);
+        imported.offers[0].currency = Some("JPY".to_string());
+        imported.offers[0].unit_price = Some(100.0);
+        let mut hits = vec![
+            hit("Texas Instruments", "TPS62130", "Mouser", 500),
+            imported,
+        ];
+        let rates = ExchangeRates {
+            rates: BTreeMap::from([("USD".to_string(), 1.0), ("JPY".to_string(), 150.0)]),
+            ..ExchangeRates::default()
+        };
+        let query = PartQuery {
+            max_results: Some(1),
+            reporting_currency: Some("USD".to_string()),
+            ranking: Some(Ranking::LandedCost),
+            quantity: Some(10),
+            ..PartQuery::default()
+        };
+
+        select_hits(&mut hits, &query, Some(&rates));
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].mpn, "BD9E301");
+        assert_eq!(hits[0].offers[0].currency.as_deref(), Some("USD"));
+    }
+}
@@ -0,0 +1,182 @@
+use futures_util::future::BoxFuture;
+use serde_json::{json, Value};
+
+use super::json::{get_ci, value_to_string};
+use super::trustedparts::parse_part_candidate;
+use super::{
+    merge_hits, post_json, resolve_endpoint, PartQuery, PartsProvider, ProviderCapabilities,
+    ProviderScope, TrustedPartHit,
+};
+
+pub const CAPABILITIES: ProviderCapabilities = ProviderCapabilities {
+    id: "mouser",
+    name: "Mouser",
+    keyword_search: true,
+    mpn_lookup: true,
+    in_stock_filter: true,
+    country_filter: false,
+    multi_distributor: false,
+};
+const API_URL: &str = "https://api.mouser.com/api/v1";
+const ENDPOINT_ENV: &str = "AHA_MOUSER_API_URL";
+
+/// Mouser Search API v1, authenticated by an API key in the query string.
+pub struct Mouser {
+    client: reqwest::Client,
+    api_key: String,
+    endpoint: String,
+}
+
+impl Mouser {
+    pub fn new(
+        client: reqwest::Client,
+        api_key: &str,
+        endpoint: Option<&str>,
+    ) -> Result<Self, String> {
+        if api_key.trim().is_empty() {
+            return Err("Mouser API Key is required.".to_string());
+        }
+        Ok(Self {
+            client,
+            api_key: api_key.trim().to_string(),
+            endpoint: resolve_endpoint(endpoint, ENDPOINT_ENV, API_URL)?,
+        })
+    }
+
+    async fn request(
+        &self,
+        endpoint: &str,
+        payload: &Value,
+    ) -> Result<Vec<TrustedPartHit>, String> {
+        let mut url = reqwest::Url::parse(&format!("{}/search/{}", self.endpoint, endpoint))
+            .map_err(|e| format!("Invalid Mouser API endpoint: {}", e))?;
+        url.query_pairs_mut().append_pair("apiKey", &self.api_key);
+        let json = post_json(&self.client, "Mouser", url.as_str(), payload, &[]).await?;
+        parse_response(&json)
+    }
+}
+
+impl PartsProvider for Mouser {
+    fn capabilities(&self) -> ProviderCapabilities {
+        CAPABILITIES
+    }
+
+    fn scope(&self) -> ProviderScope {
+        ProviderScope::new(&self.endpoint, &self.api_key)
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(async move {
+            let mut request = json!({
+                "keyword": query.search_token.trim(),
+                "records": query.max_results(),
+                "startingRecord": 0,
+            });
+            if query.in_stock_only.unwrap_or(true) {
+                request["searchOptions"] = json!("InStock");
+            }
+            self.request("keyword", &json!({ "SearchByKeywordRequest": request }))
+                .await
+        })
+    }
+
+    fn lookup_mpn<'a>(
+        &'a self,
+        mpn: &'a str,
+        _query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(async move {
+            let payload = json!({
+                "SearchByPartRequest": {
+                    "mouserPartNumber": mpn.trim(),
+                    "partSearchOptions": "Exact",
+                }
+            });
+            let mut hits = self.request("partnumber", &payload).await?;
+            hits.retain(|hit| hit.mpn.eq_ignore_ascii_case(mpn.trim()));
+            Ok(hits)
+        })
+    }
+}
+
+/// Mouser reports failures in an `Errors` array with HTTP 200.
+fn parse_response(root: &Value) -> Result<Vec<TrustedPartHit>, String> {
+    let errors: Vec<String> = root
+        .get("Errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|error| {
+            error
+                .as_object()
+                .and_then(|object| {
+                    get_ci(object, &["Message"]).or_else(|| get_ci(object, &["Code"]))
+                })
+                .and_then(value_to_string)
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(format!("Mouser API error: {}", errors.join("; ")));
+    }
+
+    let parts = root
+        .pointer("/SearchResults/Parts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .filter_map(|part| parse_part_candidate(part, Some("Mouser")));
+    Ok(merge_hits(parts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_parts_and_reports_errors() {
+        let hits = parse_response(&json!({
+            "Errors": [],
+            "SearchResults": {
+                "NumberOfResult": 1,
+                "Parts": [{
+                    "ManufacturerPartNumber": "TPS62130RGTR",
+                    "Manufacturer": "Texas Instruments",
+                    "MouserPartNumber": "595-TPS62130RGTR",
+                    "Description": "Switching Voltage Regulators 3-17V 3A Step-Down Converter",
+                    "AvailabilityInStock": "12493",
+                    "Min": "1",
+                    "PriceBreaks": [
+                        {"Quantity": 1, "Price": "$2.31", "Currency": "USD"},
+                        {"Quantity": 10, "Price": "$1.70", "Currency": "USD"}
+                    ],
+                    "ProductDetailUrl": "https://www.mouser.com/ProductDetail/595-TPS62130RGTR",
+                    "LifecycleStatus": null
+                }]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].category_hint, "PMIC");
+        let offer = &hits[0].offers[0];
+        assert_eq!(offer.distributor, "Mouser");
+        assert_eq!(offer.sku.as_deref(), Some("595-TPS62130RGTR"));
+        assert_eq!(offer.stock, Some(12493));
+        assert_eq!(offer.unit_price, Some(2.31));
+        assert_eq!(offer.currency.as_deref(), Some("USD"));
+        assert_eq!(offer.price_breaks.len(), 2);
+        assert_eq!(offer.price_breaks[1].quantity, 10);
+        assert_eq!(offer.price_breaks[1].unit_price, 1.70);
+
+        let error = parse_response(&json!({
+            "Errors": [{"Code": "InvalidApiKey", "Message": "Invalid unique identifier."}],
+            "SearchResults": null
+        }))
+        .unwrap_err();
+        assert!(error.contains("Invalid unique identifier."));
+    }
+}
@@ -0,0 +1,302 @@
+//! Quantity price breaks and order-quantity rules for distributor offers.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use super::{PartQuery, Ranking, TrustedPartHit, TrustedPartOffer};
+
+/// Unit price from `quantity` pieces upward.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceBreak {
+    pub quantity: u64,
+    pub unit_price: f64,
+    pub currency: Option<String>,
+}
+
+/// What buying an offer for a build actually costs.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferQuote {
+    pub requested_quantity: u64,
+    /// Requested quantity raised to the MOQ and the next order multiple, or
+    /// to a larger break when that costs less.
+    pub order_quantity: u64,
+    /// Quantity of the price break that applies.
+    pub break_quantity: u64,
+    pub unit_price: f64,
+    pub extended_price: f64,
+    pub currency: Option<String>,
+}
+
+/// Sorts breaks by currency then quantity and drops repeats and unusable
+/// prices.
+pub fn normalize_breaks(breaks: &mut Vec<PriceBreak>) {
+    breaks.retain(|entry| entry.unit_price.is_finite() && entry.unit_price > 0.0);
+    for entry in breaks.iter_mut() {
+        entry.quantity = entry.quantity.max(1);
+        entry.currency = entry
+            .currency
+            .as_deref()
+            .map(str::trim)
+            .filter(|currency| !currency.is_empty())
+            .map(str::to_uppercase);
+    }
+    breaks.sort_by(|a, b| {
+        a.currency
+            .cmp(&b.currency)
+            .then(a.quantity.cmp(&b.quantity))
+    });
+    breaks.dedup_by(|a, b| a.currency == b.currency && a.quantity == b.quantity);
+}
+
+/// The currency an offer is quoted in when its breaks span several: USD when
+/// listed (the default TrustedParts country), otherwise the first one.
+pub fn primary_currency(breaks: &[PriceBreak]) -> Option<String> {
+    breaks
+        .iter()
+        .find(|entry| entry.currency.as_deref() == Some("USD"))
+        .or_else(|| breaks.first())
+        .and_then(|entry| entry.currency.clone())
+}
+
+impl TrustedPartOffer {
+    /// Cheapest way to buy at least `quantity` pieces. The order is raised to
+    /// the MOQ, then to the smallest listed break, then to a whole number of
+    /// order multiples, and priced at the largest break it reaches; buying up
+    /// to a larger break wins when its total is lower. Fails when the offer
+    /// has no price or the quantity is too large to price.
+    pub fn quote(&self, quantity: u64) -> Result<OfferQuote, String> {
+        let requested_quantity = quantity.max(1);
+        let multiple = self.order_multiple.unwrap_or(1).max(1);
+        let too_large = || format!("Quantity {} is too large to quote.", quantity);
+        let round_up = |quantity: u64| {
+            quantity
+                .div_ceil(multiple)
+                .checked_mul(multiple)
+                .ok_or_else(too_large)
+        };
+
+        let currency = self.currency.clone();
+        let breaks: Vec<&PriceBreak> = self
+            .price_breaks
+            .iter()
+            .filter(|entry| entry.currency == currency)
+            .collect();
+
+        let mut minimum = requested_quantity.max(self.moq.unwrap_or(1));
+        if let Some(smallest) = breaks.iter().map(|entry| entry.quantity).min() {
+            minimum = minimum.max(smallest);
+        }
+        let minimum = round_up(minimum)?;
+
+        let price_at = |order_quantity: u64| -> Option<OfferQuote> {
+            let (break_quantity, unit_price) = match breaks
+                .iter()
+                .filter(|entry| entry.quantity <= order_quantity)
+                .max_by_key(|entry| entry.quantity)
+            {
+                Some(entry) => (entry.quantity, entry.unit_price),
+                None => (1, self.unit_price?),
+            };
+            Some(OfferQuote {
+                requested_quantity,
+                order_quantity,
+                break_quantity,
+                unit_price,
+                extended_price: unit_price * order_quantity as f64,
+                currency: currency.clone(),
+            })
+        };
+
+        let mut best =
+            price_at(minimum).ok_or_else(|| format!("{} offer has no price.", self.distributor))?;
+        for entry in breaks.iter().filter(|entry| entry.quantity > minimum) {
+            let Some(candidate) = round_up(entry.quantity).ok().and_then(price_at) else {
+                continue;
+            };
+            if candidate.extended_price < best.extended_price {
+                best = candidate;
+            }
+        }
+        Ok(best)
+    }
+}
+
+/// Orders offers within each hit, and the hits by their best offer, as
+/// `query.ranking` asks. Stock order is what [`super::merge_hits`] already
+/// produces, so only [`Ranking::LandedCost`] reorders. Prices are compared
+/// as quoted, so mixed-currency results need a reporting currency.
+pub fn rank_hits(hits: &mut [TrustedPartHit], query: &PartQuery) {
+    if query.ranking.unwrap_or_default() != Ranking::LandedCost {
+        return;
+    }
+    let quantity = query.quantity();
+    for hit in hits.iter_mut() {
+        hit.offers.sort_by(|a, b| {
+            compare_landed(&landed_cost(a, quantity), &landed_cost(b, quantity))
+                .then_with(|| a.distributor.cmp(&b.distributor))
+        });
+    }
+    hits.sort_by(|a, b| {
+        let best =
+            |hit: &TrustedPartHit| hit.offers.first().map(|offer| landed_cost(offer, quantity));
+        match (best(a), best(b)) {
+            (Some(a_cost), Some(b_cost)) => compare_landed(&a_cost, &b_cost),
+            (a_cost, b_cost) => b_cost.is_some().cmp(&a_cost.is_some()),
+        }
+        .then_with(|| a.mpn.cmp(&b.mpn))
+    });
+}
+
+/// Whether stock covers the order, its extended price, and the stock.
+type LandedCost = (bool, Option<f64>, u64);
+
+fn landed_cost(offer: &TrustedPartOffer, quantity: u64) -> LandedCost {
+    let quote = offer.quote(quantity).ok();
+    let needed = quote
+        .as_ref()
+        .map_or(quantity, |quote| quote.order_quantity);
+    let stock = offer.stock.unwrap_or(0);
+    (
+        stock >= needed,
+        quote.map(|quote| quote.extended_price),
+        stock,
+    )
+}
+
+/// Filled orders before short ones, priced before unpriced, cheaper first,
+/// then more stock.
+fn compare_landed(a: &LandedCost, b: &LandedCost) -> Ordering {
+    b.0.cmp(&a.0)
+        .then_with(|| match (a.1, b.1) {
+            (Some(a_price), Some(b_price)) => a_price.total_cmp(&b_price),
+            (a_price, b_price) => b_price.is_some().cmp(&a_price.is_some()),
+        })
+        .then_with(|| b.2.cmp(&a.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(quantity: u64, unit_price: f64) -> PriceBreak {
+        PriceBreak {
+            quantity,
+            unit_price,
+            currency: Some("USD".to_string()),
+        }
+    }
+
+    #[test]
+    fn quotes_respect_moq_multiples_and_breaks() {
+        let offer = TrustedPartOffer {
+            distributor: "Digi-Key".to_string(),
+            sku: None,
+            stock: Some(10_000),
+            moq: Some(5),
+            order_multiple: Some(5),
+            currency: Some("USD".to_string()),
+            unit_price: Some(2.31),
+            original_currency: None,
+            original_unit_price: None,
+            price_breaks: vec![price(1, 2.31), price(10, 1.70), price(100, 1.2)],
+            buy_url: None,
+            datasheet_url: None,
+        };
+
+        let small = offer.quote(2).unwrap();
+        assert_eq!(small.order_quantity, 5);
+        assert_eq!(small.break_quantity, 1);
+        assert!((small.extended_price - 11.55).abs() < 1e-9);
+
+        let rounded = offer.quote(98).unwrap();
+        assert_eq!(rounded.order_quantity, 100);
+        assert_eq!(rounded.break_quantity, 100);
+        assert!((rounded.extended_price - 120.0).abs() < 1e-9);
+
+        let mut reel = offer.clone();
+        reel.moq = None;
+        reel.order_multiple = None;
+        reel.price_breaks = vec![price(3000, 1.04)];
+        assert_eq!(reel.quote(150).unwrap().order_quantity, 3000);
+
+        // 80 at 1.00 costs more than 100 at 0.50.
+        let mut cut_tape = offer.clone();
+        cut_tape.moq = None;
+        cut_tape.order_multiple = None;
+        cut_tape.price_breaks = vec![price(1, 1.0), price(100, 0.5), price(1000, 0.45)];
+        let bought_up = cut_tape.quote(80).unwrap();
+        assert_eq!(bought_up.order_quantity, 100);
+        assert_eq!(bought_up.break_quantity, 100);
+        assert!((bought_up.extended_price - 50.0).abs() < 1e-9);
+
+        // Rounding u64::MAX up to an even multiple overflows.
+        let mut pairs = offer.clone();
+        pairs.order_multiple = Some(2);
+        assert!(pairs.quote(u64::MAX).is_err());
+        cut_tape.unit_price = None;
+        cut_tape.price_breaks.clear();
+        assert!(cut_tape.quote(1).is_err());
+    }
+
+    #[test]
+    fn landed_cost_ranking_prefers_cheapest_filled_order() {
+        let offer =
+            |distributor: &str, stock: u64, moq: u64, breaks: Vec<PriceBreak>| TrustedPartOffer {
+                distributor: distributor.to_string(),
+                sku: None,
+                stock: Some(stock),
+                moq: Some(moq),
+                order_multiple: None,
+                currency: Some("USD".to_string()),
+                unit_price: breaks.first().map(|entry| entry.unit_price),
+                original_currency: None,
+                original_unit_price: None,
+                price_breaks: breaks,
+                buy_url: None,
+                datasheet_url: None,
+            };
+        let hit = |mpn: &str, offers: Vec<TrustedPartOffer>| TrustedPartHit {
+            mpn: mpn.to_string(),
+            manufacturer: None,
+            description: None,
+            lifecycle_status: None,
+            category_hint: "Component".to_string(),
+            category_confidence: 0.0,
+            attributes: Default::default(),
+            offers,
+            from_cache: false,
+            cache_age_seconds: None,
+        };
+        let mut hits = vec![
+            hit(
+                "A",
+                vec![
+                    // Most stock, but the reel MOQ makes 100 pieces cost 300.
+                    offer("Reel", 50_000, 3000, vec![price(3000, 0.1)]),
+                    offer("Short", 40, 1, vec![price(1, 0.5)]),
+                    offer("Cut", 500, 1, vec![price(1, 0.9), price(100, 0.6)]),
+                ],
+            ),
+            hit("B", vec![offer("Cheap", 1000, 1, vec![price(1, 0.4)])]),
+        ];
+
+        let mut query = PartQuery {
+            quantity: Some(100),
+            ..PartQuery::default()
+        };
+        rank_hits(&mut hits, &query);
+        assert_eq!(hits[0].mpn, "A");
+
+        query.ranking = Some(Ranking::LandedCost);
+        rank_hits(&mut hits, &query);
+        assert_eq!(hits[0].mpn, "B");
+        let distributors: Vec<&str> = hits[1]
+            .offers
+            .iter()
+            .map(|offer| offer.distributor.as_str())
+            .collect();
+        assert_eq!(distributors, ["Cut", "Reel", "Short"]);
+    }
+}
@@ -0,0 +1,594 @@
+use futures_util::future::BoxFuture;
+use serde_json::{json, Map, Value};
+
+use super::attributes::extract_attributes;
+use super::category::infer_category;
+use super::json::{get_ci, value_to_f64, value_to_string, value_to_u64};
+use super::pricing::{normalize_breaks, primary_currency};
+use super::{
+    merge_hits, post_json, resolve_endpoint, sort_offers, MpnLookupResult, PartQuery,
+    PartsProvider, PriceBreak, ProviderCapabilities, ProviderScope, TrustedPartHit,
+    TrustedPartOffer,
+};
+
+pub const CAPABILITIES: ProviderCapabilities = ProviderCapabilities {
+    id: "trustedparts",
+    name: "TrustedParts",
+    keyword_search: true,
+    mpn_lookup: true,
+    in_stock_filter: true,
+    country_filter: true,
+    multi_distributor: true,
+};
+const SEARCH_URL: &str = "https://api.trustedparts.com/v2/search";
+/// Overrides [`SEARCH_URL`] when the query doesn't set an endpoint.
+const ENDPOINT_ENV: &str = "AHA_TRUSTEDPARTS_SEARCH_URL";
+/// Search tokens sent in one `Queries` array by [`TrustedParts::lookup_mpns`].
+pub const MAX_QUERIES_PER_REQUEST: usize = 20;
+
+/// TrustedParts aggregates offers from many authorized distributors behind a
+/// single search endpoint authenticated by company id and API key.
+pub struct TrustedParts {
+    client: reqwest::Client,
+    company_id: String,
+    api_key: String,
+    endpoint: String,
+}
+
+impl TrustedParts {
+    pub fn new(
+        client: reqwest::Client,
+        company_id: &str,
+        api_key: &str,
+        endpoint: Option<&str>,
+    ) -> Result<Self, String> {
+        if company_id.trim().is_empty() {
+            return Err("TrustedParts Company ID is required.".to_string());
+        }
+        if api_key.trim().is_empty() {
+            return Err("TrustedParts API Key is required.".to_string());
+        }
+        Ok(Self {
+            client,
+            company_id: company_id.trim().to_string(),
+            api_key: api_key.trim().to_string(),
+            endpoint: resolve_endpoint(endpoint, ENDPOINT_ENV, SEARCH_URL)?,
+        })
+    }
+
+    fn payload(&self, query: &PartQuery, search_tokens: &[&str], exact_match: bool) -> Value {
+        let queries: Vec<Value> = search_tokens
+            .iter()
+            .map(|token| json!({ "SearchToken": token }))
+            .collect();
+        json!({
+            "CompanyId": self.company_id,
+            "ApiKey": self.api_key,
+            "Queries": queries,
+            "CountryCode": query.country_code(),
+            "ExactMatch": exact_match,
+            "InStockOnly": query.in_stock_only.unwrap_or(true),
+            "IsCrawler": false,
+            "UserAgent": "AHA-Designer/0.1",
+        })
+    }
+
+    async fn request(&self, payload: &Value) -> Result<Vec<TrustedPartHit>, String> {
+        let json = post_json(&self.client, "TrustedParts", &self.endpoint, payload, &[]).await?;
+        Ok(extract_parts_from_response(&json))
+    }
+
+    /// Exact lookups for many part numbers, [`MAX_QUERIES_PER_REQUEST`] per
+    /// request. Results keep the order of `mpns`; a failed request marks
+    /// every part number in its chunk with the error instead of aborting the
+    /// batch.
+    pub async fn lookup_mpns(&self, mpns: &[String], query: &PartQuery) -> Vec<MpnLookupResult> {
+        let mut results = Vec::with_capacity(mpns.len());
+        for chunk in mpns.chunks(MAX_QUERIES_PER_REQUEST) {
+            let tokens: Vec<&str> = chunk.iter().map(|mpn| mpn.trim()).collect();
+            let payload = self.payload(query, &tokens, true);
+            match post_json(&self.client, "TrustedParts", &self.endpoint, &payload, &[]).await {
+                Ok(json) => results.extend(split_batch_response(&json, chunk)),
+                Err(error) => results.extend(chunk.iter().map(|mpn| MpnLookupResult {
+                    mpn: mpn.clone(),
+                    hits: Vec::new(),
+                    error: Some(error.clone()),
+                })),
+            }
+        }
+        results
+    }
+}
+
+impl PartsProvider for TrustedParts {
+    fn capabilities(&self) -> ProviderCapabilities {
+        CAPABILITIES
+    }
+
+    fn scope(&self) -> ProviderScope {
+        ProviderScope::new(&self.endpoint, &self.company_id)
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(async move {
+            let payload = self.payload(
+                query,
+                &[query.search_token.trim()],
+                query.exact_match.unwrap_or(false),
+            );
+            self.request(&payload).await
+        })
+    }
+
+    fn lookup_mpn<'a>(
+        &'a self,
+        mpn: &'a str,
+        query: &'a PartQuery,
+    ) -> BoxFuture<'a, Result<Vec<TrustedPartHit>, String>> {
+        Box::pin(async move {
+            let payload = self.payload(query, &[mpn.trim()], true);
+            let mut hits = self.request(&payload).await?;
+            hits.retain(|hit| hit.mpn.eq_ignore_ascii_case(mpn.trim()));
+            Ok(hits)
+        })
+    }
+}
+
+/// Walks the whole response for part-like objects rather than relying on a
+/// fixed schema, so minor API revisions don't break parsing.
+pub fn extract_parts_from_response(root: &Value) -> Vec<TrustedPartHit> {
+    let mut candidates: Vec<Map<String, Value>> = Vec::new();
+    collect_candidate_part_objects(root, &mut candidates);
+
+    merge_hits(
+        candidates
+            .iter()
+            .filter_map(|candidate| parse_part_candidate(candidate, None)),
+    )
+}
+
+/// Assigns the parts of a multi-query response to the part numbers asked
+/// for. An error reported on a `SearchResults` entry is attached to its
+/// token when that token found nothing.
+fn split_batch_response(root: &Value, mpns: &[String]) -> Vec<MpnLookupResult> {
+    let hits = extract_parts_from_response(root);
+    let errors: Vec<(String, String)> = root
+        .as_object()
+        .and_then(|object| get_ci(object, &["SearchResults", "Results"]))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .filter_map(|result| {
+            let token = get_ci(result, &["SearchToken", "Query"]).and_then(value_to_string)?;
+            let error =
+                get_ci(result, &["Error", "ErrorMessage", "Errors"]).and_then(
+                    |value| match value {
+                        Value::Array(entries) => entries.iter().find_map(value_to_string),
+                        other => value_to_string(other),
+                    },
+                )?;
+            Some((token, error))
+        })
+        .collect();
+
+    mpns.iter()
+        .map(|mpn| {
+            let matching: Vec<TrustedPartHit> = hits
+                .iter()
+                .filter(|hit| hit.mpn.eq_ignore_ascii_case(mpn.trim()))
+                .cloned()
+                .collect();
+            let error = if matching.is_empty() {
+                errors
+                    .iter()
+                    .find(|(token, _)| token.eq_ignore_ascii_case(mpn.trim()))
+                    .map(|(_, error)| error.clone())
+            } else {
+                None
+            };
+            MpnLookupResult {
+                mpn: mpn.clone(),
+                hits: matching,
+                error,
+            }
+        })
+        .collect()
+}
+
+fn collect_candidate_part_objects(value: &Value, out: &mut Vec<Map<String, Value>>) {
+    match value {
+        Value::Object(object) => {
+            if looks_like_part_object(object) {
+                out.push(object.clone());
+            }
+            for child in object.values() {
+                collect_candidate_part_objects(child, out);
+            }
+        }
+        Value::Array(array) => {
+            for child in array {
+                collect_candidate_part_objects(child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn looks_like_part_object(object: &Map<String, Value>) -> bool {
+    let has_mpn = get_ci(
+        object,
+        &[
+            "ManufacturerPartNumber",
+            "MPN",
+            "PartNumber",
+            "partNumber",
+            "mpn",
+            "Sku",
+        ],
+    )
+    .is_some();
+    let has_part_context = get_ci(
+        object,
+        &[
+            "Manufacturer",
+            "ManufacturerName",
+            "Description",
+            "Offers",
+            "SellerOffers",
+            "DistributorOffers",
+            "DatasheetUrl",
+        ],
+    )
+    .is_some();
+
+    has_mpn && has_part_context
+}
+
+/// Parses one part object. `distributor` names the seller for single-source
+/// APIs whose offers don't carry a distributor field.
+pub fn parse_part_candidate(
+    object: &Map<String, Value>,
+    distributor: Option<&str>,
+) -> Option<TrustedPartHit> {
+    let mpn = get_ci(
+        object,
+        &[
+            "ManufacturerPartNumber",
+            "ManufacturerProductNumber",
+            "MPN",
+            "PartNumber",
+            "partNumber",
+            "mpn",
+            "Sku",
+        ],
+    )
+    .and_then(value_to_string)?;
+
+    let manufacturer = get_ci(
+        object,
+        &["Manufacturer", "ManufacturerName", "Mfr", "Brand", "maker"],
+    )
+    .and_then(value_to_string);
+    let description = get_ci(
+        object,
+        &[
+            "Description",
+            "ShortDescription",
+            "Name",
+            "description",
+            "title",
+        ],
+    )
+    .and_then(|value| match value {
+        Value::Object(inner) => get_ci(inner, &["ProductDescription"])
+            .or_else(|| get_ci(inner, &["DetailedDescription"]))
+            .and_then(value_to_string),
+        other => value_to_string(other),
+    });
+    let lifecycle_status = get_ci(
+        object,
+        &[
+            "LifecycleStatus",
+            "Lifecycle",
+            "Status",
+            "PartStatus",
+            "ProductStatus",
+        ],
+    )
+    .and_then(|value| match value {
+        Value::Object(inner) => get_ci(inner, &["Status"]).and_then(value_to_string),
+        other => value_to_string(other),
+    });
+    let datasheet = get_ci(
+        object,
+        &["DatasheetUrl", "DatasheetURL", "Datasheet", "DataSheetUrl"],
+    )
+    .and_then(value_to_string);
+
+    let mut offers = extract_offers(object, datasheet.clone());
+    if let Some(distributor) = distributor {
+        for offer in &mut offers {
+            offer.distributor = distributor.to_string();
+        }
+    }
+    sort_offers(&mut offers);
+
+    let category = infer_category(&mpn, description.as_deref());
+    Some(TrustedPartHit {
+        category_hint: category.category,
+        category_confidence: category.confidence,
+        attributes: description
+            .as_deref()
+            .map(extract_attributes)
+            .unwrap_or_default(),
+        mpn,
+        manufacturer,
+        description,
+        lifecycle_status,
+        offers,
+        from_cache: false,
+        cache_age_seconds: None,
+    })
+}
+
+fn extract_offers(
+    object: &Map<String, Value>,
+    fallback_datasheet: Option<String>,
+) -> Vec<TrustedPartOffer> {
+    let mut offers = Vec::new();
+
+    // `get_ci` ignores case, so each spelling is listed once.
+    for key in [
+        "Offers",
+        "SellerOffers",
+        "DistributorOffers",
+        "Distributors",
+        "Sellers",
+        "Sources",
+    ] {
+        if let Some(Value::Array(entries)) = get_ci(object, &[key]) {
+            for entry in entries {
+                if let Some(entry_obj) = entry.as_object() {
+                    if let Some(offer) = parse_offer(entry_obj, fallback_datasheet.clone()) {
+                        offers.push(offer);
+                    }
+                }
+            }
+        }
+    }
+
+    if offers.is_empty() {
+        if let Some(offer) = parse_offer(object, fallback_datasheet) {
+            offers.push(offer);
+        }
+    }
+
+    offers
+}
+
+pub fn parse_offer(
+    object: &Map<String, Value>,
+    fallback_datasheet: Option<String>,
+) -> Option<TrustedPartOffer> {
+    let distributor = get_ci(
+        object,
+        &[
+            "Distributor",
+            "DistributorName",
+            "Seller",
+            "SellerName",
+            "Supplier",
+            "SupplierName",
+            "Source",
+            "Store",
+        ],
+    )
+    .and_then(value_to_string)
+    .unwrap_or_else(|| "Unknown Distributor".to_string());
+
+    let sku = get_ci(
+        object,
+        &[
+            "SKU",
+            "Sku",
+            "PartNumber",
+            "SellerPartNumber",
+            "SupplierPartNumber",
+            "MouserPartNumber",
+            "DigiKeyProductNumber",
+        ],
+    )
+    .and_then(value_to_string);
+
+    let stock = get_ci(
+        object,
+        &[
+            "InStockQuantity",
+            "QuantityAvailable",
+            "Stock",
+            "QtyAvailable",
+            "AvailableQuantity",
+            "AvailabilityInStock",
+            "QuantityAvailableforPackageType",
+        ],
+    )
+    .and_then(value_to_u64);
+
+    let moq = get_ci(
+        object,
+        &[
+            "MinimumOrderQuantity",
+            "MinOrderQty",
+            "MOQ",
+            "MinimumQuantity",
+            "moq",
+            "Min",
+        ],
+    )
+    .and_then(value_to_u64);
+    let order_multiple = get_ci(
+        object,
+        &[
+            "OrderMultiple",
+            "OrderMultiples",
+            "Multiple",
+            "Mult",
+            "OrderIncrement",
+        ],
+    )
+    .and_then(value_to_u64)
+    .filter(|multiple| *multiple > 0);
+
+    let price_breaks = extract_price_breaks(object, moq);
+    let currency = primary_currency(&price_breaks);
+    let unit_price = price_breaks
+        .iter()
+        .find(|entry| entry.currency == currency)
+        .map(|entry| entry.unit_price);
+    let buy_url = get_ci(
+        object,
+        &[
+            "BuyUrl",
+            "ProductUrl",
+            "ProductURL",
+            "ProductDetailUrl",
+            "Url",
+            "URL",
+            "Link",
+            "PurchaseUrl",
+        ],
+    )
+    .and_then(value_to_string);
+
+    let datasheet_url = get_ci(
+        object,
+        &["DatasheetUrl", "DatasheetURL", "Datasheet", "DataSheetUrl"],
+    )
+    .and_then(value_to_string)
+    .or(fallback_datasheet);
+
+    if stock.is_none() && unit_price.is_none() && buy_url.is_none() && datasheet_url.is_none() {
+        return None;
+    }
+
+    Some(TrustedPartOffer {
+        distributor,
+        sku,
+        stock,
+        moq,
+        order_multiple,
+        currency,
+        unit_price,
+        original_currency: None,
+        original_unit_price: None,
+        price_breaks,
+        buy_url,
+        datasheet_url,
+    })
+}
+
+/// Every price break of an offer, from a `Prices`-like container (a list of
+/// breaks, or breaks keyed by currency) or else a single unit price valid
+/// from the MOQ.
+fn extract_price_breaks(object: &Map<String, Value>, moq: Option<u64>) -> Vec<PriceBreak> {
+    let currency = get_ci(object, &["Currency"]).and_then(value_to_string);
+    let mut breaks = Vec::new();
+    if let Some(prices) = get_ci(object, &["Prices", "PriceBreaks", "StandardPricing"]) {
+        collect_price_breaks(prices, currency.as_deref(), &mut breaks);
+    }
+    if breaks.is_empty() {
+        if let Some(price) = get_ci(object, &["UnitPrice"])
+            .or_else(|| get_ci(object, &["Price"]))
+            .and_then(value_to_f64)
+        {
+            breaks.push(PriceBreak {
+                quantity: moq.unwrap_or(1),
+                unit_price: price,
+                currency,
+            });
+        }
+    }
+    normalize_breaks(&mut breaks);
+    breaks
+}
+
+fn collect_price_breaks(value: &Value, currency: Option<&str>, out: &mut Vec<PriceBreak>) {
+    match value {
+        Value::Object(object) => {
+            let price = get_ci(object, &["UnitPrice"]).or_else(|| get_ci(object, &["Price"]));
+            if let Some(price) = price {
+                // A single break: { "Quantity": 10, "Price": "1.70", "Currency": "USD" }
+                if let Some(unit_price) = value_to_f64(price) {
+                    out.push(PriceBreak {
+                        quantity: get_ci(
+                            object,
+                            &["Quantity", "BreakQuantity", "Qty", "MinQty", "MinQuantity"],
+                        )
+                        .and_then(value_to_u64)
+                        .unwrap_or(1),
+                        unit_price,
+                        currency: get_ci(object, &["Currency"])
+                            .and_then(value_to_string)
+                            .or_else(|| currency.map(str::to_string)),
+                    });
+                }
+            } else {
+                // Breaks keyed by currency: { "USD": [ ... ], "EUR": [ ... ] }
+                for (key, entry) in object {
+                    collect_price_breaks(entry, Some(key), out);
+                }
+            }
+        }
+        Value::Array(entries) => {
+            for entry in entries {
+                collect_price_breaks(entry, currency, out);
+            }
+        }
+        other => {
+            if let Some(unit_price) = value_to_f64(other) {
+                out.push(PriceBreak {
+                    quantity: 1,
+                    unit_price,
+                    currency: currency.map(str::to_string),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_every_break_and_quotes_one_currency() {
+        let offer = parse_offer(
+            json!({
+                "Distributor": "Farnell",
+                "InStockQuantity": 500,
+                "Mult": 5,
+                "Prices": {
+                    "EUR": [{"Quantity": 1, "Price": 2.10}, {"Quantity": 25, "Price": 1.55}],
+                    "USD": [{"Quantity": 25, "Price": "1.70"}, {"Quantity": 1, "Price": "2.31"}]
+                }
+            })
+            .as_object()
+            .unwrap(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(offer.price_breaks.len(), 4);
+        assert_eq!(offer.currency.as_deref(), Some("USD"));
+        assert_eq!(offer.unit_price, Some(2.31));
+        assert_eq!(offer.order_multiple, Some(5));
+
+        let quote = offer.quote(22).unwrap();
+        assert_eq!(quote.order_quantity, 25);
+        assert_eq!(quote.unit_price, 1.70);
+    }
+}
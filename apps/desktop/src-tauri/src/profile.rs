@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+/// Operating conditions an analysis is evaluated under.
+///
+/// The frontend only passes a profile name (`"max_load"`), so named presets
+/// resolve to concrete parameters here; anything unrecognised falls back to
+/// the nominal preset rather than failing the run.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationProfile {
+    pub name: String,
+    /// Ambient temperature around the board, in °C.
+    pub ambient_c: f64,
+    /// Fraction of each component's TDP drawn under this profile (0.0–1.0).
+    pub load_factor: f64,
+}
+
+impl SimulationProfile {
+    pub fn resolve(name: &str) -> Self {
+        let normalized = name.trim().to_lowercase();
+        let (ambient_c, load_factor) = match normalized.as_str() {
+            "idle" => (25.0, 0.2),
+            "max_load" => (40.0, 1.0),
+            "worst_case" => (70.0, 1.0),
+            _ => (25.0, 0.6),
+        };
+        let name = if normalized.is_empty() {
+            "nominal".to_string()
+        } else {
+            normalized
+        };
+
+        Self {
+            name,
+            ambient_c,
+            load_factor,
+        }
+    }
+}
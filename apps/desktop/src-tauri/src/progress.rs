@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Tauri event carrying [`ProgressEvent`] payloads.
+pub const PROGRESS_EVENT: &str = "analysis-progress";
+
+static RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// One progress line emitted by an engine.
+///
+/// Engines may interleave lines like
+///
+/// ```json
+/// {"type": "progress", "percent": 40, "phase": "drc", "issues": ["..."]}
+/// ```
+///
+/// with their normal output; every stdout line that is a JSON object with
+/// `"type": "progress"` is treated as an update and stripped from the final
+/// result.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressUpdate {
+    #[serde(default)]
+    pub percent: Option<f64>,
+    #[serde(default)]
+    pub phase: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Issues found so far; the final result still lists all of them.
+    #[serde(default)]
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressEvent {
+    pub run_id: String,
+    pub engine: String,
+    #[serde(flatten)]
+    pub update: ProgressUpdate,
+}
+
+/// Receives progress updates while an engine runs. Must be `Sync` because
+/// sweeps report from several worker threads.
+pub type ProgressSink<'a> = &'a (dyn Fn(ProgressUpdate) + Sync);
+
+pub fn parse_progress_line(line: &str) -> Option<ProgressUpdate> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('{') {
+        return None;
+    }
+    let value: Value = serde_json::from_str(trimmed).ok()?;
+    if value.get("type").and_then(Value::as_str) != Some("progress") {
+        return None;
+    }
+    let mut update: ProgressUpdate = serde_json::from_value(value).ok()?;
+    update.percent = update
+        .percent
+        .filter(|percent| percent.is_finite())
+        .map(|percent| percent.clamp(0.0, 100.0));
+    Some(update)
+}
+
+/// Run id for callers that didn't supply one.
+pub fn new_run_id() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    format!(
+        "run-{}-{}",
+        timestamp,
+        RUN_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_only_progress_objects() {
+        let update = parse_progress_line(
+            r#"{"type":"progress","percent":140,"phase":"drc","issues":["x"]}"#,
+        )
+        .unwrap();
+        assert_eq!(update.percent, Some(100.0));
+        assert_eq!(update.phase.as_deref(), Some("drc"));
+        assert_eq!(update.issues, vec!["x"]);
+
+        assert!(parse_progress_line(r#"{"status":"success","issues":[]}"#).is_none());
+        assert!(parse_progress_line("plain text").is_none());
+    }
+}
@@ -0,0 +1,274 @@
+use serde::Serialize;
+
+use crate::findings::{Finding, Severity};
+use crate::graph::{DesignGraph, GraphNode};
+use crate::profile::SimulationProfile;
+
+/// Temperature the category default FIT rates are quoted at, in °C.
+pub const REFERENCE_TEMP_C: f64 = 40.0;
+
+const BOLTZMANN_EV_PER_K: f64 = 8.617_333_262e-5;
+const CELSIUS_TO_KELVIN: f64 = 273.15;
+const HOURS_PER_YEAR: f64 = 8760.0;
+/// Operating temperatures the Arrhenius model is applied over, in °C. Past
+/// absolute zero it has no answer, and far outside this it has no meaning.
+const OPERATING_RANGE_C: (f64, f64) = (-55.0, 200.0);
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FitSource {
+    CategoryDefault,
+    PartOverride,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentReliability {
+    pub node_id: String,
+    pub label: String,
+    pub category: String,
+    pub quantity: u32,
+    /// Failures per 10^9 device-hours at the reference temperature.
+    pub base_fit: f64,
+    pub fit_source: FitSource,
+    pub activation_energy_ev: f64,
+    pub operating_temp_c: f64,
+    pub acceleration_factor: f64,
+    /// Accelerated FIT for all `quantity` instances of this node.
+    pub fit: f64,
+    pub contribution_pct: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReliabilityReport {
+    pub profile: String,
+    pub ambient_c: f64,
+    pub reference_temp_c: f64,
+    pub total_fit: f64,
+    pub mtbf_hours: Option<f64>,
+    pub mtbf_years: Option<f64>,
+    pub components: Vec<ComponentReliability>,
+    pub findings: Vec<Finding>,
+}
+
+/// Series-system reliability: every node is required, so failure rates add
+/// and the system MTBF is the reciprocal of the summed rate.
+///
+/// Nodes may override the category defaults with `fit` and
+/// `activation_energy_ev`, multiply with `quantity`, and raise their operating
+/// temperature above ambient with `temp_rise_c`; the result is clamped to
+/// [`OPERATING_RANGE_C`] with a finding.
+pub fn analyze(graph: &DesignGraph, profile: &SimulationProfile) -> ReliabilityReport {
+    let mut findings = Vec::new();
+    let mut components: Vec<ComponentReliability> = graph
+        .nodes
+        .iter()
+        .map(|node| component_reliability(node, profile, &mut findings))
+        .collect();
+
+    let total_fit: f64 = components.iter().map(|component| component.fit).sum();
+    for component in &mut components {
+        component.contribution_pct = if total_fit > 0.0 {
+            component.fit / total_fit * 100.0
+        } else {
+            0.0
+        };
+    }
+    components.sort_by(|a, b| {
+        b.fit
+            .total_cmp(&a.fit)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+
+    let mtbf_hours = (total_fit > 0.0).then(|| 1e9 / total_fit);
+
+    ReliabilityReport {
+        profile: profile.name.clone(),
+        ambient_c: profile.ambient_c,
+        reference_temp_c: REFERENCE_TEMP_C,
+        total_fit,
+        mtbf_hours,
+        mtbf_years: mtbf_hours.map(|hours| hours / HOURS_PER_YEAR),
+        components,
+        findings,
+    }
+}
+
+fn component_reliability(
+    node: &GraphNode,
+    profile: &SimulationProfile,
+    findings: &mut Vec<Finding>,
+) -> ComponentReliability {
+    let category = node.category();
+    let (default_fit, default_ea) = category_defaults(&category);
+
+    let (base_fit, fit_source) = match node.number("fit").filter(|fit| *fit >= 0.0) {
+        Some(fit) => (fit, FitSource::PartOverride),
+        None => (default_fit, FitSource::CategoryDefault),
+    };
+    let activation_energy_ev = node
+        .number("activation_energy_ev")
+        .filter(|ea| *ea > 0.0)
+        .unwrap_or(default_ea);
+    let quantity = node
+        .number("quantity")
+        .filter(|qty| *qty >= 1.0)
+        .map(|qty| qty.round() as u32)
+        .unwrap_or(1);
+
+    let operating_temp_c = operating_temp(node, profile, findings);
+    let acceleration_factor =
+        arrhenius_acceleration(activation_energy_ev, REFERENCE_TEMP_C, operating_temp_c);
+
+    ComponentReliability {
+        node_id: node.id.clone(),
+        label: node.label(),
+        category,
+        quantity,
+        base_fit,
+        fit_source,
+        activation_energy_ev,
+        operating_temp_c,
+        acceleration_factor,
+        fit: base_fit * acceleration_factor * f64::from(quantity),
+        contribution_pct: 0.0,
+    }
+}
+
+/// Ambient plus `temp_rise_c`, kept within [`OPERATING_RANGE_C`].
+fn operating_temp(
+    node: &GraphNode,
+    profile: &SimulationProfile,
+    findings: &mut Vec<Finding>,
+) -> f64 {
+    let Some(rise) = node.number("temp_rise_c") else {
+        return profile.ambient_c;
+    };
+    let (low, high) = OPERATING_RANGE_C;
+    let requested = profile.ambient_c + rise;
+    if requested.is_finite() && (low..=high).contains(&requested) {
+        return requested;
+    }
+
+    let clamped = if requested.is_nan() {
+        profile.ambient_c.clamp(low, high)
+    } else {
+        requested.clamp(low, high)
+    };
+    findings.push(
+        Finding::new(
+            "temperature-out-of-range",
+            Severity::Warning,
+            format!(
+                "'{}' temp_rise_c of {} °C puts it outside {} to {} °C; {} °C is used instead.",
+                node.label(),
+                rise,
+                low,
+                high,
+                clamped
+            ),
+        )
+        .with_nodes(vec![node.id.clone()]),
+    );
+    clamped
+}
+
+/// Default (FIT at reference temperature, activation energy in eV) per
+/// category. Values are deliberately conservative handbook-style figures.
+fn category_defaults(category: &str) -> (f64, f64) {
+    match category {
+        "SoC" => (50.0, 0.7),
+        "MCU" => (20.0, 0.7),
+        "PMIC" => (25.0, 0.7),
+        "Memory" => (15.0, 0.6),
+        "Storage" => (40.0, 0.6),
+        "Sensor" => (30.0, 0.6),
+        "RF" => (35.0, 0.7),
+        _ => (10.0, 0.7),
+    }
+}
+
+/// Arrhenius acceleration factor of `use_c` relative to `reference_c`.
+/// Temperatures at or below absolute zero give NaN or infinity.
+pub fn arrhenius_acceleration(activation_energy_ev: f64, reference_c: f64, use_c: f64) -> f64 {
+    let reference_k = reference_c + CELSIUS_TO_KELVIN;
+    let use_k = use_c + CELSIUS_TO_KELVIN;
+    ((activation_energy_ev / BOLTZMANN_EV_PER_K) * (1.0 / reference_k - 1.0 / use_k)).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_mtbf_sums_fit_rates_and_applies_overrides() {
+        let graph = DesignGraph::from_json(
+            r#"{"nodes":[
+                {"id":"soc","data":{"label":"SoC","category":"SoC"}},
+                {"id":"ldo","data":{"label":"LDO","category":"PMIC","fit":"5","quantity":2}}
+            ]}"#,
+        )
+        .unwrap();
+        let profile = SimulationProfile {
+            name: "test".to_string(),
+            ambient_c: REFERENCE_TEMP_C,
+            load_factor: 1.0,
+        };
+
+        let report = analyze(&graph, &profile);
+
+        assert!((report.total_fit - 60.0).abs() < 1e-9);
+        assert!((report.mtbf_hours.unwrap() - 1e9 / 60.0).abs() < 1e-3);
+        assert_eq!(report.components[0].node_id, "soc");
+        assert_eq!(report.components[1].fit_source, FitSource::PartOverride);
+        assert!((report.components[1].fit - 10.0).abs() < 1e-9);
+        assert!(report.findings.is_empty());
+    }
+
+    #[test]
+    fn out_of_range_temperature_rise_is_clamped() {
+        let graph = DesignGraph::from_json(
+            r#"{"nodes":[
+                {"id":"cold","data":{"label":"Cold","temp_rise_c":-400}},
+                {"id":"hot","data":{"label":"Hot","temp_rise_c":1e308}},
+                {"id":"warm","data":{"label":"Warm","temp_rise_c":30}}
+            ]}"#,
+        )
+        .unwrap();
+        let profile = SimulationProfile {
+            name: "test".to_string(),
+            ambient_c: REFERENCE_TEMP_C,
+            load_factor: 1.0,
+        };
+
+        let report = analyze(&graph, &profile);
+
+        assert!(report.total_fit.is_finite());
+        assert!(report.mtbf_hours.is_some());
+        let temp = |id: &str| {
+            report
+                .components
+                .iter()
+                .find(|component| component.node_id == id)
+                .map(|component| component.operating_temp_c)
+        };
+        assert_eq!(temp("cold"), Some(OPERATING_RANGE_C.0));
+        assert_eq!(temp("hot"), Some(OPERATING_RANGE_C.1));
+        assert_eq!(temp("warm"), Some(70.0));
+        let flagged: Vec<&[String]> = report
+            .findings
+            .iter()
+            .map(|finding| finding.node_ids.as_slice())
+            .collect();
+        assert_eq!(flagged, [["cold"], ["hot"]]);
+    }
+
+    #[test]
+    fn arrhenius_accelerates_above_reference_temperature() {
+        assert!((arrhenius_acceleration(0.7, 40.0, 40.0) - 1.0).abs() < 1e-12);
+        let hot = arrhenius_acceleration(0.7, 40.0, 70.0);
+        assert!(hot > 9.0 && hot < 10.5, "unexpected acceleration {hot}");
+        assert!(arrhenius_acceleration(0.7, 40.0, 0.0) < 1.0);
+    }
+}
@@ -0,0 +1,381 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::findings::{self, Finding, Severity};
+use crate::graph::{DesignGraph, EdgeKind, GraphNode};
+
+/// Bands closer than this are treated as adjacent: front-end filters of
+/// small designs rarely reject a strong transmitter this close.
+const ADJACENT_GUARD_MHZ: f64 = 50.0;
+
+/// Named bands accepted in `rf_bands`, with their (transmit) range in MHz.
+const BANDS: &[(&str, f64, f64)] = &[
+    ("wifi-2.4", 2400.0, 2483.5),
+    ("wifi-5", 5150.0, 5895.0),
+    ("wifi-6e", 5925.0, 7125.0),
+    ("ble", 2400.0, 2483.5),
+    ("bluetooth", 2400.0, 2483.5),
+    ("zigbee", 2400.0, 2483.5),
+    ("thread", 2400.0, 2483.5),
+    ("lte-b1", 1920.0, 1980.0),
+    ("lte-b3", 1710.0, 1785.0),
+    ("lte-b7", 2500.0, 2570.0),
+    ("lte-b8", 880.0, 915.0),
+    ("lte-b20", 832.0, 862.0),
+    ("lte-b40", 2300.0, 2400.0),
+    ("lte-b41", 2496.0, 2690.0),
+    ("gnss-l1", 1559.0, 1610.0),
+    ("gnss-l5", 1164.0, 1189.0),
+    ("lora-868", 863.0, 870.0),
+    ("lora-915", 902.0, 928.0),
+    ("uwb", 6240.0, 8240.0),
+    ("nfc", 13.553, 13.567),
+];
+
+/// Label keywords used when a radio doesn't declare `rf_bands`.
+const BAND_KEYWORDS: &[(&str, &str)] = &[
+    ("wifi", "wifi-2.4"),
+    ("wi-fi", "wifi-2.4"),
+    ("ble", "ble"),
+    ("bluetooth", "bluetooth"),
+    ("zigbee", "zigbee"),
+    ("lora", "lora-868"),
+    ("gnss", "gnss-l1"),
+    ("gps", "gnss-l1"),
+    // Band 7 sits right above the 2.4 GHz ISM band, the worst case for a
+    // modem that doesn't say which bands it uses.
+    ("lte", "lte-b7"),
+    ("cellular", "lte-b7"),
+];
+
+/// Antenna types that are part of the radio or module itself.
+const INTEGRATED_ANTENNAS: &[&str] = &["integrated", "module"];
+/// Antenna types that need an impedance matching network between the radio
+/// and the antenna.
+const MATCHED_ANTENNAS: &[&str] = &["chip", "pcb"];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Radio {
+    pub node_id: String,
+    pub bands: Vec<String>,
+    pub tx_power_dbm: Option<f64>,
+    pub antenna_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RfReport {
+    pub status: &'static str,
+    pub radios: Vec<Radio>,
+    pub findings: Vec<Finding>,
+}
+
+/// Coexistence and antenna checks for the radios in a design.
+///
+/// A radio is any `RF` node or node with `rf_bands` (a list or
+/// comma-separated string of names from [`BANDS`]). It may set
+/// `rf_tx_power_dbm` and `antenna_type` (`chip`, `pcb`, `external`,
+/// `integrated`). Two radios on overlapping or adjacent bands are a
+/// coexistence risk unless they share an RF path (an `rf`/`ant` edge, or
+/// an untyped one in hand-written graphs) or a `coex_group`; power, data
+/// and clock wiring says nothing about coexistence. A combo chip with
+/// several bands on one node is assumed to arbitrate internally.
+pub fn analyze(graph: &DesignGraph) -> RfReport {
+    let mut findings = Vec::new();
+    let radios: Vec<(&GraphNode, Radio)> = graph
+        .nodes
+        .iter()
+        .filter(|node| is_radio(node))
+        .map(|node| (node, radio(node, &mut findings)))
+        .collect();
+
+    let mut neighbours: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut rf_paths: BTreeSet<(&str, &str)> = BTreeSet::new();
+    for edge in &graph.edges {
+        let (source, target) = (edge.source.as_str(), edge.target.as_str());
+        neighbours.entry(source).or_default().insert(target);
+        neighbours.entry(target).or_default().insert(source);
+        if matches!(edge.kind(), EdgeKind::Rf | EdgeKind::Unknown) {
+            rf_paths.insert((source.min(target), source.max(target)));
+        }
+    }
+    let by_id: BTreeMap<&str, &GraphNode> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect();
+
+    for (index, (node_a, radio_a)) in radios.iter().enumerate() {
+        for (node_b, radio_b) in &radios[index + 1..] {
+            let (a, b) = (node_a.id.as_str(), node_b.id.as_str());
+            let wired = rf_paths.contains(&(a.min(b), a.max(b)));
+            let grouped = matches!(
+                (node_a.text("coex_group"), node_b.text("coex_group")),
+                (Some(a), Some(b)) if !a.is_empty() && a == b
+            );
+            if wired || grouped {
+                continue;
+            }
+            if let Some(finding) = coexistence(node_a, radio_a, node_b, radio_b) {
+                findings.push(finding);
+            }
+        }
+    }
+
+    for (node, radio) in &radios {
+        check_antenna(node, radio, &neighbours, &by_id, &mut findings);
+    }
+
+    findings.sort_by_key(|finding| finding.severity);
+    RfReport {
+        status: findings::overall_status(&findings),
+        radios: radios.into_iter().map(|(_, radio)| radio).collect(),
+        findings,
+    }
+}
+
+fn is_radio(node: &GraphNode) -> bool {
+    node.category() == "RF" || node.data.contains_key("rf_bands")
+}
+
+fn radio(node: &GraphNode, findings: &mut Vec<Finding>) -> Radio {
+    let declared: Vec<String> = match node.data.get("rf_bands") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(text)) => text.split(',').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    let mut bands: Vec<String> = declared
+        .iter()
+        .map(|band| band.trim().to_ascii_lowercase())
+        .filter(|band| !band.is_empty())
+        .collect();
+
+    if bands.is_empty() {
+        let label = node.label().to_ascii_lowercase();
+        for (keyword, band) in BAND_KEYWORDS {
+            let matched = label
+                .split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '-')
+                .any(|word| word == *keyword);
+            if matched && !bands.iter().any(|known| known == band) {
+                bands.push(band.to_string());
+            }
+        }
+    }
+
+    for band in &bands {
+        if band_range(band).is_none() {
+            findings.push(
+                Finding::new(
+                    "unknown-rf-band",
+                    Severity::Warning,
+                    format!(
+                        "'{}' declares unknown band '{}'; it is excluded from coexistence checks.",
+                        node.label(),
+                        band
+                    ),
+                )
+                .with_nodes(vec![node.id.clone()]),
+            );
+        }
+    }
+
+    Radio {
+        node_id: node.id.clone(),
+        bands,
+        tx_power_dbm: node.number("rf_tx_power_dbm"),
+        antenna_type: node
+            .text("antenna_type")
+            .map(|kind| kind.trim().to_ascii_lowercase())
+            .filter(|kind| !kind.is_empty()),
+    }
+}
+
+fn band_range(band: &str) -> Option<(f64, f64)> {
+    BANDS
+        .iter()
+        .find(|(name, _, _)| *name == band)
+        .map(|(_, low, high)| (*low, *high))
+}
+
+/// Worst interaction between any band of `a` and any band of `b`.
+fn coexistence(node_a: &GraphNode, a: &Radio, node_b: &GraphNode, b: &Radio) -> Option<Finding> {
+    let mut worst: Option<(f64, &str, &str)> = None;
+    for band_a in &a.bands {
+        let Some((low_a, high_a)) = band_range(band_a) else {
+            continue;
+        };
+        for band_b in &b.bands {
+            let Some((low_b, high_b)) = band_range(band_b) else {
+                continue;
+            };
+            // Negative when the ranges overlap.
+            let gap = low_a.max(low_b) - high_a.min(high_b);
+            if gap < ADJACENT_GUARD_MHZ && worst.is_none_or(|(best, _, _)| gap < best) {
+                worst = Some((gap, band_a, band_b));
+            }
+        }
+    }
+
+    let (gap, band_a, band_b) = worst?;
+    let power = [a.tx_power_dbm, b.tx_power_dbm]
+        .into_iter()
+        .flatten()
+        .reduce(f64::max)
+        .map(|dbm| format!(" (up to {} dBm TX)", dbm))
+        .unwrap_or_default();
+    let (severity, relation) = if gap <= 0.0 {
+        (Severity::Error, "share".to_string())
+    } else {
+        (Severity::Warning, format!("are {:.1} MHz apart on", gap))
+    };
+    Some(
+        Finding::new(
+            "rf-coexistence-risk",
+            severity,
+            format!(
+                "'{}' ({}) and '{}' ({}) {} spectrum{} without a coexistence interface.",
+                node_a.label(),
+                band_a,
+                node_b.label(),
+                band_b,
+                relation,
+                power
+            ),
+        )
+        .with_nodes(vec![node_a.id.clone(), node_b.id.clone()]),
+    )
+}
+
+fn is_antenna(node: &GraphNode) -> bool {
+    node.category() == "Antenna" || node.flag("antenna")
+}
+
+fn is_matching_network(node: &GraphNode) -> bool {
+    node.category() == "Matching Network" || node.flag("matching_network")
+}
+
+fn check_antenna(
+    node: &GraphNode,
+    radio: &Radio,
+    neighbours: &BTreeMap<&str, BTreeSet<&str>>,
+    by_id: &BTreeMap<&str, &GraphNode>,
+    findings: &mut Vec<Finding>,
+) {
+    let antenna_type = radio.antenna_type.as_deref();
+    if antenna_type.is_some_and(|kind| INTEGRATED_ANTENNAS.contains(&kind)) {
+        return;
+    }
+    let adjacent = |id: &str| -> Vec<&GraphNode> {
+        neighbours
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|other| by_id.get(other).copied())
+            .collect()
+    };
+
+    let direct = adjacent(&node.id).into_iter().any(is_antenna);
+    let matched = adjacent(&node.id)
+        .into_iter()
+        .filter(|other| is_matching_network(other))
+        .any(|network| adjacent(&network.id).into_iter().any(is_antenna));
+
+    if !direct && !matched {
+        findings.push(
+            Finding::new(
+                "missing-antenna",
+                Severity::Error,
+                format!(
+                    "'{}' is not connected to an antenna; set antenna_type to 'integrated' for modules with one on board.",
+                    node.label()
+                ),
+            )
+            .with_nodes(vec![node.id.clone()]),
+        );
+    } else if !matched && antenna_type.is_some_and(|kind| MATCHED_ANTENNAS.contains(&kind)) {
+        findings.push(
+            Finding::new(
+                "missing-matching-network",
+                Severity::Warning,
+                format!(
+                    "'{}' drives a {} antenna directly; add a matching network between them.",
+                    node.label(),
+                    antenna_type.unwrap_or_default()
+                ),
+            )
+            .with_nodes(vec![node.id.clone()]),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_coexistence_and_antenna_problems() {
+        let graph = DesignGraph::from_json(
+            r#"{
+                "nodes": [
+                    {"id":"wifi","data":{"label":"WiFi Module","category":"RF","rf_bands":["wifi-2.4"],"rf_tx_power_dbm":20,"antenna_type":"chip"}},
+                    {"id":"ble","data":{"label":"nRF52 BLE","category":"RF","antenna_type":"integrated"}},
+                    {"id":"lte","data":{"label":"LTE Modem","category":"RF","rf_bands":"lte-b7, lte-b20"}},
+                    {"id":"lora","data":{"label":"LoRa","rf_bands":["lora-868"],"coex_group":"sub-ghz"}},
+                    {"id":"ant1","data":{"label":"Chip Antenna","category":"Antenna"}},
+                    {"id":"ant2","data":{"label":"LTE Antenna","category":"Antenna"}},
+                    {"id":"match","data":{"label":"Pi Match","category":"Matching Network"}},
+                    {"id":"catm","data":{"label":"Cat-M1 LTE Modem","category":"RF","antenna_type":"integrated"}}
+                ],
+                "edges": [
+                    {"source":"wifi","target":"ble","sourceHandle":"data-out","targetHandle":"data-in"},
+                    {"source":"catm","target":"ble","sourceHandle":"rf-out","targetHandle":"rf-in"},
+                    {"source":"wifi","target":"ant1"},
+                    {"source":"lte","target":"match"},
+                    {"source":"match","target":"ant2"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let report = analyze(&graph);
+        let found = |code: &str, nodes: &[&str]| {
+            report
+                .findings
+                .iter()
+                .any(|finding| finding.code == code && finding.node_ids == nodes)
+        };
+
+        assert_eq!(report.radios[1].bands, vec!["ble"]);
+        assert_eq!(report.radios[4].bands, vec!["lte-b7"]);
+        // A data link between the radios isn't a coexistence interface; a
+        // shared RF path is.
+        assert!(found("rf-coexistence-risk", &["wifi", "ble"]));
+        assert!(found("rf-coexistence-risk", &["wifi", "catm"]));
+        assert!(!found("rf-coexistence-risk", &["ble", "catm"]));
+        assert!(found("rf-coexistence-risk", &["wifi", "lte"]));
+        assert!(found("rf-coexistence-risk", &["ble", "lte"]));
+        assert!(found("rf-coexistence-risk", &["lte", "lora"]));
+        assert!(found("missing-matching-network", &["wifi"]));
+        assert!(found("missing-antenna", &["lora"]));
+        assert!(!report
+            .findings
+            .iter()
+            .any(|finding| finding.node_ids == ["lte"] || finding.node_ids == ["ble"]));
+
+        let severity = |nodes: &[&str]| {
+            report
+                .findings
+                .iter()
+                .find(|finding| finding.node_ids == nodes)
+                .map(|finding| finding.severity)
+        };
+        assert_eq!(severity(&["wifi", "ble"]), Some(Severity::Error));
+        assert_eq!(severity(&["wifi", "lte"]), Some(Severity::Warning));
+    }
+}
@@ -0,0 +1,323 @@
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Environment variables passed through to engines; everything else (API
+/// keys, tokens, proxy credentials) is dropped.
+const ENV_ALLOWLIST: &[&str] = &[
+    "PATH",
+    "HOME",
+    "LANG",
+    "LC_ALL",
+    "LC_CTYPE",
+    "TZ",
+    "VIRTUAL_ENV",
+    "SYSTEMROOT",
+    "WINDIR",
+    "PATHEXT",
+];
+
+static SCRATCH_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Resource ceilings applied to every engine process.
+#[derive(Debug, Clone)]
+pub struct SandboxLimits {
+    pub cpu_seconds: u64,
+    pub memory_bytes: u64,
+    /// Bytes of stderr kept for error messages.
+    pub stderr_bytes: usize,
+    /// Bytes of stdout (the result document and progress lines) read before
+    /// the process is killed.
+    pub stdout_bytes: usize,
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self {
+            cpu_seconds: 120,
+            memory_bytes: 4 * 1024 * 1024 * 1024,
+            stderr_bytes: 16 * 1024,
+            stdout_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// Per-run working directory, removed with everything in it on drop.
+#[derive(Debug)]
+pub struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    pub fn create(tag: &str) -> Result<Self, String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Failed to get system timestamp: {e}"))?
+            .as_millis();
+        let safe_tag: String = tag
+            .chars()
+            .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+            .collect();
+
+        let path = env::temp_dir().join(format!(
+            "aha_run_{}_{}_{}_{}",
+            safe_tag,
+            std::process::id(),
+            timestamp,
+            SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Failed to create engine scratch directory: {}", e))?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Builds a `Command` for `program` that starts in `scratch` with a cleared
+/// environment (allowlist plus temp variables pointing at the scratch
+/// directory) and, on Linux, CPU-time and address-space rlimits.
+pub fn command(program: &str, scratch: &ScratchDir, limits: &SandboxLimits) -> Command {
+    let mut command = Command::new(program);
+    command.current_dir(scratch.path()).env_clear();
+    for key in ENV_ALLOWLIST {
+        if let Some(value) = env::var_os(key) {
+            command.env(key, value);
+        }
+    }
+    for key in ["TMPDIR", "TEMP", "TMP", "AHA_SCRATCH_DIR"] {
+        command.env(key, scratch.path());
+    }
+    command.env("PYTHONDONTWRITEBYTECODE", "1");
+
+    apply_resource_limits(&mut command, limits);
+    command
+}
+
+#[cfg(target_os = "linux")]
+fn apply_resource_limits(command: &mut Command, limits: &SandboxLimits) {
+    use std::os::unix::process::CommandExt;
+
+    let cpu_seconds = limits.cpu_seconds as libc::rlim_t;
+    let memory_bytes = limits.memory_bytes as libc::rlim_t;
+    // SAFETY: the closure runs in the forked child before exec and only calls
+    // getrlimit/setrlimit, which are async-signal-safe.
+    unsafe {
+        command.pre_exec(move || {
+            // Soft CPU limit delivers SIGXCPU; the hard limit one second
+            // later is a SIGKILL for engines that ignore it.
+            lower_limit(libc::RLIMIT_CPU, cpu_seconds, cpu_seconds.saturating_add(1))?;
+            lower_limit(libc::RLIMIT_AS, memory_bytes, memory_bytes)
+        });
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(all(target_os = "linux", not(target_env = "gnu")))]
+type RlimitResource = libc::c_int;
+
+/// Lowers a limit without trying to raise it above the inherited hard limit,
+/// which an unprivileged process isn't allowed to do.
+#[cfg(target_os = "linux")]
+fn lower_limit(
+    resource: RlimitResource,
+    soft: libc::rlim_t,
+    hard: libc::rlim_t,
+) -> std::io::Result<()> {
+    let mut current = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    // SAFETY: `current` is a valid, writable rlimit struct.
+    if unsafe { libc::getrlimit(resource, &mut current) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let hard = hard.min(current.rlim_max);
+    let limit = libc::rlimit {
+        rlim_cur: soft.min(hard),
+        rlim_max: hard,
+    };
+    // SAFETY: `limit` is a valid rlimit struct.
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_resource_limits(_command: &mut Command, _limits: &SandboxLimits) {}
+
+/// Drains `stderr` to EOF keeping only the last `limit` bytes (that is where
+/// tracebacks end), and returns them as lossy UTF-8 noting how much was cut.
+pub fn collect_stderr<R: Read>(mut stderr: R, limit: usize) -> String {
+    let mut tail: Vec<u8> = Vec::new();
+    let mut total = 0usize;
+    let mut buffer = [0u8; 4096];
+    loop {
+        match stderr.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => {
+                total += read;
+                tail.extend_from_slice(&buffer[..read]);
+                if tail.len() > limit.saturating_mul(2).max(buffer.len()) {
+                    tail.drain(..tail.len() - limit);
+                }
+            }
+        }
+    }
+    if tail.len() > limit {
+        tail.drain(..tail.len() - limit);
+    }
+
+    let text = String::from_utf8_lossy(&tail).trim().to_string();
+    if total > tail.len() {
+        format!("[{} bytes truncated] {}", total - tail.len(), text)
+    } else {
+        text
+    }
+}
+
+/// Passes each line of `stdout` to `on_line` until EOF. Fails on a read error
+/// or once more than `limit` bytes arrive, so the caller can kill the process
+/// rather than stop reading and leave it blocked on a full pipe.
+pub fn read_stdout_lines<R: Read>(
+    stdout: R,
+    limit: usize,
+    mut on_line: impl FnMut(&[u8]),
+) -> Result<(), String> {
+    let mut reader = BufReader::new(stdout.take(limit as u64 + 1));
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) if reader.get_ref().limit() == 0 => {
+                return Err(format!("output exceeded {} bytes", limit));
+            }
+            Ok(_) => on_line(&line),
+            Err(e) => return Err(format!("output could not be read: {}", e)),
+        }
+    }
+}
+
+/// Human-readable description of a non-success exit, naming the resource
+/// limit when the process was killed for exceeding it.
+pub fn describe_exit(status: &std::process::ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            let reason = match signal {
+                24 => " (CPU time limit exceeded)",
+                9 => " (killed; CPU or memory limit exceeded)",
+                _ => "",
+            };
+            return format!("terminated by signal {}{}", signal, reason);
+        }
+    }
+    match status.code() {
+        Some(code) => format!("exited with status {}", code),
+        None => "terminated abnormally".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stderr_is_capped_from_the_front() {
+        assert_eq!(collect_stderr(&b"short"[..], 16), "short");
+        assert_eq!(
+            collect_stderr(&b"0123456789"[..], 4),
+            "[6 bytes truncated] 6789"
+        );
+        let long = vec![b'x'; 100_000];
+        assert!(collect_stderr(&long[..], 10).starts_with("[99990 bytes truncated] "));
+    }
+
+    #[test]
+    fn stdout_over_the_limit_or_unreadable_fails() {
+        let mut lines = Vec::new();
+        read_stdout_lines(&b"one\ntwo"[..], 7, |line| lines.push(line.to_vec())).unwrap();
+        assert_eq!(lines, [b"one\n".to_vec(), b"two".to_vec()]);
+
+        let long = [b'x'; 100];
+        assert!(read_stdout_lines(&long[..], 99, |_| {})
+            .unwrap_err()
+            .contains("exceeded 99 bytes"));
+
+        struct Broken;
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("pipe closed"))
+            }
+        }
+        assert!(read_stdout_lines(Broken, 99, |_| {})
+            .unwrap_err()
+            .contains("pipe closed"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn engine_runs_in_scratch_dir_with_filtered_env_and_cpu_limit() {
+        let scratch = ScratchDir::create("test").unwrap();
+        let scratch_path = scratch.path().to_path_buf();
+        let limits = SandboxLimits {
+            cpu_seconds: 1,
+            ..SandboxLimits::default()
+        };
+
+        let output = command("sh", &scratch, &limits)
+            .args(["-c", "pwd; env"])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        assert_eq!(
+            Path::new(lines.next().unwrap()).canonicalize().unwrap(),
+            scratch_path.canonicalize().unwrap()
+        );
+        for line in lines {
+            let key = line.split('=').next().unwrap_or_default();
+            assert!(
+                ENV_ALLOWLIST.contains(&key)
+                    || [
+                        "TMPDIR",
+                        "TEMP",
+                        "TMP",
+                        "AHA_SCRATCH_DIR",
+                        "PYTHONDONTWRITEBYTECODE",
+                        "PWD",
+                        "SHLVL",
+                        "_"
+                    ]
+                    .contains(&key),
+                "unexpected variable {key} leaked into the sandbox"
+            );
+        }
+
+        let spin = command("sh", &scratch, &limits)
+            .args(["-c", "while :; do :; done"])
+            .output()
+            .unwrap();
+        assert!(!spin.status.success());
+        assert!(describe_exit(&spin.status).contains("limit"));
+
+        drop(scratch);
+        assert!(!scratch_path.exists());
+    }
+}
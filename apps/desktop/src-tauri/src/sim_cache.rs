@@ -0,0 +1,217 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::profile::SimulationProfile;
+use crate::progress::ProgressSink;
+use crate::simulator;
+
+/// Bump when the Rust side of the simulator contract changes in a way that
+/// invalidates previously cached results.
+const CACHE_FORMAT_VERSION: &str = "1";
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static WRITE_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    pub directory: String,
+    pub entries: u64,
+    pub total_bytes: u64,
+    /// Hits and misses since the app started.
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// On-disk store of simulator outputs keyed by a SHA-256 over the
+/// canonicalized graph, the profile and the engine version.
+#[derive(Debug, Clone)]
+pub struct SimulationCache {
+    dir: PathBuf,
+}
+
+impl SimulationCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Returns the cached result for this input, or runs the engine and
+    /// stores its output. `force` skips the lookup but still refreshes the
+    /// entry. Cache hits return immediately without progress updates.
+    pub fn run(
+        &self,
+        document: &Value,
+        profile: &SimulationProfile,
+        force: bool,
+        on_progress: ProgressSink,
+    ) -> Result<String, String> {
+        let key = cache_key(document, profile);
+        if !force {
+            if let Some(cached) = self.get(&key) {
+                HITS.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached);
+            }
+        }
+        MISSES.fetch_add(1, Ordering::Relaxed);
+
+        let output = simulator::run(document, profile, on_progress)?;
+        // Only well-formed engine output is worth replaying.
+        if serde_json::from_str::<Value>(&output).is_ok() {
+            let _ = self.put(&key, &output);
+        }
+        Ok(output)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let (entries, total_bytes) = self
+            .entry_paths()
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .fold((0, 0), |(count, bytes), meta| {
+                (count + 1, bytes + meta.len())
+            });
+
+        CacheStats {
+            directory: self.dir.to_string_lossy().to_string(),
+            entries,
+            total_bytes,
+            hits: HITS.load(Ordering::Relaxed),
+            misses: MISSES.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn clear(&self) -> Result<CacheStats, String> {
+        for path in self.entry_paths() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove cache entry {}: {}", path.display(), e))?;
+        }
+        Ok(self.stats())
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.entry_path(key)).ok()
+    }
+
+    fn put(&self, key: &str, output: &str) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create simulation cache directory: {}", e))?;
+        // Write-then-rename so a concurrent reader never sees a partial entry.
+        let final_path = self.entry_path(key);
+        let temp_path = final_path.with_extension(format!(
+            "tmp{}-{}",
+            std::process::id(),
+            WRITE_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp_path, output)
+            .and_then(|_| fs::rename(&temp_path, &final_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp_path);
+                format!("Failed to write simulation cache entry: {}", e)
+            })
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    fn entry_paths(&self) -> Vec<PathBuf> {
+        fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+pub fn cache_key(document: &Value, profile: &SimulationProfile) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(CACHE_FORMAT_VERSION.as_bytes());
+    hasher.update([0]);
+    hasher.update(engine_fingerprint(&simulator::runner_script_path()));
+    hasher.update([0]);
+    hasher.update(canonical_graph(document).to_string().as_bytes());
+    hasher.update([0]);
+    hasher.update(
+        json!({
+            "ambientC": profile.ambient_c,
+            "loadFactor": profile.load_factor,
+        })
+        .to_string()
+        .as_bytes(),
+    );
+    format!("{:x}", hasher.finalize())
+}
+
+/// Hash of the runner script, so editing the engine invalidates the cache.
+fn engine_fingerprint(script: &Path) -> Vec<u8> {
+    fs::read(script)
+        .map(|bytes| Sha256::digest(bytes).to_vec())
+        .unwrap_or_default()
+}
+
+/// Reduces the graph to what the engine reads: node ids and data, and edge
+/// endpoints. Canvas state such as positions, selection and measured sizes is
+/// dropped, and object keys serialize sorted, so moving a node on the canvas
+/// doesn't miss the cache.
+fn canonical_graph(document: &Value) -> Value {
+    let nodes: Vec<Value> = document
+        .get("nodes")
+        .and_then(Value::as_array)
+        .map(|nodes| {
+            nodes
+                .iter()
+                .map(|node| pick(node, &["id", "data"]))
+                .collect()
+        })
+        .unwrap_or_default();
+    let edges: Vec<Value> = document
+        .get("edges")
+        .and_then(Value::as_array)
+        .map(|edges| {
+            edges
+                .iter()
+                .map(|edge| pick(edge, &["source", "target", "sourceHandle", "targetHandle"]))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({ "nodes": nodes, "edges": edges })
+}
+
+fn pick(value: &Value, keys: &[&str]) -> Value {
+    let mut picked = Map::new();
+    for key in keys {
+        if let Some(field) = value.get(*key) {
+            picked.insert(key.to_string(), field.clone());
+        }
+    }
+    Value::Object(picked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key_ignores_canvas_state_but_not_data_or_profile() {
+        let profile = SimulationProfile::resolve("max_load");
+        let base = json!({ "nodes": [{ "id": "a", "position": { "x": 0, "y": 0 }, "data": { "tdp_w": 5 } }] });
+        let moved = json!({ "nodes": [{ "id": "a", "position": { "x": 90, "y": 10 }, "selected": true, "data": { "tdp_w": 5 } }] });
+        let edited = json!({ "nodes": [{ "id": "a", "data": { "tdp_w": 6 } }] });
+
+        assert_eq!(cache_key(&base, &profile), cache_key(&moved, &profile));
+        assert_ne!(cache_key(&base, &profile), cache_key(&edited, &profile));
+        assert_ne!(
+            cache_key(&base, &profile),
+            cache_key(&base, &SimulationProfile::resolve("idle"))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::thread;
+
+use crate::profile::SimulationProfile;
+use crate::progress::{self, ProgressSink};
+use crate::sandbox::{self, SandboxLimits, ScratchDir};
+
+/// Placeholder an engine's argument list may use for the input file path;
+/// when absent the path is appended as the last argument.
+pub const INPUT_PLACEHOLDER: &str = "{input}";
+
+/// Runs the built-in Python simulation engine on `document` under `profile`.
+/// Returns the runner's raw JSON stdout.
+pub fn run(
+    document: &Value,
+    profile: &SimulationProfile,
+    on_progress: ProgressSink,
+) -> Result<String, String> {
+    let input = with_profile(document, profile)?;
+    let script = runner_script_path().to_string_lossy().to_string();
+    invoke("python3", &[script], &input, &profile.name, on_progress)
+}
+
+/// Embeds the profile in the engine input as a top-level `profile` object so
+/// engines can apply ambient temperature and load factor.
+pub fn with_profile(document: &Value, profile: &SimulationProfile) -> Result<Value, String> {
+    let mut input = document.clone();
+    match input.as_object_mut() {
+        Some(object) => {
+            object.insert(
+                "profile".to_string(),
+                serde_json::to_value(profile)
+                    .map_err(|e| format!("Failed to serialize profile: {}", e))?,
+            );
+        }
+        None => return Err("Simulation input must be a JSON object.".to_string()),
+    }
+    Ok(input)
+}
+
+/// Writes `input` into a fresh scratch directory, runs `program` with `args`
+/// on it inside the sandbox and returns stdout. This is the whole engine
+/// contract: JSON file in, JSON document on stdout. Progress lines are
+/// forwarded to `on_progress` as they arrive and left out of the result.
+pub fn invoke(
+    program: &str,
+    args: &[String],
+    input: &Value,
+    tag: &str,
+    on_progress: ProgressSink,
+) -> Result<String, String> {
+    let limits = SandboxLimits::default();
+    let scratch = ScratchDir::create(tag)?;
+    let input_path = scratch.path().join("input.json");
+    fs::File::create(&input_path)
+        .and_then(|mut file| write!(file, "{}", input))
+        .map_err(|e| format!("Failed to write engine input file: {}", e))?;
+
+    let input_arg = input_path.to_string_lossy().to_string();
+    let mut command_args: Vec<String> = args
+        .iter()
+        .map(|arg| arg.replace(INPUT_PLACEHOLDER, &input_arg))
+        .collect();
+    if !args.iter().any(|arg| arg.contains(INPUT_PLACEHOLDER)) {
+        command_args.push(input_arg);
+    }
+
+    let mut child = sandbox::command(program, &scratch, &limits)
+        .args(&command_args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {} process: {}", program, e))?;
+
+    // stderr is drained on its own thread so a chatty engine can't block on
+    // a full pipe while we wait for stdout.
+    let stderr_limit = limits.stderr_bytes;
+    let stderr_reader = child
+        .stderr
+        .take()
+        .map(|stderr| thread::spawn(move || sandbox::collect_stderr(stderr, stderr_limit)));
+
+    let mut stdout = String::new();
+    if let Some(pipe) = child.stdout.take() {
+        let read = sandbox::read_stdout_lines(pipe, limits.stdout_bytes, |line| {
+            let text = String::from_utf8_lossy(line);
+            match progress::parse_progress_line(&text) {
+                Some(update) => on_progress(update),
+                None => stdout.push_str(&text),
+            }
+        });
+        if let Err(error) = read {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!("Engine '{}' was stopped: {}", program, error));
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for {} process: {}", program, e))?;
+    let stderr = stderr_reader
+        .and_then(|reader| reader.join().ok())
+        .unwrap_or_default();
+
+    if stdout.trim().is_empty() {
+        return Err(format!(
+            "Engine '{}' {} without output. Stderr: {}",
+            program,
+            sandbox::describe_exit(&status),
+            stderr
+        ));
+    }
+
+    Ok(stdout)
+}
+
+pub fn runner_script_path() -> PathBuf {
+    let current_dir = env::current_dir().unwrap_or_default();
+    let possible_paths = vec![
+        current_dir.join("../../../simulator/python-runner/main.py"),
+        current_dir.join("../../simulator/python-runner/main.py"),
+        current_dir.join("simulator/python-runner/main.py"),
+    ];
+
+    possible_paths
+        .into_iter()
+        .find(|path| path.exists())
+        .unwrap_or_else(|| current_dir.join("../../../simulator/python-runner/main.py"))
+}
@@ -0,0 +1,493 @@
+//! Component sourcing commands. They live outside `lib.rs` because Tauri
+//! can't export `pub` commands from the crate root, and integration tests
+//! call them directly.
+
+use futures_util::stream::{self, StreamExt};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+use crate::credentials;
+use crate::lifecycle::{self, LifecycleReport};
+pub use crate::parts::PartSearchCache;
+use crate::parts::{
+    self, AggregatedSearch, AlternatesReport, CachedProvider, CategoryGuess, ExchangeRates,
+    MpnLookupResult, OfferQuote, PartQuery, PartsProvider, ProviderCapabilities, ProviderSettings,
+    SearchKind, Taxonomy, TrustedPartHit, TrustedPartOffer, TrustedParts,
+};
+use crate::sim_cache::CacheStats;
+
+/// Part numbers looked up at once during a lifecycle check.
+const LIFECYCLE_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPartsQueryInput {
+    #[serde(default)]
+    company_id: String,
+    #[serde(default)]
+    api_key: String,
+    /// Vault credential holding `companyId` and `apiKey`.
+    #[serde(default)]
+    credential: Option<String>,
+    /// Search URL override, e.g. a corporate proxy.
+    #[serde(default)]
+    endpoint: Option<String>,
+    #[serde(flatten)]
+    query: PartQuery,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPartsBatchInput {
+    #[serde(default)]
+    company_id: String,
+    #[serde(default)]
+    api_key: String,
+    /// Vault credential holding `companyId` and `apiKey`.
+    #[serde(default)]
+    credential: Option<String>,
+    #[serde(default)]
+    endpoint: Option<String>,
+    mpns: Vec<String>,
+    #[serde(flatten)]
+    query: PartQuery,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartsSearchInput {
+    providers: Vec<ProviderSettings>,
+    #[serde(flatten)]
+    query: PartQuery,
+}
+
+#[tauri::command]
+pub async fn search_trustedparts_inventory(
+    app: AppHandle,
+    mut query: TrustedPartsQueryInput,
+) -> Result<Vec<TrustedPartHit>, String> {
+    resolve_trustedparts_credential(
+        &app,
+        query.credential.as_deref(),
+        &mut query.company_id,
+        &mut query.api_key,
+        &mut query.endpoint,
+    )?;
+    let rates = reporting_rates(&app, &query.query)?;
+    let mut hits =
+        search_trustedparts(query, Some(part_search_cache(&app)?), rates.as_ref()).await?;
+    finish_hits(&app, &mut hits)?;
+    Ok(hits)
+}
+
+/// [`search_trustedparts_inventory`] with an explicit cache and exchange
+/// rates, or none.
+pub async fn search_trustedparts(
+    query: TrustedPartsQueryInput,
+    cache: Option<Arc<PartSearchCache>>,
+    rates: Option<&ExchangeRates>,
+) -> Result<Vec<TrustedPartHit>, String> {
+    let provider = TrustedParts::new(
+        parts::http_client()?,
+        &query.company_id,
+        &query.api_key,
+        query.endpoint.as_deref(),
+    )?;
+    if query.query.search_token.trim().is_empty() {
+        return Err("Search token cannot be empty.".to_string());
+    }
+
+    let provider = with_cache(Arc::new(provider), cache);
+    let mut hits = provider.search(&query.query).await?;
+    parts::select_hits(&mut hits, &query.query, rates);
+    Ok(hits)
+}
+
+/// Looks up every part number of a BOM in as few TrustedParts requests as
+/// possible. Results follow the input order with duplicates removed.
+#[tauri::command]
+pub async fn lookup_trustedparts_mpns(
+    app: AppHandle,
+    mut input: TrustedPartsBatchInput,
+) -> Result<Vec<MpnLookupResult>, String> {
+    resolve_trustedparts_credential(
+        &app,
+        input.credential.as_deref(),
+        &mut input.company_id,
+        &mut input.api_key,
+        &mut input.endpoint,
+    )?;
+    let rates = reporting_rates(&app, &input.query)?;
+    let mut results =
+        lookup_trustedparts_batch(input, Some(part_search_cache(&app)?), rates.as_ref()).await?;
+    for result in &mut results {
+        finish_hits(&app, &mut result.hits)?;
+    }
+    Ok(results)
+}
+
+/// [`lookup_trustedparts_mpns`] with an explicit cache and exchange rates,
+/// or none. Part numbers with a fresh cache entry aren't sent; failed ones
+/// fall back to any cached entry.
+pub async fn lookup_trustedparts_batch(
+    input: TrustedPartsBatchInput,
+    cache: Option<Arc<PartSearchCache>>,
+    rates: Option<&ExchangeRates>,
+) -> Result<Vec<MpnLookupResult>, String> {
+    let provider = TrustedParts::new(
+        parts::http_client()?,
+        &input.company_id,
+        &input.api_key,
+        input.endpoint.as_deref(),
+    )?;
+    let mut mpns: Vec<String> = Vec::new();
+    for mpn in input.mpns.iter().map(|mpn| mpn.trim()) {
+        if !mpn.is_empty() && !mpns.iter().any(|known| known.eq_ignore_ascii_case(mpn)) {
+            mpns.push(mpn.to_string());
+        }
+    }
+    if mpns.is_empty() {
+        return Err("At least one manufacturer part number is required.".to_string());
+    }
+
+    let query = &input.query;
+    let mut results: Vec<Option<MpnLookupResult>> = mpns
+        .iter()
+        .map(|mpn| {
+            let cached = cache
+                .as_ref()
+                .filter(|_| !query.refresh.unwrap_or(false))?
+                .get_mpn(&provider, mpn, query, false)?;
+            Some(MpnLookupResult {
+                mpn: mpn.clone(),
+                hits: cached,
+                error: None,
+            })
+        })
+        .collect();
+
+    let pending: Vec<String> = mpns
+        .iter()
+        .zip(&results)
+        .filter(|(_, cached)| cached.is_none())
+        .map(|(mpn, _)| mpn.clone())
+        .collect();
+    let mut fetched = provider.lookup_mpns(&pending, query).await.into_iter();
+    for slot in results.iter_mut().filter(|slot| slot.is_none()) {
+        let Some(mut result) = fetched.next() else {
+            break;
+        };
+        if let Some(cache) = &cache {
+            if result.error.is_none() {
+                let _ = cache.put_mpn(&provider, &result.mpn, query, &result.hits);
+            } else if let Some(hits) = cache.get_mpn(&provider, &result.mpn, query, true) {
+                result.hits = hits;
+                result.error = None;
+            }
+        }
+        *slot = Some(result);
+    }
+
+    Ok(results
+        .into_iter()
+        .flatten()
+        .map(|mut result| {
+            parts::select_hits(&mut result.hits, query, rates);
+            result
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn list_parts_providers() -> Vec<ProviderCapabilities> {
+    parts::available_providers()
+}
+
+#[tauri::command]
+pub async fn search_parts(
+    app: AppHandle,
+    input: PartsSearchInput,
+) -> Result<AggregatedSearch, String> {
+    if input.query.search_token.trim().is_empty() {
+        return Err("Search token cannot be empty.".to_string());
+    }
+    let providers = build_parts_providers(&app, &input.providers)?;
+    let rates = reporting_rates(&app, &input.query)?;
+    let mut search = parts::aggregate(
+        &providers,
+        &input.query,
+        SearchKind::Keyword,
+        rates.as_ref(),
+    )
+    .await?;
+    finish_hits(&app, &mut search.hits)?;
+    Ok(search)
+}
+
+#[tauri::command]
+pub async fn lookup_part_by_mpn(
+    app: AppHandle,
+    mpn: String,
+    input: PartsSearchInput,
+) -> Result<AggregatedSearch, String> {
+    if mpn.trim().is_empty() {
+        return Err("Manufacturer part number cannot be empty.".to_string());
+    }
+    let providers = build_parts_providers(&app, &input.providers)?;
+    let rates = reporting_rates(&app, &input.query)?;
+    let mut search = parts::aggregate(
+        &providers,
+        &input.query,
+        SearchKind::Mpn(&mpn),
+        rates.as_ref(),
+    )
+    .await?;
+    finish_hits(&app, &mut search.hits)?;
+    Ok(search)
+}
+
+/// Searches the category of `reference` for parts with the same package,
+/// supply and interfaces and ranks them as replacements at `quantity`. An
+/// empty `searchToken` is derived from the reference.
+#[tauri::command]
+pub async fn find_part_alternates(
+    app: AppHandle,
+    reference: TrustedPartHit,
+    quantity: Option<u64>,
+    input: PartsSearchInput,
+) -> Result<AlternatesReport, String> {
+    let mut query = input.query;
+    let mut reference = reference;
+    let rates = reporting_rates(&app, &query)?;
+    let reference_hits = std::slice::from_mut(&mut reference);
+    finish_hits(&app, reference_hits)?;
+    if let (Some(rates), Some(currency)) = (&rates, &query.reporting_currency) {
+        rates.convert_hits(reference_hits, currency);
+    }
+    if query.search_token.trim().is_empty() {
+        let extensions = Taxonomy::load_extensions(&taxonomy_path(&app)?)?;
+        query.search_token =
+            parts::alternates_search_token(&reference, &Taxonomy::builtin().extended(&extensions));
+    }
+    if query.search_token.trim().is_empty() {
+        return Err(format!(
+            "Not enough is known about {} to search for alternates.",
+            reference.mpn
+        ));
+    }
+
+    let providers = build_parts_providers(&app, &input.providers)?;
+    let mut search =
+        parts::aggregate(&providers, &query, SearchKind::Keyword, rates.as_ref()).await?;
+    finish_hits(&app, &mut search.hits)?;
+    Ok(parts::rank_alternates(
+        &reference,
+        search.hits,
+        quantity.unwrap_or(1),
+        query.search_token,
+    ))
+}
+
+/// Re-queries the lifecycle status of every workspace node with an MPN,
+/// stores it on the node and reports parts that became at risk since the
+/// previous check. The frontend saves the returned `workspaceJson`.
+#[tauri::command]
+pub async fn check_workspace_lifecycle(
+    app: AppHandle,
+    workspace_json: String,
+    input: PartsSearchInput,
+) -> Result<LifecycleReport, String> {
+    let mut workspace: serde_json::Value = serde_json::from_str(&workspace_json)
+        .map_err(|e| format!("Failed to parse workspace: {}", e))?;
+    let providers = build_parts_providers(&app, &input.providers)?;
+    // Obsolete parts are often out of stock everywhere; they must still be
+    // found to be flagged.
+    let query = &PartQuery {
+        in_stock_only: Some(false),
+        max_results: Some(usize::MAX),
+        ..input.query
+    };
+
+    let lookups: HashMap<String, Result<Vec<TrustedPartHit>, String>> =
+        stream::iter(lifecycle::workspace_mpns(&workspace))
+            .map(|mpn| {
+                let providers = &providers;
+                async move {
+                    let hits = parts::aggregate(providers, query, SearchKind::Mpn(&mpn), None)
+                        .await
+                        .map(|search| search.hits);
+                    (mpn.to_uppercase(), hits)
+                }
+            })
+            .buffer_unordered(LIFECYCLE_CONCURRENCY)
+            .collect()
+            .await;
+
+    lifecycle::record(&mut workspace, &lookups, crate::unix_now())
+}
+
+/// Cheapest extended price of buying `quantity` pieces from `offer`.
+#[tauri::command]
+pub fn quote_part_offer(offer: TrustedPartOffer, quantity: u64) -> Result<OfferQuote, String> {
+    if quantity == 0 {
+        return Err("Quantity must be at least 1.".to_string());
+    }
+    offer.quote(quantity)
+}
+
+/// The user's additions to the built-in part taxonomy.
+#[tauri::command]
+pub fn get_part_taxonomy(app: AppHandle) -> Result<Taxonomy, String> {
+    Taxonomy::load_extensions(&taxonomy_path(&app)?)
+}
+
+#[tauri::command]
+pub fn set_part_taxonomy(app: AppHandle, taxonomy: Taxonomy) -> Result<Taxonomy, String> {
+    let taxonomy = taxonomy.validated()?;
+    taxonomy.save(&taxonomy_path(&app)?)?;
+    Ok(taxonomy)
+}
+
+#[tauri::command]
+pub fn classify_part(
+    app: AppHandle,
+    mpn: String,
+    description: Option<String>,
+) -> Result<CategoryGuess, String> {
+    let extensions = Taxonomy::load_extensions(&taxonomy_path(&app)?)?;
+    Ok(Taxonomy::builtin()
+        .extended(&extensions)
+        .classify(&mpn, description.as_deref()))
+}
+
+#[tauri::command]
+pub fn get_exchange_rates(app: AppHandle) -> Result<ExchangeRates, String> {
+    load_exchange_rates(&app)
+}
+
+/// Replaces the exchange-rate table with an edited one.
+#[tauri::command]
+pub fn set_exchange_rates(app: AppHandle, rates: ExchangeRates) -> Result<ExchangeRates, String> {
+    let mut rates = rates.validated()?;
+    rates.touch();
+    rates.save(&exchange_rates_path(&app)?)?;
+    Ok(rates)
+}
+
+/// Merges `currency,rate` rows into the stored table.
+#[tauri::command]
+pub fn import_exchange_rates_csv(app: AppHandle, csv: String) -> Result<ExchangeRates, String> {
+    let path = exchange_rates_path(&app)?;
+    let mut rates = ExchangeRates::load(&path)?;
+    rates.import_csv(&csv)?;
+    rates.save(&path)?;
+    Ok(rates)
+}
+
+#[tauri::command]
+pub fn get_part_cache_stats(app: AppHandle) -> Result<CacheStats, String> {
+    Ok(part_search_cache(&app)?.stats())
+}
+
+/// Drops cached results for one provider and/or search token, or all of them.
+#[tauri::command]
+pub fn invalidate_part_search_cache(
+    app: AppHandle,
+    provider: Option<String>,
+    search_token: Option<String>,
+) -> Result<CacheStats, String> {
+    part_search_cache(&app)?.invalidate(provider.as_deref(), search_token.as_deref())
+}
+
+/// Providers with their vault credentials resolved, behind the search cache.
+fn build_parts_providers(
+    app: &AppHandle,
+    settings: &[ProviderSettings],
+) -> Result<Vec<Arc<dyn PartsProvider>>, String> {
+    let client = parts::http_client()?;
+    let cache = part_search_cache(app)?;
+    settings
+        .iter()
+        .map(|provider| {
+            let provider = match provider.credential() {
+                Some(name) => provider
+                    .clone()
+                    .with_credential(&credentials::credential_fields(app, name)?),
+                None => provider.clone(),
+            };
+            Ok(with_cache(
+                provider.build(client.clone())?,
+                Some(cache.clone()),
+            ))
+        })
+        .collect()
+}
+
+/// Fills empty TrustedParts secrets from the vault. As for
+/// [`ProviderSettings::with_credential`], the endpoint then comes from the
+/// credential rather than the frontend.
+fn resolve_trustedparts_credential(
+    app: &AppHandle,
+    credential: Option<&str>,
+    company_id: &mut String,
+    api_key: &mut String,
+    endpoint: &mut Option<String>,
+) -> Result<(), String> {
+    if let Some(name) = credential {
+        let fields = credentials::credential_fields(app, name)?;
+        parts::fill_secret(company_id, &fields, "companyId");
+        parts::fill_secret(api_key, &fields, "apiKey");
+        *endpoint = fields.get("endpoint").cloned();
+    }
+    Ok(())
+}
+
+fn with_cache(
+    provider: Arc<dyn PartsProvider>,
+    cache: Option<Arc<PartSearchCache>>,
+) -> Arc<dyn PartsProvider> {
+    match cache {
+        Some(cache) => Arc::new(CachedProvider::new(provider, cache)),
+        None => provider,
+    }
+}
+
+/// Applies the user's taxonomy extensions, which the providers and the
+/// cache don't know about.
+fn finish_hits(app: &AppHandle, hits: &mut [TrustedPartHit]) -> Result<(), String> {
+    let extensions = Taxonomy::load_extensions(&taxonomy_path(app)?)?;
+    if !extensions.categories.is_empty() {
+        parts::classify_hits(hits, &Taxonomy::builtin().extended(&extensions));
+    }
+    Ok(())
+}
+
+/// Exchange rates when the query asks for a reporting currency.
+fn reporting_rates(app: &AppHandle, query: &PartQuery) -> Result<Option<ExchangeRates>, String> {
+    query
+        .reporting_currency
+        .as_ref()
+        .map(|_| load_exchange_rates(app))
+        .transpose()
+}
+
+pub(crate) fn taxonomy_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(crate::app_data_subdir(app, "sourcing")?.join("taxonomy.json"))
+}
+
+fn exchange_rates_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+    Ok(crate::app_data_subdir(app, "sourcing")?.join("exchange-rates.json"))
+}
+
+fn load_exchange_rates(app: &AppHandle) -> Result<ExchangeRates, String> {
+    ExchangeRates::load(&exchange_rates_path(app)?)
+}
+
+pub(crate) fn part_search_cache(app: &AppHandle) -> Result<Arc<PartSearchCache>, String> {
+    Ok(Arc::new(PartSearchCache::new(crate::app_data_subdir(
+        app,
+        "part-search-cache",
+    )?)))
+}
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::profile::SimulationProfile;
+use crate::progress::{ProgressSink, ProgressUpdate};
+use crate::sim_cache::SimulationCache;
+
+/// Upper bound on grid size so a typo in an axis can't queue thousands of
+/// Python processes.
+const MAX_COMBINATIONS: usize = 256;
+const MAX_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepSpec {
+    #[serde(default)]
+    pub base_profile: Option<String>,
+    #[serde(default)]
+    pub profile_axes: Vec<ProfileAxis>,
+    #[serde(default)]
+    pub node_axes: Vec<NodeAxis>,
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Bypass cached results for every combination.
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileAxis {
+    pub parameter: ProfileParameter,
+    pub values: Vec<f64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProfileParameter {
+    AmbientC,
+    LoadFactor,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeAxis {
+    pub node_id: String,
+    pub attribute: String,
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepRow {
+    pub index: usize,
+    /// Column name to the value used for this combination.
+    pub parameters: BTreeMap<String, Value>,
+    pub status: String,
+    pub issues: Vec<String>,
+    pub report_data: Value,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SweepReport {
+    pub base_profile: String,
+    pub columns: Vec<String>,
+    pub concurrency: usize,
+    pub rows: Vec<SweepRow>,
+}
+
+/// One dimension of the grid after validation, with node axes resolved to an
+/// index into the document's `nodes` array.
+enum Axis {
+    Profile(ProfileParameter, Vec<f64>),
+    Node {
+        node_index: usize,
+        attribute: String,
+        values: Vec<Value>,
+    },
+}
+
+impl Axis {
+    fn len(&self) -> usize {
+        match self {
+            Axis::Profile(_, values) => values.len(),
+            Axis::Node { values, .. } => values.len(),
+        }
+    }
+}
+
+impl ProfileParameter {
+    fn column(self) -> &'static str {
+        match self {
+            ProfileParameter::AmbientC => "ambientC",
+            ProfileParameter::LoadFactor => "loadFactor",
+        }
+    }
+}
+
+/// Runs the simulation engine once per point of the cartesian product of all
+/// axes, at most `max_concurrency` runner processes at a time. Rows come back
+/// in grid order regardless of completion order; a failed run becomes an
+/// `error` row instead of aborting the sweep. Combinations already simulated
+/// are served from `cache`. `on_progress` receives one update per finished
+/// combination.
+pub fn run(
+    document: Value,
+    spec: SweepSpec,
+    cache: &SimulationCache,
+    on_progress: ProgressSink,
+) -> Result<SweepReport, String> {
+    let base_profile = SimulationProfile::resolve(spec.base_profile.as_deref().unwrap_or(""));
+    let (axes, columns) = resolve_axes(&document, &spec)?;
+
+    let total = axes.iter().try_fold(1usize, |acc, axis| {
+        acc.checked_mul(axis.len())
+            .filter(|count| *count <= MAX_COMBINATIONS)
+    });
+    let total = total.ok_or_else(|| {
+        format!(
+            "Sweep grid exceeds the limit of {} combinations.",
+            MAX_COMBINATIONS
+        )
+    })?;
+
+    let concurrency = spec
+        .max_concurrency
+        .unwrap_or_else(|| thread::available_parallelism().map_or(2, |n| n.get()))
+        .clamp(1, MAX_CONCURRENCY)
+        .min(total);
+
+    let next = AtomicUsize::new(0);
+    let completed = AtomicUsize::new(0);
+    let rows: Mutex<Vec<Option<SweepRow>>> = Mutex::new(vec![None; total]);
+    thread::scope(|scope| {
+        for _ in 0..concurrency {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= total {
+                    break;
+                }
+                let row = run_combination(
+                    index,
+                    &document,
+                    &base_profile,
+                    &axes,
+                    &columns,
+                    cache,
+                    spec.force,
+                );
+                let issues = row.issues.clone();
+                if let Ok(mut rows) = rows.lock() {
+                    rows[index] = Some(row);
+                }
+                let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                on_progress(ProgressUpdate {
+                    percent: Some(done as f64 / total as f64 * 100.0),
+                    phase: Some("sweep".to_string()),
+                    message: Some(format!("Combination {} of {} finished", done, total)),
+                    issues,
+                });
+            });
+        }
+    });
+
+    let rows = rows
+        .into_inner()
+        .map_err(|_| "Sweep worker panicked while recording results.".to_string())?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(SweepReport {
+        base_profile: base_profile.name,
+        columns,
+        concurrency,
+        rows,
+    })
+}
+
+fn resolve_axes(document: &Value, spec: &SweepSpec) -> Result<(Vec<Axis>, Vec<String>), String> {
+    let nodes = document
+        .get("nodes")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "Sweep graph has no nodes array.".to_string())?;
+
+    let mut axes = Vec::new();
+    let mut columns = Vec::new();
+
+    for axis in &spec.profile_axes {
+        if axis.values.is_empty() {
+            return Err(format!(
+                "Sweep axis '{}' has no values.",
+                axis.parameter.column()
+            ));
+        }
+        if let Some(value) = axis.values.iter().find(|value| !value.is_finite()) {
+            return Err(format!(
+                "Sweep axis '{}' contains a non-finite value ({}).",
+                axis.parameter.column(),
+                value
+            ));
+        }
+        columns.push(axis.parameter.column().to_string());
+        axes.push(Axis::Profile(axis.parameter, axis.values.clone()));
+    }
+
+    for axis in &spec.node_axes {
+        let column = format!("{}.{}", axis.node_id, axis.attribute);
+        if axis.attribute.trim().is_empty() {
+            return Err(format!(
+                "Sweep axis for node '{}' has no attribute.",
+                axis.node_id
+            ));
+        }
+        if axis.values.is_empty() {
+            return Err(format!("Sweep axis '{}' has no values.", column));
+        }
+        let node_index = nodes
+            .iter()
+            .position(|node| node.get("id").and_then(Value::as_str) == Some(axis.node_id.as_str()))
+            .ok_or_else(|| format!("Sweep axis references unknown node '{}'.", axis.node_id))?;
+
+        columns.push(column);
+        axes.push(Axis::Node {
+            node_index,
+            attribute: axis.attribute.clone(),
+            values: axis.values.clone(),
+        });
+    }
+
+    Ok((axes, columns))
+}
+
+fn run_combination(
+    index: usize,
+    document: &Value,
+    base_profile: &SimulationProfile,
+    axes: &[Axis],
+    columns: &[String],
+    cache: &SimulationCache,
+    force: bool,
+) -> SweepRow {
+    let mut profile = base_profile.clone();
+    let mut input = document.clone();
+    let mut parameters = BTreeMap::new();
+
+    // Mixed-radix decomposition of the row index, first axis varying slowest.
+    let mut stride = axes.iter().map(Axis::len).product::<usize>();
+    for (axis, column) in axes.iter().zip(columns) {
+        stride /= axis.len();
+        let position = (index / stride) % axis.len();
+        match axis {
+            Axis::Profile(parameter, values) => {
+                let value = values[position];
+                match parameter {
+                    ProfileParameter::AmbientC => profile.ambient_c = value,
+                    ProfileParameter::LoadFactor => profile.load_factor = value,
+                }
+                parameters.insert(column.clone(), Value::from(value));
+            }
+            Axis::Node {
+                node_index,
+                attribute,
+                values,
+            } => {
+                let value = values[position].clone();
+                if let Some(node) = input
+                    .get_mut("nodes")
+                    .and_then(|nodes| nodes.get_mut(*node_index))
+                    .and_then(Value::as_object_mut)
+                {
+                    let data = node
+                        .entry("data")
+                        .or_insert_with(|| Value::Object(Map::new()));
+                    if let Some(data) = data.as_object_mut() {
+                        data.insert(attribute.clone(), value.clone());
+                    }
+                }
+                parameters.insert(column.clone(), value);
+            }
+        }
+    }
+
+    // Per-engine progress would interleave across workers; the sweep reports
+    // its own completion instead.
+    let outcome = cache
+        .run(&input, &profile, force, &|_| {})
+        .and_then(|stdout| {
+            serde_json::from_str::<Value>(&stdout)
+                .map_err(|e| format!("Simulation engine returned invalid JSON: {}", e))
+        });
+
+    match outcome {
+        Ok(result) => SweepRow {
+            index,
+            parameters,
+            status: result
+                .get("status")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string(),
+            issues: result
+                .get("issues")
+                .and_then(Value::as_array)
+                .map(|issues| {
+                    issues
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            report_data: result.get("report_data").cloned().unwrap_or(Value::Null),
+            error: None,
+        },
+        Err(error) => SweepRow {
+            index,
+            parameters,
+            status: "error".to_string(),
+            issues: Vec::new(),
+            report_data: Value::Null,
+            error: Some(error),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resolves_axes_and_rejects_unknown_nodes() {
+        let document = json!({ "nodes": [{ "id": "cam", "data": { "quantity": 1 } }] });
+        let spec: SweepSpec = serde_json::from_value(json!({
+            "profileAxes": [{ "parameter": "ambientC", "values": [0.0, 70.0] }],
+            "nodeAxes": [{ "nodeId": "cam", "attribute": "quantity", "values": [1, 2, 3, 4] }]
+        }))
+        .unwrap();
+
+        let (axes, columns) = resolve_axes(&document, &spec).unwrap();
+        assert_eq!(columns, vec!["ambientC", "cam.quantity"]);
+        assert_eq!(axes.iter().map(Axis::len).product::<usize>(), 8);
+
+        let bad: SweepSpec = serde_json::from_value(json!({
+            "nodeAxes": [{ "nodeId": "missing", "attribute": "quantity", "values": [1] }]
+        }))
+        .unwrap();
+        assert!(resolve_axes(&document, &bad).is_err());
+    }
+}
@@ -0,0 +1,425 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::clock;
+use crate::findings::{self, Finding, Severity};
+use crate::graph::{DesignGraph, EdgeKind, GraphNode};
+
+const DEFAULT_MAX_FAN_OUT: usize = 8;
+
+/// Categories that supply power rather than consume it. Nodes outside these
+/// can opt in with `power_source: true` (connectors, batteries, bench
+/// supplies).
+const POWER_SOURCE_CATEGORIES: &[&str] = &["PMIC", "Power Stage", "Battery", "Power Supply"];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologyOptions {
+    /// Maximum outgoing edges per node; a node's `max_fan_out` attribute
+    /// overrides it.
+    #[serde(default)]
+    pub max_fan_out: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologyReport {
+    pub status: &'static str,
+    pub findings: Vec<Finding>,
+}
+
+/// Structural checks on the graph itself, independent of any engine:
+/// dangling edges and missing handles, unpowered consumers, isolated nodes
+/// and nodes without data connectivity, power loops and excessive fan-out.
+pub fn check(graph: &DesignGraph, options: &TopologyOptions) -> TopologyReport {
+    let node_ids: BTreeSet<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+    let mut findings = Vec::new();
+
+    check_edges(graph, &node_ids, &mut findings);
+    // Everything below only looks at edges whose endpoints exist.
+    let edges: Vec<_> = graph
+        .edges
+        .iter()
+        .filter(|edge| {
+            node_ids.contains(edge.source.as_str()) && node_ids.contains(edge.target.as_str())
+        })
+        .collect();
+
+    let mut powered: BTreeSet<&str> = BTreeSet::new();
+    let mut data_connected: BTreeSet<&str> = BTreeSet::new();
+    let mut connected: BTreeSet<&str> = BTreeSet::new();
+    let mut power_successors: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut fan_out: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+
+    for edge in &edges {
+        connected.insert(edge.source.as_str());
+        connected.insert(edge.target.as_str());
+        fan_out
+            .entry(edge.source.as_str())
+            .or_default()
+            .push(edge.display_id());
+        match edge.kind() {
+            EdgeKind::Power => {
+                powered.insert(edge.target.as_str());
+                power_successors
+                    .entry(edge.source.as_str())
+                    .or_default()
+                    .push(edge.target.as_str());
+            }
+            EdgeKind::Data => {
+                data_connected.insert(edge.source.as_str());
+                data_connected.insert(edge.target.as_str());
+            }
+            EdgeKind::Clock | EdgeKind::Rf | EdgeKind::Unknown => {}
+        }
+    }
+
+    for node in &graph.nodes {
+        let id = node.id.as_str();
+        if !connected.contains(id) {
+            findings.push(
+                Finding::new(
+                    "isolated-node",
+                    Severity::Warning,
+                    format!("'{}' has no connections.", node.label()),
+                )
+                .with_nodes(vec![node.id.clone()]),
+            );
+            continue;
+        }
+        let source = is_power_source(node);
+        // Crystals are passive and only carry a clock; oscillators still need
+        // power but have no data interface.
+        let crystal = node.category() == "Crystal";
+        if !source && !crystal && !powered.contains(id) {
+            findings.push(
+                Finding::new(
+                    "unpowered-consumer",
+                    Severity::Error,
+                    format!("'{}' has no incoming power edge.", node.label()),
+                )
+                .with_nodes(vec![node.id.clone()]),
+            );
+        }
+        if !source && !clock::is_clock_source(node) && !data_connected.contains(id) {
+            findings.push(
+                Finding::new(
+                    "no-data-connectivity",
+                    Severity::Warning,
+                    format!("'{}' is not connected to any data edge.", node.label()),
+                )
+                .with_nodes(vec![node.id.clone()]),
+            );
+        }
+    }
+
+    for cycle in power_cycles(&graph.nodes, &power_successors) {
+        let labels: Vec<String> = cycle
+            .iter()
+            .filter_map(|id| graph.nodes.iter().find(|node| node.id == *id))
+            .map(GraphNode::label)
+            .collect();
+        findings.push(
+            Finding::new(
+                "power-cycle",
+                Severity::Error,
+                format!("Power loop between {}.", labels.join(", ")),
+            )
+            .with_nodes(cycle),
+        );
+    }
+
+    let default_limit = options.max_fan_out.unwrap_or(DEFAULT_MAX_FAN_OUT);
+    for node in &graph.nodes {
+        let limit = node
+            .number("max_fan_out")
+            .filter(|limit| *limit >= 0.0)
+            .map_or(default_limit, |limit| limit as usize);
+        if let Some(outgoing) = fan_out.get(node.id.as_str()) {
+            if outgoing.len() > limit {
+                findings.push(
+                    Finding::new(
+                        "fan-out-exceeded",
+                        Severity::Warning,
+                        format!(
+                            "'{}' drives {} edges, above the fan-out limit of {}.",
+                            node.label(),
+                            outgoing.len(),
+                            limit
+                        ),
+                    )
+                    .with_nodes(vec![node.id.clone()])
+                    .with_edges(outgoing.clone()),
+                );
+            }
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.severity);
+    TopologyReport {
+        status: findings::overall_status(&findings),
+        findings,
+    }
+}
+
+pub fn is_power_source(node: &GraphNode) -> bool {
+    node.flag("power_source") || POWER_SOURCE_CATEGORIES.contains(&node.category().as_str())
+}
+
+fn check_edges(graph: &DesignGraph, node_ids: &BTreeSet<&str>, findings: &mut Vec<Finding>) {
+    for edge in &graph.edges {
+        let missing_endpoints: Vec<String> = [&edge.source, &edge.target]
+            .into_iter()
+            .filter(|id| !node_ids.contains(id.as_str()))
+            .cloned()
+            .collect();
+        if !missing_endpoints.is_empty() {
+            findings.push(
+                Finding::new(
+                    "dangling-edge",
+                    Severity::Error,
+                    format!(
+                        "Edge {} references missing node(s) {}.",
+                        edge.display_id(),
+                        missing_endpoints.join(", ")
+                    ),
+                )
+                .with_nodes(vec![edge.source.clone(), edge.target.clone()])
+                .with_edges(vec![edge.display_id()]),
+            );
+            continue;
+        }
+
+        let missing_handles: Vec<&str> = [
+            ("source", &edge.source_handle),
+            ("target", &edge.target_handle),
+        ]
+        .into_iter()
+        .filter(|(_, handle)| handle.as_deref().is_none_or(|h| h.trim().is_empty()))
+        .map(|(side, _)| side)
+        .collect();
+        if !missing_handles.is_empty() {
+            findings.push(
+                Finding::new(
+                    "missing-handle",
+                    Severity::Error,
+                    format!(
+                        "Edge {} has no {} handle.",
+                        edge.display_id(),
+                        missing_handles.join(" or ")
+                    ),
+                )
+                .with_nodes(vec![edge.source.clone(), edge.target.clone()])
+                .with_edges(vec![edge.display_id()]),
+            );
+        }
+    }
+}
+
+/// Strongly connected components of the power subgraph that form a loop:
+/// more than one node, or a node powering itself.
+fn power_cycles(nodes: &[GraphNode], successors: &BTreeMap<&str, Vec<&str>>) -> Vec<Vec<String>> {
+    struct Tarjan<'a> {
+        successors: &'a BTreeMap<&'a str, Vec<&'a str>>,
+        index: BTreeMap<&'a str, usize>,
+        low_link: BTreeMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        components: Vec<Vec<&'a str>>,
+    }
+
+    impl<'a> Tarjan<'a> {
+        /// Depth-first search from `root`. The call stack is explicit (each
+        /// frame is a node and the position of its next successor) so a long
+        /// supply chain can't overflow the thread's stack.
+        fn visit(&mut self, root: &'a str) {
+            self.open(root);
+            let mut calls: Vec<(&'a str, usize)> = vec![(root, 0)];
+            while let Some((node, position)) = calls.last_mut() {
+                let node = *node;
+                let successors = self.successors;
+                let next = successors
+                    .get(node)
+                    .and_then(|successors| successors.get(*position))
+                    .copied();
+                *position += 1;
+
+                match next {
+                    Some(next) if !self.index.contains_key(next) => {
+                        self.open(next);
+                        calls.push((next, 0));
+                    }
+                    Some(next) => {
+                        if self.on_stack.contains(next) {
+                            let low = self.low_link[node].min(self.index[next]);
+                            self.low_link.insert(node, low);
+                        }
+                    }
+                    None => {
+                        calls.pop();
+                        self.close(node);
+                        if let Some(&(parent, _)) = calls.last() {
+                            let low = self.low_link[parent].min(self.low_link[node]);
+                            self.low_link.insert(parent, low);
+                        }
+                    }
+                }
+            }
+        }
+
+        fn open(&mut self, node: &'a str) {
+            let index = self.index.len();
+            self.index.insert(node, index);
+            self.low_link.insert(node, index);
+            self.stack.push(node);
+            self.on_stack.insert(node);
+        }
+
+        /// Pops `node`'s component once all its successors are done, if it
+        /// is the component's root.
+        fn close(&mut self, node: &'a str) {
+            if self.low_link[node] == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        successors,
+        index: BTreeMap::new(),
+        low_link: BTreeMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        components: Vec::new(),
+    };
+    for node in nodes {
+        if !tarjan.index.contains_key(node.id.as_str()) {
+            tarjan.visit(node.id.as_str());
+        }
+    }
+
+    tarjan
+        .components
+        .into_iter()
+        .filter(|component| {
+            component.len() > 1
+                || successors
+                    .get(component[0])
+                    .is_some_and(|next| next.contains(&component[0]))
+        })
+        .map(|component| {
+            let mut ids: Vec<String> = component.into_iter().map(str::to_string).collect();
+            ids.sort();
+            ids
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(report: &TopologyReport) -> Vec<(&'static str, Vec<String>)> {
+        report
+            .findings
+            .iter()
+            .map(|finding| (finding.code, finding.node_ids.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_unpowered_isolated_and_cyclic_structures() {
+        let graph = DesignGraph::from_json(
+            r#"{
+                "nodes": [
+                    {"id":"pmic_a","data":{"label":"PMIC A","category":"PMIC"}},
+                    {"id":"pmic_b","data":{"label":"PMIC B","category":"PMIC"}},
+                    {"id":"soc","data":{"label":"SoC","category":"SoC"}},
+                    {"id":"imu","data":{"label":"IMU","category":"Sensor"}},
+                    {"id":"lonely","data":{"label":"Lonely","category":"Memory"}}
+                ],
+                "edges": [
+                    {"id":"e1","source":"pmic_a","target":"pmic_b","sourceHandle":"pwr-out","targetHandle":"pwr-in"},
+                    {"id":"e2","source":"pmic_b","target":"pmic_a","sourceHandle":"pwr-out","targetHandle":"pwr-in"},
+                    {"id":"e3","source":"pmic_a","target":"soc","sourceHandle":"pwr-out","targetHandle":"pwr-in"},
+                    {"id":"e4","source":"imu","target":"soc","sourceHandle":"data-out","targetHandle":"data-in"},
+                    {"id":"e5","source":"soc","target":"ghost","sourceHandle":"data-out","targetHandle":"data-in"},
+                    {"id":"e6","source":"pmic_b","target":"imu","sourceHandle":"pwr-out"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let report = check(&graph, &TopologyOptions::default());
+        let found = codes(&report);
+
+        assert_eq!(report.status, "error");
+        assert!(found.contains(&("power-cycle", vec!["pmic_a".into(), "pmic_b".into()])));
+        assert!(found.contains(&("isolated-node", vec!["lonely".into()])));
+        assert!(found.contains(&("dangling-edge", vec!["soc".into(), "ghost".into()])));
+        assert!(found.contains(&("missing-handle", vec!["pmic_b".into(), "imu".into()])));
+        // e6 still counts as a power edge by its source handle.
+        assert!(!found.iter().any(|(code, _)| *code == "unpowered-consumer"));
+    }
+
+    #[test]
+    fn fan_out_limit_is_configurable_per_node() {
+        let graph = DesignGraph::from_json(
+            r#"{
+                "nodes": [
+                    {"id":"pmic","data":{"label":"PMIC","category":"PMIC","max_fan_out":1}},
+                    {"id":"a","data":{"label":"A","category":"MCU"}},
+                    {"id":"b","data":{"label":"B","category":"MCU"}}
+                ],
+                "edges": [
+                    {"id":"p1","source":"pmic","target":"a","sourceHandle":"pwr-out","targetHandle":"pwr-in"},
+                    {"id":"p2","source":"pmic","target":"b","sourceHandle":"pwr-out","targetHandle":"pwr-in"},
+                    {"id":"d1","source":"a","target":"b","sourceHandle":"data-out","targetHandle":"data-in"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let report = check(&graph, &TopologyOptions::default());
+        let fan_out: Vec<_> = report
+            .findings
+            .iter()
+            .filter(|finding| finding.code == "fan-out-exceeded")
+            .collect();
+        assert_eq!(fan_out.len(), 1);
+        assert_eq!(fan_out[0].edge_ids, vec!["p1", "p2"]);
+        assert_eq!(report.status, "warning");
+    }
+
+    #[test]
+    fn long_power_chains_do_not_overflow_the_stack() {
+        let ids: Vec<String> = (0..50_000).map(|n| format!("n{}", n)).collect();
+        let nodes: Vec<GraphNode> = ids
+            .iter()
+            .map(|id| GraphNode {
+                id: id.clone(),
+                data: Default::default(),
+            })
+            .collect();
+        let mut successors: BTreeMap<&str, Vec<&str>> = ids
+            .windows(2)
+            .map(|pair| (pair[0].as_str(), vec![pair[1].as_str()]))
+            .collect();
+
+        assert!(power_cycles(&nodes, &successors).is_empty());
+
+        // Closing the chain makes it one loop through every node.
+        successors.insert(ids[ids.len() - 1].as_str(), vec![ids[0].as_str()]);
+        let cycles = power_cycles(&nodes, &successors);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), ids.len());
+    }
+}
@@ -0,0 +1,401 @@
+//! End-to-end tests for the TrustedParts search command against a local HTTP
+//! stub replaying recorded TrustedParts responses from `fixtures/`.
+
+use aha_desktop_lib::sourcing::{
+    lookup_trustedparts_batch, search_trustedparts, PartSearchCache, TrustedPartsBatchInput,
+    TrustedPartsQueryInput,
+};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SUCCESS: &str = include_str!("fixtures/trustedparts/search_success.json");
+const UNAUTHORIZED: &str = include_str!("fixtures/trustedparts/error_unauthorized.json");
+const MALFORMED: &str = include_str!("fixtures/trustedparts/malformed_truncated.json");
+const BATCH: &str = include_str!("fixtures/trustedparts/batch_mixed.json");
+
+/// One canned reply of a [`StubServer`].
+struct StubResponse {
+    status: u16,
+    content_type: &'static str,
+    headers: &'static str,
+    body: &'static str,
+    delay: Duration,
+}
+
+impl StubResponse {
+    fn new(status: u16, content_type: &'static str, body: &'static str) -> Self {
+        Self {
+            status,
+            content_type,
+            headers: "",
+            body,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+/// Replies to each connection with the next of `responses` (the last one
+/// repeats) and forwards each request body it receives.
+struct StubServer {
+    url: String,
+    requests: Receiver<Value>,
+    /// Requests received since the server started.
+    received: Arc<AtomicUsize>,
+}
+
+impl StubServer {
+    fn start(status: u16, content_type: &'static str, body: &'static str) -> Self {
+        Self::replay(vec![StubResponse::new(status, content_type, body)])
+    }
+
+    fn replay(responses: Vec<StubResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/v2/search", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = received.clone();
+
+        thread::spawn(move || {
+            for (index, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+                }
+                let mut request = vec![0; content_length];
+                reader.read_exact(&mut request).unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = sender.send(serde_json::from_slice(&request).unwrap_or(Value::Null));
+
+                let reply = &responses[index.min(responses.len() - 1)];
+                thread::sleep(reply.delay);
+                let response = format!(
+                    "HTTP/1.1 {} Stub\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+                    reply.status,
+                    reply.content_type,
+                    reply.body.len(),
+                    reply.headers,
+                    reply.body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        Self {
+            url,
+            requests,
+            received,
+        }
+    }
+
+    fn request_count(&self) -> usize {
+        self.received.load(Ordering::SeqCst)
+    }
+}
+
+fn search(query: Value) -> Result<Value, String> {
+    search_with_cache(query, None)
+}
+
+fn search_with_cache(query: Value, cache: Option<Arc<PartSearchCache>>) -> Result<Value, String> {
+    let input: TrustedPartsQueryInput = serde_json::from_value(query).unwrap();
+    tauri::async_runtime::block_on(search_trustedparts(input, cache, None))
+        .map(|hits| serde_json::to_value(hits).unwrap())
+}
+
+fn query(server: &StubServer) -> Value {
+    json!({
+        "companyId": "ACME-1",
+        "apiKey": "secret",
+        "searchToken": " TPS6213 ",
+        "inStockOnly": false,
+        "endpoint": server.url,
+    })
+}
+
+#[test]
+fn parses_recorded_search_response() {
+    let server = StubServer::start(200, "application/json", SUCCESS);
+    let hits = search(query(&server)).unwrap();
+
+    let request = server.requests.recv().unwrap();
+    assert_eq!(request["CompanyId"], "ACME-1");
+    assert_eq!(request["ApiKey"], "secret");
+    assert_eq!(request["Queries"][0]["SearchToken"], "TPS6213");
+    assert_eq!(request["CountryCode"], "US");
+    assert_eq!(request["InStockOnly"], false);
+
+    let hits = hits.as_array().unwrap();
+    assert_eq!(hits.len(), 2);
+    let first = &hits[0];
+    assert_eq!(first["mpn"], "TPS62130RGTR");
+    assert_eq!(first["manufacturer"], "Texas Instruments");
+    assert_eq!(first["lifecycleStatus"], "Active");
+    assert_eq!(
+        first["attributes"]["supplyVoltage"],
+        json!({"kind": "range", "min": 3.0, "max": 17.0, "unit": "V"})
+    );
+    assert_eq!(first["attributes"]["package"]["value"], "QFN");
+
+    let offers = first["offers"].as_array().unwrap();
+    assert_eq!(offers.len(), 2);
+    assert_eq!(offers[0]["distributor"], "Digi-Key");
+    assert_eq!(offers[0]["stock"], 12493);
+    assert_eq!(offers[0]["unitPrice"], 2.31);
+    assert_eq!(offers[0]["currency"], "USD");
+    assert_eq!(
+        offers[0]["priceBreaks"],
+        json!([
+            {"quantity": 1, "unitPrice": 2.31, "currency": "USD"},
+            {"quantity": 10, "unitPrice": 1.70, "currency": "USD"}
+        ])
+    );
+    assert_eq!(
+        offers[0]["datasheetUrl"],
+        "https://www.ti.com/lit/ds/symlink/tps62130.pdf"
+    );
+    assert_eq!(offers[1]["distributor"], "Arrow Electronics");
+    assert_eq!(offers[1]["moq"], 3000);
+
+    assert_eq!(hits[1]["mpn"], "TPS62133RGTT");
+}
+
+#[test]
+fn truncates_to_max_results() {
+    let server = StubServer::start(200, "application/json", SUCCESS);
+    let mut input = query(&server);
+    input["maxResults"] = json!(1);
+
+    let hits = search(input).unwrap();
+    assert_eq!(hits.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn surfaces_api_error_message() {
+    let server = StubServer::start(401, "application/json", UNAUTHORIZED);
+    let error = search(query(&server)).unwrap_err();
+    assert_eq!(
+        error,
+        "Authorization has been denied for this request. Check CompanyId and ApiKey."
+    );
+}
+
+#[test]
+fn falls_back_to_status_for_non_json_errors() {
+    let server = StubServer::start(502, "text/html", "<html>Bad Gateway</html>");
+    let error = search(query(&server)).unwrap_err();
+    assert_eq!(error, "HTTP 502 returned by TrustedParts API.");
+}
+
+#[test]
+fn rejects_malformed_json() {
+    let server = StubServer::start(200, "application/json", MALFORMED);
+    let error = search(query(&server)).unwrap_err();
+    assert!(
+        error.starts_with("TrustedParts API returned invalid JSON:"),
+        "{error}"
+    );
+}
+
+#[test]
+fn validates_input_before_sending() {
+    let server = StubServer::start(200, "application/json", SUCCESS);
+
+    let mut missing_key = query(&server);
+    missing_key["apiKey"] = json!("  ");
+    assert_eq!(
+        search(missing_key).unwrap_err(),
+        "TrustedParts API Key is required."
+    );
+
+    let mut bad_endpoint = query(&server);
+    bad_endpoint["endpoint"] = json!("ftp://parts.example.com");
+    assert!(search(bad_endpoint)
+        .unwrap_err()
+        .contains("must be an https URL"));
+
+    let mut plain_http = query(&server);
+    plain_http["endpoint"] = json!("http://parts.example.com/v2/search");
+    assert!(search(plain_http)
+        .unwrap_err()
+        .contains("must be an https URL"));
+
+    let mut empty_token = query(&server);
+    empty_token["searchToken"] = json!("");
+    assert_eq!(
+        search(empty_token).unwrap_err(),
+        "Search token cannot be empty."
+    );
+
+    assert!(server.requests.try_recv().is_err());
+}
+
+#[test]
+fn repeats_and_outages_are_served_from_cache() {
+    let dir = std::env::temp_dir().join(format!("aha_trustedparts_cache_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let cache = Arc::new(PartSearchCache::new(dir.clone()));
+    let server = StubServer::replay(vec![
+        StubResponse::new(200, "application/json", SUCCESS),
+        StubResponse::new(502, "text/html", "<html>Bad Gateway</html>"),
+    ]);
+
+    let live = search_with_cache(query(&server), Some(cache.clone())).unwrap();
+    assert_eq!(live[0]["fromCache"], false);
+    server.requests.recv().unwrap();
+
+    let mut repeat = query(&server);
+    repeat["searchToken"] = json!("tps6213");
+    let cached = search_with_cache(repeat, Some(cache.clone())).unwrap();
+    assert_eq!(cached[0]["mpn"], "TPS62130RGTR");
+    assert_eq!(cached[0]["fromCache"], true);
+    assert!(cached[0]["cacheAgeSeconds"].is_u64());
+    assert!(server.requests.try_recv().is_err());
+
+    let mut refresh = query(&server);
+    refresh["refresh"] = json!(true);
+    let fallback = search_with_cache(refresh, Some(cache.clone())).unwrap();
+    assert_eq!(fallback[0]["fromCache"], true);
+    server.requests.recv().unwrap();
+
+    // Entries belong to the endpoint they were fetched from.
+    let elsewhere = StubServer::start(200, "application/json", SUCCESS);
+    let other = search_with_cache(query(&elsewhere), Some(cache.clone())).unwrap();
+    assert_eq!(other[0]["fromCache"], false);
+    elsewhere.requests.recv().unwrap();
+
+    assert_eq!(
+        cache
+            .invalidate(Some("trustedparts"), None)
+            .unwrap()
+            .entries,
+        0
+    );
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn batches_mpn_lookups_into_chunked_requests() {
+    let server = StubServer::start(200, "application/json", BATCH);
+    let mut mpns = vec![
+        json!("TPS62130RGTR"),
+        json!(" tps62130rgtr "),
+        json!("STM32F405RGT6"),
+        json!("XYZ-NOT-A-PART"),
+        json!(""),
+    ];
+    mpns.extend((1..=20).map(|index| json!(format!("RC0603FR-07{index}KL"))));
+    let mut query = query(&server);
+    query["mpns"] = json!(mpns);
+
+    let input: TrustedPartsBatchInput = serde_json::from_value(query).unwrap();
+    let results =
+        tauri::async_runtime::block_on(lookup_trustedparts_batch(input, None, None)).unwrap();
+    let results = serde_json::to_value(results).unwrap();
+    let results = results.as_array().unwrap();
+
+    // 23 distinct part numbers: one full request of 20 and one of 3.
+    let first = server.requests.recv().unwrap();
+    let second = server.requests.recv().unwrap();
+    assert_eq!(first["Queries"].as_array().unwrap().len(), 20);
+    assert_eq!(second["Queries"].as_array().unwrap().len(), 3);
+    assert_eq!(first["ExactMatch"], true);
+    assert_eq!(first["Queries"][1]["SearchToken"], "STM32F405RGT6");
+
+    assert_eq!(results.len(), 23);
+    assert_eq!(results[0]["mpn"], "TPS62130RGTR");
+    assert_eq!(results[0]["hits"][0]["manufacturer"], "Texas Instruments");
+    assert_eq!(results[1]["hits"][0]["offers"][0]["distributor"], "Mouser");
+    assert_eq!(results[2]["hits"].as_array().unwrap().len(), 0);
+    assert_eq!(
+        results[2]["error"],
+        "No results found for search token XYZ-NOT-A-PART."
+    );
+    assert!(results[3]["error"].is_null());
+    assert_eq!(results[3]["hits"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn batch_request_failures_are_reported_per_item() {
+    let server = StubServer::start(401, "application/json", UNAUTHORIZED);
+    let mut query = query(&server);
+    query["mpns"] = json!(["TPS62130RGTR", "LM317T"]);
+
+    let input: TrustedPartsBatchInput = serde_json::from_value(query).unwrap();
+    let results =
+        tauri::async_runtime::block_on(lookup_trustedparts_batch(input, None, None)).unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.error.as_deref()
+        == Some("Authorization has been denied for this request. Check CompanyId and ApiKey.")));
+}
+
+#[test]
+fn retries_throttled_requests_honoring_retry_after() {
+    let mut throttled = StubResponse::new(429, "application/json", "{}");
+    throttled.headers = "Retry-After: 1\r\n";
+    let server = StubServer::replay(vec![
+        throttled,
+        StubResponse::new(503, "text/html", "<html>Service Unavailable</html>"),
+        StubResponse::new(200, "application/json", SUCCESS),
+    ]);
+
+    let started = Instant::now();
+    let hits = search(query(&server)).unwrap();
+    assert_eq!(hits[0]["mpn"], "TPS62130RGTR");
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(server.request_count(), 3);
+}
+
+#[test]
+fn gives_up_on_long_retry_after_and_after_max_attempts() {
+    let mut throttled = StubResponse::new(429, "application/json", "{}");
+    throttled.headers = "Retry-After: 3600\r\n";
+    let server = StubServer::replay(vec![throttled]);
+    assert_eq!(
+        search(query(&server)).unwrap_err(),
+        "TrustedParts API is rate limited; retry after 3600 s."
+    );
+    assert_eq!(server.request_count(), 1);
+
+    let server = StubServer::start(503, "text/html", "<html>Service Unavailable</html>");
+    assert_eq!(
+        search(query(&server)).unwrap_err(),
+        "HTTP 503 returned by TrustedParts API."
+    );
+    assert_eq!(server.request_count(), 3);
+}
+
+#[test]
+fn identical_in_flight_searches_share_one_request() {
+    let mut slow = StubResponse::new(200, "application/json", SUCCESS);
+    slow.delay = Duration::from_millis(300);
+    let server = StubServer::replay(vec![slow]);
+
+    let input = || -> TrustedPartsQueryInput { serde_json::from_value(query(&server)).unwrap() };
+    let (first, second) = tauri::async_runtime::block_on(futures_util::future::join(
+        search_trustedparts(input(), None, None),
+        search_trustedparts(input(), None, None),
+    ));
+    assert_eq!(first.unwrap().len(), 2);
+    assert_eq!(second.unwrap().len(), 2);
+    assert_eq!(server.request_count(), 1);
+
+    // Once finished, the same search goes out again.
+    search(query(&server)).unwrap();
+    assert_eq!(server.request_count(), 2);
+}
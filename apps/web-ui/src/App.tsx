@@ -9,6 +9,7 @@ import {
   Undo2,
   Redo2,
   FileDown,
+  FileArchive,
   Trash2,
   FolderOpen,
   SunMedium,
@@ -28,6 +29,18 @@ const GRAPH_STORAGE_KEY = "aha-designer-graph-v1";
 const THEME_STORAGE_KEY = "aha-designer-theme";
 const WORKSPACE_STORAGE_KEY = "aha-designer-workspace";
 
+type DatasheetReport = {
+  downloaded: number;
+  failed: number;
+  workspaceJson: string;
+};
+
+type DatasheetExport = {
+  path: string;
+  files: number;
+  missing: string[];
+};
+
 type PersistedGraph = {
   nodes: AhaNode[];
   edges: Edge[];
@@ -185,6 +198,49 @@ function App() {
     document.body.removeChild(link);
   };
 
+  const handleExportDatasheets = async () => {
+    try {
+      const workspaceEnvelope: WorkspaceEnvelope = {
+        version: 1,
+        savedAt: new Date().toISOString(),
+        nodes,
+        edges,
+      };
+      const report = await invoke<DatasheetReport>(
+        "archive_workspace_datasheets",
+        { workspaceJson: JSON.stringify(workspaceEnvelope) },
+      );
+      const archivedGraph = normalizeLoadedGraph(
+        JSON.parse(report.workspaceJson),
+      );
+      if (archivedGraph) {
+        setGraph(archivedGraph.nodes, archivedGraph.edges);
+      }
+
+      const selected = await saveDialog({
+        title: t("app.exportDatasheets"),
+        defaultPath: "aha-datasheets.zip",
+        filters: [{ name: "Zip Archive", extensions: ["zip"] }],
+      });
+      if (!selected) {
+        return;
+      }
+      const exported = await invoke<DatasheetExport>(
+        "export_workspace_datasheets",
+        { workspaceJson: report.workspaceJson, path: selected },
+      );
+      window.alert(
+        t("app.datasheetsExported", {
+          files: exported.files,
+          missing: exported.missing.length,
+        }),
+      );
+    } catch (err) {
+      console.error("Failed to export datasheets:", err);
+      window.alert(`Failed to export datasheets: ${String(err)}`);
+    }
+  };
+
   const handleSaveWorkspace = async () => {
     try {
       let targetPath = workspacePath;
@@ -411,6 +467,9 @@ function App() {
           <button className="btn" onClick={handleExportBOM}>
             <FileDown size={14} /> {t("app.exportBOM")}
           </button>
+          <button className="btn" onClick={handleExportDatasheets}>
+            <FileArchive size={14} /> {t("app.exportDatasheets")}
+          </button>
           <button className="btn" onClick={handleSaveWorkspace}>
             <Save size={14} /> {t("app.save")}
           </button>
@@ -426,7 +485,7 @@ function App() {
       {/* Main Workspace containing the panes */}
       <main className="main-workspace">
         <ReactFlowProvider>
-          <LeftPanel />
+          <LeftPanel workspacePath={workspacePath} />
           <CanvasPane />
           <RightPanel />
         </ReactFlowProvider>
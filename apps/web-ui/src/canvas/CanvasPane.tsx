@@ -74,11 +74,13 @@ export default function CanvasPane() {
       let label = fallbackLabel;
       let tdp = fallbackType === "SoC" ? 15 : 2;
       let manufacturer: string | undefined;
+      let packageName: string | undefined;
       let mpn: string | undefined;
       let description: string | undefined;
       let datasheetUrl: string | undefined;
       let buyUrl: string | undefined;
       let stock: number | undefined;
+      let ports: { name: string; kind: string }[] | undefined;
 
       const parsePayload = (raw: string) => {
         try {
@@ -88,11 +90,13 @@ export default function CanvasPane() {
             label?: string;
             tdp?: number;
             mfg?: string;
+            package?: string;
             mpn?: string;
             description?: string;
             datasheetUrl?: string;
             buyUrl?: string;
             stock?: number;
+            ports?: { name: string; kind: string }[];
           };
 
           if (!parsed.ahaDrag) {
@@ -103,11 +107,13 @@ export default function CanvasPane() {
           label = parsed.label ?? label;
           tdp = typeof parsed.tdp === "number" ? parsed.tdp : tdp;
           manufacturer = parsed.mfg;
+          packageName = parsed.package;
           mpn = parsed.mpn;
           description = parsed.description;
           datasheetUrl = parsed.datasheetUrl;
           buyUrl = parsed.buyUrl;
           stock = parsed.stock;
+          ports = parsed.ports;
         } catch (error) {
           console.warn("Failed to parse drop payload:", error);
         }
@@ -141,11 +147,13 @@ export default function CanvasPane() {
           category: type,
           tdp_w: tdp,
           manufacturer,
+          package: packageName,
           mpn,
           description,
           datasheet_url: datasheetUrl,
           buy_url: buyUrl,
           stock,
+          ports,
         },
       };
 
@@ -99,6 +99,22 @@ export default memo(function HardwareNode({ data, selected }: NodeProps<AhaNode>
                 id="pwr-out"
                 style={{ background: '#ef4444', width: '8px', height: '8px', border: '1px solid var(--bg-panel-solid)', borderRadius: '4px' }}
             />
+
+            {/* Clock Input (lower left) */}
+            <Handle
+                type="target"
+                position={Position.Left}
+                id="clk-in"
+                style={{ top: '75%', background: '#eab308', width: '8px', height: '8px', border: '1px solid var(--bg-panel-solid)' }}
+            />
+
+            {/* Clock Output (lower right, for crystals, oscillators and PLLs) */}
+            <Handle
+                type="source"
+                position={Position.Right}
+                id="clk-out"
+                style={{ top: '75%', background: '#eab308', width: '8px', height: '8px', border: '1px solid var(--bg-panel-solid)' }}
+            />
         </div>
     );
 });
@@ -15,6 +15,8 @@ const messages: Record<Locale, Record<string, string>> = {
     "app.clearCanvas": "Clear Canvas",
     "app.clearCanvasConfirm": "Are you sure you want to clear the entire canvas?",
     "app.exportBOM": "Export BOM",
+    "app.exportDatasheets": "Datasheets",
+    "app.datasheetsExported": "Exported {files} datasheets, {missing} not archived",
     "app.save": "Save",
     "app.savedAt": "Saved at {time}",
     "app.changeRequest": "Change Request",
@@ -39,6 +41,7 @@ const messages: Record<Locale, Record<string, string>> = {
     "left.liveTitle": "Live Component Search (TrustedParts)",
     "left.liveCompanyId": "Company ID",
     "left.liveApiKey": "API Key",
+    "left.liveCredentialStored": "Saved in vault (type to replace)",
     "left.liveQueryPlaceholder": "Search MPN or keyword...",
     "left.liveSearch": "Search Live",
     "left.liveSearching": "Searching trusted inventory...",
@@ -49,6 +52,16 @@ const messages: Record<Locale, Record<string, string>> = {
     "left.liveOffers": "offers",
     "left.liveExactMatch": "Exact match",
     "left.liveInStockOnly": "In-stock only",
+    "left.liveRanking": "Sort by",
+    "left.liveRankingStock": "Stock",
+    "left.liveRankingLandedCost": "Landed cost",
+    "left.liveQuantity": "Build quantity",
+    "left.librarySave": "Save to personal library",
+    "left.libraryDelete": "Remove from library",
+    "left.libraryCached": "Cached",
+    "left.libraryRoot.personal": "Personal",
+    "left.libraryRoot.team": "Team",
+    "left.libraryRoot.project": "Project",
 
     "canvas.selectMode": "Select Mode",
     "canvas.panMode": "Pan Mode",
@@ -81,12 +94,15 @@ const messages: Record<Locale, Record<string, string>> = {
     "right.manufacturer": "Manufacturer",
     "right.mpn": "MPN",
     "right.openDatasheet": "Open Datasheet",
+    "right.openArchivedDatasheet": "Open Archived Copy",
+    "right.datasheetRetrieved": "Retrieved {date}",
     "right.maxTdp": "Max TDP (Watts)",
     "right.aiProvider": "AI Provider",
     "right.baseUrl": "Base URL",
     "right.model": "Model",
     "right.loading": "Loading...",
     "right.apiKey": "API Key",
+    "right.apiKeyStored": "Saved in vault for this base URL (type to replace)",
     "right.optional": "Optional",
     "right.notRequiredLocal": "Not required for local",
     "right.aiDesc":
@@ -117,6 +133,8 @@ const messages: Record<Locale, Record<string, string>> = {
     "app.clearCanvas": "清空画布",
     "app.clearCanvasConfirm": "确认清空整个画布吗？",
     "app.exportBOM": "导出 BOM",
+    "app.exportDatasheets": "Datasheet 归档",
+    "app.datasheetsExported": "已导出 {files} 份 Datasheet，{missing} 份未归档",
     "app.save": "保存",
     "app.savedAt": "已保存于 {time}",
     "app.changeRequest": "创建变更请求",
@@ -141,6 +159,7 @@ const messages: Record<Locale, Record<string, string>> = {
     "left.liveTitle": "实时元件检索（TrustedParts）",
     "left.liveCompanyId": "Company ID",
     "left.liveApiKey": "API Key",
+    "left.liveCredentialStored": "已保存在凭据库中（输入以替换）",
     "left.liveQueryPlaceholder": "输入型号或关键词检索...",
     "left.liveSearch": "实时检索",
     "left.liveSearching": "正在检索真实库存...",
@@ -151,6 +170,16 @@ const messages: Record<Locale, Record<string, string>> = {
     "left.liveOffers": "个报价",
     "left.liveExactMatch": "精确匹配",
     "left.liveInStockOnly": "仅看有库存",
+    "left.liveRanking": "排序",
+    "left.liveRankingStock": "库存",
+    "left.liveRankingLandedCost": "到手总价",
+    "left.liveQuantity": "生产数量",
+    "left.librarySave": "保存到个人元件库",
+    "left.libraryDelete": "从元件库移除",
+    "left.libraryCached": "缓存",
+    "left.libraryRoot.personal": "个人",
+    "left.libraryRoot.team": "团队",
+    "left.libraryRoot.project": "项目",
 
     "canvas.selectMode": "选择模式",
     "canvas.panMode": "平移模式",
@@ -183,12 +212,15 @@ const messages: Record<Locale, Record<string, string>> = {
     "right.manufacturer": "厂商",
     "right.mpn": "型号",
     "right.openDatasheet": "打开 Datasheet",
+    "right.openArchivedDatasheet": "打开本地归档副本",
+    "right.datasheetRetrieved": "获取于 {date}",
     "right.maxTdp": "最大 TDP (瓦)",
     "right.aiProvider": "AI 服务商",
     "right.baseUrl": "基础 URL",
     "right.model": "模型",
     "right.loading": "加载中...",
     "right.apiKey": "API Key",
+    "right.apiKeyStored": "已为此 Base URL 保存在凭据库中（输入以替换）",
     "right.optional": "可选",
     "right.notRequiredLocal": "本地模式无需填写",
     "right.aiDesc": "描述你的硬件需求，AI 将生成一份架构草图。",
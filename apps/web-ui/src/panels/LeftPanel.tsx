@@ -7,13 +7,35 @@ import {
   Search,
   Layers,
   DatabaseZap,
+  BookmarkPlus,
+  Trash2,
 } from "lucide-react";
-import { useMemo, useState } from "react";
+import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useI18n } from "../i18n";
 
+// Where older builds kept the TrustedParts secrets; moved into the vault.
 const TRUSTEDPARTS_COMPANY_ID_KEY = "AHA_TRUSTEDPARTS_COMPANY_ID";
 const TRUSTEDPARTS_API_KEY_KEY = "AHA_TRUSTEDPARTS_API_KEY";
+const TRUSTEDPARTS_CREDENTIAL = "trustedparts";
+
+type CredentialInfo = {
+  name: string;
+  fields: string[];
+  updatedAt: number;
+};
+
+const hasTrustedPartsCredential = (credentials: CredentialInfo[]) =>
+  credentials.some((credential) => credential.name === TRUSTEDPARTS_CREDENTIAL);
+
+/** Stores the TrustedParts secrets in the backend vault. */
+const storeTrustedPartsCredential = async (companyId: string, apiKey: string) =>
+  hasTrustedPartsCredential(
+    await invoke<CredentialInfo[]>("set_credential", {
+      name: TRUSTEDPARTS_CREDENTIAL,
+      fields: { companyId, apiKey },
+    }),
+  );
 
 type DragPayload = {
   ahaDrag: true;
@@ -27,15 +49,33 @@ type DragPayload = {
   datasheetUrl?: string;
   buyUrl?: string;
   stock?: number;
+  ports?: LibraryPort[];
 };
 
-type LibraryItem = {
-  type: string;
+type LibraryRoot = "builtin" | "personal" | "team" | "project";
+
+type LibraryPort = {
+  name: string;
+  kind: string;
+};
+
+type LibraryEntry = {
+  id: string;
   label: string;
-  mfg: string;
-  icon: JSX.Element;
-  tdp: number;
-  package: string;
+  category: string;
+  manufacturer?: string | null;
+  mpn?: string | null;
+  package?: string | null;
+  tdpW: number;
+  ports: LibraryPort[];
+  datasheetUrl?: string | null;
+  description?: string | null;
+  root: LibraryRoot;
+  overrides?: LibraryRoot;
+};
+
+type LibraryListing = {
+  entries: LibraryEntry[];
 };
 
 type LiveOffer = {
@@ -43,8 +83,12 @@ type LiveOffer = {
   sku?: string | null;
   stock?: number | null;
   moq?: number | null;
+  orderMultiple?: number | null;
   currency?: string | null;
   unitPrice?: number | null;
+  originalCurrency?: string | null;
+  originalUnitPrice?: number | null;
+  priceBreaks?: { quantity: number; unitPrice: number; currency?: string | null }[];
   buyUrl?: string | null;
   datasheetUrl?: string | null;
 };
@@ -55,99 +99,50 @@ type LivePart = {
   description?: string | null;
   lifecycleStatus?: string | null;
   categoryHint: string;
+  categoryConfidence?: number;
+  attributes?: Record<
+    string,
+    | { kind: "range"; min: number; max: number; unit: string }
+    | { kind: "quantity"; value: number; unit: string }
+    | { kind: "text"; value: string }
+    | { kind: "list"; values: string[] }
+  >;
   offers: LiveOffer[];
 };
 
-const LIBRARY_ITEM: LibraryItem[] = [
-  {
-    type: "SoC",
-    label: "Jetson Orin NX (16GB)",
-    mfg: "NVIDIA",
-    icon: <Cpu strokeWidth={1.5} />,
-    tdp: 15,
-    package: "260-pin SO-DIMM",
-  },
-  {
-    type: "SoC",
-    label: "Jetson AGX Orin",
-    mfg: "NVIDIA",
-    icon: <Cpu strokeWidth={1.5} />,
-    tdp: 60,
-    package: "Custom Module",
-  },
-  {
-    type: "SoC",
-    label: "Snapdragon 8 Gen 3",
-    mfg: "Qualcomm",
-    icon: <Cpu strokeWidth={1.5} />,
-    tdp: 12,
-    package: "FCBGA",
-  },
-  {
-    type: "MCU",
-    label: "STM32G474VET6",
-    mfg: "STMicroelectronics",
-    icon: <Cpu strokeWidth={1.5} />,
-    tdp: 0.5,
-    package: "LQFP-100",
-  },
-  {
-    type: "MCU",
-    label: "RP2040",
-    mfg: "Raspberry Pi",
-    icon: <Cpu strokeWidth={1.5} />,
-    tdp: 0.3,
-    package: "QFN-56",
-  },
-  {
-    type: "MCU",
-    label: "ESP32-S3-WROOM-1",
-    mfg: "Espressif",
-    icon: <Radio strokeWidth={1.5} />,
-    tdp: 1.2,
-    package: "Module",
-  },
-  {
-    type: "Sensor",
-    label: "IMX219 8MP Camera",
-    mfg: "Sony",
-    icon: <Activity strokeWidth={1.5} />,
-    tdp: 1.5,
-    package: "MIPI CSI-2 Module",
-  },
-  {
-    type: "Sensor",
-    label: "BME680 Env",
-    mfg: "Bosch",
-    icon: <Activity strokeWidth={1.5} />,
-    tdp: 0.05,
-    package: "LGA-8",
-  },
-  {
-    type: "PMIC",
-    label: "TPS65219",
-    mfg: "Texas Instruments",
-    icon: <Zap strokeWidth={1.5} />,
-    tdp: 0.8,
-    package: "VQFN-32",
-  },
-  {
-    type: "Memory",
-    label: "16GB DDR4",
-    mfg: "Samsung",
-    icon: <Layers strokeWidth={1.5} />,
-    tdp: 3.5,
-    package: "FBGA",
-  },
-  {
-    type: "Storage",
-    label: "980 PRO 1TB NVMe",
-    mfg: "Samsung",
-    icon: <HardDrive strokeWidth={1.5} />,
-    tdp: 6.5,
-    package: "M.2 2280",
-  },
-];
+type IndexHit =
+  | { score: number; source: "library"; entry: LibraryEntry }
+  | { score: number; source: "cached"; hit: LivePart };
+
+type FacetCount = {
+  value: string;
+  count: number;
+};
+
+type IndexResults = {
+  total: number;
+  hits: IndexHit[];
+  facets: { categories: FacetCount[]; manufacturers: FacetCount[] };
+  elapsedMicros: number;
+};
+
+function libraryIcon(entry: LibraryEntry) {
+  if (entry.ports.some((port) => port.kind === "rf")) return <Radio strokeWidth={1.5} />;
+  switch (entry.category) {
+    case "Sensor":
+      return <Activity strokeWidth={1.5} />;
+    case "PMIC":
+      return <Zap strokeWidth={1.5} />;
+    case "Memory":
+      return <Layers strokeWidth={1.5} />;
+    case "Storage":
+      return <HardDrive strokeWidth={1.5} />;
+    case "RF":
+      return <Radio strokeWidth={1.5} />;
+    default:
+      return <Cpu strokeWidth={1.5} />;
+  }
+}
 
 function normalizeCategory(raw?: string | null) {
   const category = (raw || "Component").toLowerCase();
@@ -180,35 +175,99 @@ function defaultTdpForCategory(category: string) {
   }
 }
 
-export default function LeftPanel() {
+/** Library entries as they are; cached distributor hits as unsaved entries. */
+function indexEntry(hit: IndexHit): LibraryEntry {
+  if (hit.source === "library") {
+    return hit.entry;
+  }
+  const part = hit.hit;
+  const category = normalizeCategory(part.categoryHint);
+  const packageAttribute = part.attributes?.package;
+  return {
+    id: `cached:${part.manufacturer || ""}:${part.mpn}`,
+    label: part.mpn,
+    category,
+    manufacturer: part.manufacturer,
+    mpn: part.mpn,
+    package: packageAttribute?.kind === "text" ? packageAttribute.value : null,
+    tdpW: defaultTdpForCategory(category),
+    ports: [],
+    datasheetUrl: part.offers[0]?.datasheetUrl,
+    description: part.description,
+    root: "personal",
+  };
+}
+
+export default function LeftPanel({ workspacePath }: { workspacePath: string | null }) {
   const { t } = useI18n();
 
   const [activeTab, setActiveTab] = useState<"library" | "live">("library");
   const [searchQuery, setSearchQuery] = useState("");
 
-  const [liveCompanyId, setLiveCompanyId] = useState(
-    () => localStorage.getItem(TRUSTEDPARTS_COMPANY_ID_KEY) || "",
-  );
-  const [liveApiKey, setLiveApiKey] = useState(
-    () => localStorage.getItem(TRUSTEDPARTS_API_KEY_KEY) || "",
-  );
+  const [liveCompanyId, setLiveCompanyId] = useState("");
+  const [liveApiKey, setLiveApiKey] = useState("");
+  const [liveCredentialStored, setLiveCredentialStored] = useState(false);
   const [liveQuery, setLiveQuery] = useState("");
   const [liveExactMatch, setLiveExactMatch] = useState(false);
   const [liveInStockOnly, setLiveInStockOnly] = useState(true);
+  const [liveRanking, setLiveRanking] = useState<"stock" | "landedCost">("stock");
+  const [liveQuantity, setLiveQuantity] = useState(1);
   const [liveLoading, setLiveLoading] = useState(false);
   const [liveResults, setLiveResults] = useState<LivePart[]>([]);
   const [liveError, setLiveError] = useState<string | null>(null);
 
-  const filteredLibs = useMemo(
-    () =>
-      LIBRARY_ITEM.filter(
-        (item) =>
-          item.label.toLowerCase().includes(searchQuery.toLowerCase()) ||
-          item.mfg.toLowerCase().includes(searchQuery.toLowerCase()) ||
-          item.type.toLowerCase().includes(searchQuery.toLowerCase()),
-      ),
-    [searchQuery],
-  );
+  const [libraryHits, setLibraryHits] = useState<IndexHit[]>([]);
+  const [libraryCategories, setLibraryCategories] = useState<FacetCount[]>([]);
+  const [libraryCategory, setLibraryCategory] = useState<string | null>(null);
+  const [libraryError, setLibraryError] = useState<string | null>(null);
+  const [libraryRevision, setLibraryRevision] = useState(0);
+
+  useEffect(() => {
+    invoke<IndexResults>("search_library", {
+      query: { text: searchQuery, category: libraryCategory, limit: 100 },
+      workspacePath,
+    })
+      .then((results) => {
+        setLibraryHits(results.hits);
+        setLibraryCategories(results.facets.categories);
+        setLibraryError(null);
+      })
+      .catch((error) => setLibraryError(String(error)));
+  }, [searchQuery, libraryCategory, workspacePath, libraryRevision]);
+
+  const saveToLibrary = async (part: LivePart, category: string) => {
+    try {
+      await invoke<LibraryListing>("save_library_entry", {
+        entry: {
+          label: part.mpn,
+          category,
+          manufacturer: part.manufacturer,
+          mpn: part.mpn,
+          tdpW: defaultTdpForCategory(category),
+          datasheetUrl: part.offers[0]?.datasheetUrl,
+          description: part.description,
+        },
+        root: "personal",
+        workspacePath,
+      });
+      setLibraryRevision((revision) => revision + 1);
+    } catch (error) {
+      setLiveError(String(error));
+    }
+  };
+
+  const deleteFromLibrary = async (entry: LibraryEntry) => {
+    try {
+      await invoke<LibraryListing>("delete_library_entry", {
+        id: entry.id,
+        root: entry.root,
+        workspacePath,
+      });
+      setLibraryRevision((revision) => revision + 1);
+    } catch (error) {
+      setLibraryError(String(error));
+    }
+  };
 
   const onDragStart = (event: React.DragEvent, payload: DragPayload) => {
     const serialized = JSON.stringify(payload);
@@ -227,16 +286,29 @@ export default function LeftPanel() {
     }
   };
 
-  const saveTrustedPartsCredentials = (companyId: string, apiKey: string) => {
-    localStorage.setItem(TRUSTEDPARTS_COMPANY_ID_KEY, companyId);
-    localStorage.setItem(TRUSTEDPARTS_API_KEY_KEY, apiKey);
-  };
+  useEffect(() => {
+    const migrateAndCheck = async () => {
+      const legacyCompanyId = localStorage.getItem(TRUSTEDPARTS_COMPANY_ID_KEY);
+      const legacyApiKey = localStorage.getItem(TRUSTEDPARTS_API_KEY_KEY);
+      if (legacyCompanyId && legacyApiKey) {
+        await storeTrustedPartsCredential(legacyCompanyId, legacyApiKey);
+      }
+      localStorage.removeItem(TRUSTEDPARTS_COMPANY_ID_KEY);
+      localStorage.removeItem(TRUSTEDPARTS_API_KEY_KEY);
+
+      setLiveCredentialStored(
+        hasTrustedPartsCredential(await invoke<CredentialInfo[]>("list_credentials")),
+      );
+    };
+    migrateAndCheck().catch((error) => setLiveError(String(error)));
+  }, []);
 
   const handleLiveSearch = async () => {
     const companyId = liveCompanyId.trim();
     const apiKey = liveApiKey.trim();
     const query = liveQuery.trim();
-    if (!companyId || !apiKey) {
+    const entered = Boolean(companyId && apiKey);
+    if (!entered && !liveCredentialStored) {
       setLiveError(t("left.liveAuthHint"));
       return;
     }
@@ -246,15 +318,20 @@ export default function LeftPanel() {
 
     setLiveLoading(true);
     setLiveError(null);
-    saveTrustedPartsCredentials(companyId, apiKey);
     try {
+      if (entered) {
+        setLiveCredentialStored(await storeTrustedPartsCredential(companyId, apiKey));
+        setLiveCompanyId("");
+        setLiveApiKey("");
+      }
       const response = await invoke<LivePart[]>("search_trustedparts_inventory", {
         query: {
-          companyId,
-          apiKey,
+          credential: TRUSTEDPARTS_CREDENTIAL,
           searchToken: query,
           exactMatch: liveExactMatch,
           inStockOnly: liveInStockOnly,
+          ranking: liveRanking,
+          quantity: liveQuantity,
           maxResults: 20,
           countryCode: "US",
         },
@@ -347,7 +424,11 @@ export default function LeftPanel() {
             </div>
             <input
               type="text"
-              placeholder={t("left.liveCompanyId")}
+              placeholder={
+                liveCredentialStored
+                  ? t("left.liveCredentialStored")
+                  : t("left.liveCompanyId")
+              }
               value={liveCompanyId}
               onChange={(event) => setLiveCompanyId(event.target.value)}
               style={{
@@ -361,7 +442,11 @@ export default function LeftPanel() {
             />
             <input
               type="password"
-              placeholder={t("left.liveApiKey")}
+              placeholder={
+                liveCredentialStored
+                  ? t("left.liveCredentialStored")
+                  : t("left.liveApiKey")
+              }
               value={liveApiKey}
               onChange={(event) => setLiveApiKey(event.target.value)}
               style={{
@@ -404,6 +489,31 @@ export default function LeftPanel() {
               />
               {t("left.liveInStockOnly")}
             </label>
+            <label style={{ display: "flex", alignItems: "center", gap: "6px", fontSize: "11px", color: "var(--text-secondary)" }}>
+              {t("left.liveRanking")}
+              <select
+                value={liveRanking}
+                onChange={(event) =>
+                  setLiveRanking(event.target.value as "stock" | "landedCost")
+                }
+                style={{ flex: 1, fontSize: "11px" }}
+              >
+                <option value="stock">{t("left.liveRankingStock")}</option>
+                <option value="landedCost">{t("left.liveRankingLandedCost")}</option>
+              </select>
+              {liveRanking === "landedCost" && (
+                <input
+                  type="number"
+                  min={1}
+                  value={liveQuantity}
+                  onChange={(event) =>
+                    setLiveQuantity(Math.max(1, Math.floor(Number(event.target.value)) || 1))
+                  }
+                  title={t("left.liveQuantity")}
+                  style={{ width: "64px", fontSize: "11px" }}
+                />
+              )}
+            </label>
             <button
               className="btn primary"
               style={{ justifyContent: "center" }}
@@ -430,123 +540,206 @@ export default function LeftPanel() {
 
         {activeTab === "library" ? (
           <div style={{ display: "flex", flexDirection: "column", gap: "10px" }}>
-            {filteredLibs.map((item, idx) => (
+            {libraryError && (
               <div
-                key={idx}
-                className="lib-item"
-                draggable
-                  onDragStart={(event) =>
-                    onDragStart(event, {
-                      ahaDrag: true,
-                      type: item.type,
-                      label: item.label,
-                      tdp: item.tdp,
-                    mfg: item.mfg,
-                    package: item.package,
-                  })
-                }
                 style={{
-                  background:
-                    "linear-gradient(145deg, var(--surface-soft) 0%, var(--surface-subtle) 100%)",
-                  border: "1px solid var(--surface-border)",
+                  background: "rgba(239, 68, 68, 0.12)",
+                  border: "1px solid rgba(239, 68, 68, 0.3)",
                   borderRadius: "8px",
-                  padding: "12px",
-                  cursor: "grab",
-                  transition: "all 0.2s ease",
-                  display: "flex",
-                  gap: "12px",
-                  alignItems: "center",
+                  padding: "10px",
+                  color: "#fca5a5",
+                  fontSize: "12px",
+                  whiteSpace: "pre-wrap",
+                  wordBreak: "break-word",
                 }}
-                onMouseOver={(event) =>
-                  (event.currentTarget.style.borderColor = "var(--accent-primary)")
-                }
-                onMouseOut={(event) =>
-                  (event.currentTarget.style.borderColor = "var(--surface-border)")
-                }
               >
+                {libraryError}
+              </div>
+            )}
+
+            {libraryCategories.length > 1 && (
+              <div style={{ display: "flex", gap: "4px", flexWrap: "wrap" }}>
+                {libraryCategories.map((facet) => (
+                  <button
+                    key={facet.value}
+                    className="btn"
+                    style={{
+                      padding: "2px 6px",
+                      fontSize: "10px",
+                      background:
+                        libraryCategory === facet.value ? "var(--accent-soft)" : undefined,
+                    }}
+                    onClick={() =>
+                      setLibraryCategory((current) =>
+                        current === facet.value ? null : facet.value,
+                      )
+                    }
+                  >
+                    {facet.value} ({facet.count})
+                  </button>
+                ))}
+              </div>
+            )}
+
+            {libraryHits.map((hit) => {
+              const item = indexEntry(hit);
+              const cached = hit.source === "cached";
+              return (
                 <div
+                  key={`${hit.source}-${item.id}`}
+                  className="lib-item"
+                  draggable
+                  onDragStart={(event) =>
+                    onDragStart(event, {
+                      ahaDrag: true,
+                      type: item.category,
+                      label: item.label,
+                      tdp: item.tdpW,
+                      mfg: item.manufacturer || undefined,
+                      package: item.package || undefined,
+                      mpn: item.mpn || undefined,
+                      description: item.description || undefined,
+                      datasheetUrl: item.datasheetUrl || undefined,
+                      buyUrl: cached ? hit.hit.offers[0]?.buyUrl || undefined : undefined,
+                      stock: cached ? hit.hit.offers[0]?.stock || undefined : undefined,
+                      ports: item.ports.length > 0 ? item.ports : undefined,
+                    })
+                  }
                   style={{
-                    width: "40px",
-                    height: "40px",
-                    background: "var(--surface-elevated)",
-                    borderRadius: "6px",
+                    background:
+                      "linear-gradient(145deg, var(--surface-soft) 0%, var(--surface-subtle) 100%)",
+                    border: "1px solid var(--surface-border)",
+                    borderRadius: "8px",
+                    padding: "12px",
+                    cursor: "grab",
+                    transition: "all 0.2s ease",
                     display: "flex",
+                    gap: "12px",
                     alignItems: "center",
-                    justifyContent: "center",
-                    flexShrink: 0,
-                    border: "1px solid var(--surface-border)",
                   }}
+                  onMouseOver={(event) =>
+                    (event.currentTarget.style.borderColor = "var(--accent-primary)")
+                  }
+                  onMouseOut={(event) =>
+                    (event.currentTarget.style.borderColor = "var(--surface-border)")
+                  }
                 >
-                  <span style={{ color: "var(--accent-primary)" }}>{item.icon}</span>
-                </div>
+                  <div
+                    style={{
+                      width: "40px",
+                      height: "40px",
+                      background: "var(--surface-elevated)",
+                      borderRadius: "6px",
+                      display: "flex",
+                      alignItems: "center",
+                      justifyContent: "center",
+                      flexShrink: 0,
+                      border: "1px solid var(--surface-border)",
+                    }}
+                  >
+                    <span style={{ color: "var(--accent-primary)" }}>{libraryIcon(item)}</span>
+                  </div>
 
-                <div
-                  style={{
-                    flex: 1,
-                    minWidth: 0,
-                    display: "flex",
-                    flexDirection: "column",
-                    gap: "4px",
-                  }}
-                >
-                  <h4
+                  <div
                     style={{
-                      margin: 0,
-                      fontSize: "13px",
-                      whiteSpace: "nowrap",
-                      overflow: "hidden",
-                      textOverflow: "ellipsis",
-                      fontWeight: 500,
-                      color: "var(--text-primary)",
+                      flex: 1,
+                      minWidth: 0,
+                      display: "flex",
+                      flexDirection: "column",
+                      gap: "4px",
                     }}
-                    title={item.label}
                   >
-                    {item.label}
-                  </h4>
-                  <div style={{ display: "flex", gap: "6px", fontSize: "10px" }}>
-                    <span
+                    <h4
                       style={{
-                        color: "#60a5fa",
-                        background: "rgba(96, 165, 250, 0.1)",
-                        padding: "2px 6px",
-                        borderRadius: "4px",
-                        border: "1px solid rgba(96, 165, 250, 0.2)",
+                        margin: 0,
+                        fontSize: "13px",
+                        whiteSpace: "nowrap",
+                        overflow: "hidden",
+                        textOverflow: "ellipsis",
+                        fontWeight: 500,
+                        color: "var(--text-primary)",
                       }}
+                      title={item.label}
                     >
-                      {item.mfg}
-                    </span>
-                    <span
+                      {item.label}
+                    </h4>
+                    <div style={{ display: "flex", gap: "6px", fontSize: "10px" }}>
+                      <span
+                        style={{
+                          color: "#60a5fa",
+                          background: "rgba(96, 165, 250, 0.1)",
+                          padding: "2px 6px",
+                          borderRadius: "4px",
+                          border: "1px solid rgba(96, 165, 250, 0.2)",
+                        }}
+                      >
+                        {item.manufacturer || "Unknown Manufacturer"}
+                      </span>
+                      <span
+                        style={{
+                          color: "var(--text-secondary)",
+                          background: "var(--surface-soft)",
+                          padding: "2px 6px",
+                          borderRadius: "4px",
+                        }}
+                      >
+                        {item.category}
+                      </span>
+                      {(cached || item.root !== "builtin") && (
+                        <span
+                          style={{
+                            color: "var(--accent-primary)",
+                            padding: "2px 6px",
+                            borderRadius: "4px",
+                            border: "1px solid var(--accent-primary)",
+                          }}
+                        >
+                          {cached ? t("left.libraryCached") : t(`left.libraryRoot.${item.root}`)}
+                        </span>
+                      )}
+                      {cached && (
+                        <button
+                          className="btn"
+                          title={t("left.librarySave")}
+                          style={{ marginLeft: "auto", padding: "0 4px" }}
+                          onClick={() => void saveToLibrary(hit.hit, item.category)}
+                        >
+                          <BookmarkPlus size={12} />
+                        </button>
+                      )}
+                      {!cached && item.root !== "builtin" && (
+                        <button
+                          className="btn"
+                          title={t("left.libraryDelete")}
+                          style={{ marginLeft: "auto", padding: "0 4px" }}
+                          onClick={() => void deleteFromLibrary(item)}
+                        >
+                          <Trash2 size={12} />
+                        </button>
+                      )}
+                    </div>
+                    <div
                       style={{
-                        color: "var(--text-secondary)",
-                        background: "var(--surface-soft)",
-                        padding: "2px 6px",
-                        borderRadius: "4px",
+                        fontSize: "10px",
+                        color: "var(--text-muted)",
+                        display: "flex",
+                        justifyContent: "space-between",
                       }}
                     >
-                      {item.type}
-                    </span>
-                  </div>
-                  <div
-                    style={{
-                      fontSize: "10px",
-                      color: "var(--text-muted)",
-                      display: "flex",
-                      justifyContent: "space-between",
-                    }}
-                  >
-                    <span>
-                      {t("left.tdp")}:{" "}
-                      <strong style={{ color: "var(--text-primary)" }}>{item.tdp}W</strong>
-                    </span>
-                    <span>
-                      {t("left.pkg")}: {item.package}
-                    </span>
+                      <span>
+                        {t("left.tdp")}:{" "}
+                        <strong style={{ color: "var(--text-primary)" }}>{item.tdpW}W</strong>
+                      </span>
+                      <span>
+                        {t("left.pkg")}: {item.package || "—"}
+                      </span>
+                    </div>
                   </div>
                 </div>
-              </div>
-            ))}
+              );
+            })}
 
-            {filteredLibs.length === 0 && (
+            {libraryHits.length === 0 && !libraryError && (
               <div
                 style={{
                   textAlign: "center",
@@ -666,17 +859,27 @@ export default function LeftPanel() {
                         {part.manufacturer || "Unknown Manufacturer"}
                       </div>
                     </div>
-                    <span
-                      style={{
-                        fontSize: "10px",
-                        color: "var(--accent-primary)",
-                        border: "1px solid var(--accent-primary)",
-                        borderRadius: "999px",
-                        padding: "2px 6px",
-                      }}
-                    >
-                      {category}
-                    </span>
+                    <div style={{ display: "flex", gap: "4px", alignItems: "center" }}>
+                      <span
+                        style={{
+                          fontSize: "10px",
+                          color: "var(--accent-primary)",
+                          border: "1px solid var(--accent-primary)",
+                          borderRadius: "999px",
+                          padding: "2px 6px",
+                        }}
+                      >
+                        {category}
+                      </span>
+                      <button
+                        className="btn"
+                        title={t("left.librarySave")}
+                        style={{ padding: "0 4px" }}
+                        onClick={() => void saveToLibrary(part, category)}
+                      >
+                        <BookmarkPlus size={12} />
+                      </button>
+                    </div>
                   </div>
 
                   {part.description && (
@@ -17,6 +17,27 @@ import ReactMarkdown from "react-markdown";
 import remarkGfm from "remark-gfm";
 import { useI18n } from "../i18n";
 
+const LLM_CREDENTIAL = "llm";
+const LEGACY_LLM_API_KEY = "OPENAI_API_KEY";
+
+type CredentialInfo = {
+  name: string;
+  fields: string[];
+  updatedAt: number;
+};
+
+const hasLlmCredential = (credentials: CredentialInfo[]) =>
+  credentials.some((credential) => credential.name === LLM_CREDENTIAL);
+
+/** Stores the LLM key in the backend vault, usable only with `baseUrl`. */
+const storeLlmCredential = async (apiKey: string, baseUrl: string) =>
+  hasLlmCredential(
+    await invoke<CredentialInfo[]>("set_credential", {
+      name: LLM_CREDENTIAL,
+      fields: { apiKey, baseUrl },
+    }),
+  );
+
 export default function RightPanel() {
   const { t } = useI18n();
   const { nodes, selectedNodeId, updateNodeData, setGraph } = useGraphStore();
@@ -114,53 +135,32 @@ export default function RightPanel() {
   const [modelName, setModelName] = useState(
     localStorage.getItem("AI_MODEL") || "gpt-4o",
   );
-  const [apiKey, setApiKey] = useState(
-    localStorage.getItem("OPENAI_API_KEY") || "",
-  );
+  const [apiKey, setApiKey] = useState("");
+  const [llmCredentialStored, setLlmCredentialStored] = useState(false);
   const [availableModels, setAvailableModels] = useState<string[]>([]);
   const [isLoadingModels, setIsLoadingModels] = useState(false);
 
   const fetchModels = async (
     currentBaseUrl: string,
-    currentApiKey: string,
     currentProvider: string,
+    credentialStored: boolean,
   ) => {
     if (!currentBaseUrl) return;
-    if (currentProvider !== "ollama" && !currentApiKey) return;
+    if (currentProvider !== "ollama" && !credentialStored) return;
 
     setIsLoadingModels(true);
     try {
-      const endpoint = currentBaseUrl.replace(/\/$/, "") + "/models";
-      const res = await fetch(endpoint, {
-        method: "GET",
-        headers: {
-          "Content-Type": "application/json",
-          ...(currentApiKey
-            ? { Authorization: `Bearer ${currentApiKey}` }
-            : {}),
-        },
+      const models = await invoke<string[]>("list_llm_models", {
+        baseUrl: currentBaseUrl,
+        credential: currentProvider === "ollama" ? null : LLM_CREDENTIAL,
       });
-
-      if (res.ok) {
-        const data = await res.json();
-        if (data && data.data && Array.isArray(data.data)) {
-          const models = data.data.map((m: any) => m.id);
-          setAvailableModels(models);
-          if (models.length > 0 && !models.includes(modelName)) {
-            setModelName(models[0]);
-            localStorage.setItem("AI_MODEL", models[0]);
-          }
-        } else if (data && data.models && Array.isArray(data.models)) {
-          // Ollama format
-          const models = data.models.map((m: any) => m.name);
-          setAvailableModels(models);
-          if (models.length > 0 && !models.includes(modelName)) {
-            setModelName(models[0]);
-            localStorage.setItem("AI_MODEL", models[0]);
-          }
-        }
+      setAvailableModels(models);
+      if (models.length > 0 && !models.includes(modelName)) {
+        setModelName(models[0]);
+        localStorage.setItem("AI_MODEL", models[0]);
       }
     } catch (e) {
+      setAvailableModels([]);
       console.error("Failed to fetch models:", e);
     } finally {
       setIsLoadingModels(false);
@@ -168,9 +168,28 @@ export default function RightPanel() {
   };
 
   useEffect(() => {
-    // Fetch models when provider, baseUrl, or apiKey changes
-    fetchModels(baseUrl, apiKey, provider);
-  }, [baseUrl, apiKey, provider]);
+    const migrateAndCheck = async () => {
+      const legacyApiKey = localStorage.getItem(LEGACY_LLM_API_KEY);
+      if (legacyApiKey) {
+        await storeLlmCredential(
+          legacyApiKey,
+          localStorage.getItem("AI_BASE_URL") || "https://api.openai.com/v1",
+        );
+      }
+      localStorage.removeItem(LEGACY_LLM_API_KEY);
+      setLlmCredentialStored(
+        hasLlmCredential(await invoke<CredentialInfo[]>("list_credentials")),
+      );
+    };
+    migrateAndCheck().catch((e) =>
+      console.error("Failed to check the LLM credential:", e),
+    );
+  }, []);
+
+  useEffect(() => {
+    // Fetch models when provider, baseUrl, or the stored key changes
+    fetchModels(baseUrl, provider, llmCredentialStored);
+  }, [baseUrl, provider, llmCredentialStored]);
 
   const handleProviderChange = (e: React.ChangeEvent<HTMLSelectElement>) => {
     const p = e.target.value;
@@ -212,7 +231,19 @@ export default function RightPanel() {
 
   const handleKeyChange = (e: React.ChangeEvent<HTMLInputElement>) => {
     setApiKey(e.target.value);
-    localStorage.setItem("OPENAI_API_KEY", e.target.value);
+  };
+
+  /** Moves an entered key into the vault, bound to the current base URL. */
+  const handleKeyCommit = async () => {
+    const entered = apiKey.trim();
+    if (!entered) return;
+    try {
+      setLlmCredentialStored(await storeLlmCredential(entered, baseUrl));
+      setApiKey("");
+      fetchModels(baseUrl, provider, true);
+    } catch (e) {
+      setChatLog((prev) => [...prev, { role: "ai", text: `Error: ${String(e)}` }]);
+    }
   };
 
   useEffect(() => {
@@ -243,7 +274,11 @@ export default function RightPanel() {
   };
 
   const handleAISubmit = async () => {
-    if (!chatInput.trim() || (provider !== "ollama" && !apiKey)) {
+    if (apiKey.trim()) {
+      await handleKeyCommit();
+    }
+    const credentialStored = llmCredentialStored || Boolean(apiKey.trim());
+    if (!chatInput.trim() || (provider !== "ollama" && !credentialStored)) {
       setChatLog((prev) => [
         ...prev,
         { role: "ai", text: t("right.aiMissing") },
@@ -261,7 +296,7 @@ export default function RightPanel() {
         provider,
         baseUrl,
         modelName,
-        apiKey,
+        credential: provider === "ollama" ? undefined : LLM_CREDENTIAL,
       });
       setGraph(result.nodes, result.edges);
       setChatLog((prev) => [...prev, { role: "ai", text: result.explanation }]);
@@ -600,6 +635,36 @@ export default function RightPanel() {
                   </a>
                 )}
 
+                {selectedNode.data.datasheet_sha256 && (
+                  <a
+                    href="#"
+                    onClick={(event) => {
+                      event.preventDefault();
+                      invoke("open_datasheet", {
+                        sha256: selectedNode.data.datasheet_sha256,
+                      }).catch((err) =>
+                        window.alert(`Failed to open datasheet: ${String(err)}`),
+                      );
+                    }}
+                    title={
+                      selectedNode.data.datasheet_retrieved_at
+                        ? t("right.datasheetRetrieved", {
+                            date: new Date(
+                              selectedNode.data.datasheet_retrieved_at * 1000,
+                            ).toLocaleDateString(),
+                          })
+                        : undefined
+                    }
+                    style={{
+                      color: "var(--accent-primary)",
+                      fontSize: "12px",
+                      textDecoration: "none",
+                    }}
+                  >
+                    {t("right.openArchivedDatasheet")}
+                  </a>
+                )}
+
                 <div
                   style={{
                     display: "flex",
@@ -788,8 +853,13 @@ export default function RightPanel() {
                 type="password"
                 value={apiKey}
                 onChange={handleKeyChange}
+                onBlur={() => void handleKeyCommit()}
                 placeholder={
-                  provider === "ollama" ? t("right.notRequiredLocal") : "sk-..."
+                  provider === "ollama"
+                    ? t("right.notRequiredLocal")
+                    : llmCredentialStored
+                      ? t("right.apiKeyStored")
+                      : "sk-..."
                 }
                 style={{
                   background: "var(--input-bg)",
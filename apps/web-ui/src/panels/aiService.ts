@@ -1,6 +1,7 @@
 import { AhaNode } from '../store/useGraphStore';
 import { Edge } from '@xyflow/react';
 import { v4 as uuidv4 } from 'uuid';
+import { invoke } from '@tauri-apps/api/core';
 
 export interface AISynthesizeResult {
     nodes: AhaNode[];
@@ -12,7 +13,8 @@ export interface AIConfig {
     provider: string;
     baseUrl: string;
     modelName: string;
-    apiKey: string;
+    /** Vault credential holding the API key; unset for local providers. */
+    credential?: string;
 }
 
 const SYSTEM_PROMPT = `You are an expert AI Hardware Architect (AHA). You design system-level architectures for electronics.
@@ -31,19 +33,14 @@ Rules:
 4. Output strictly valid parseable JSON. No markdown backticks.`;
 
 export const synthesizeArchitecture = async (prompt: string, config: AIConfig): Promise<AISynthesizeResult> => {
-    if (config.provider !== 'ollama' && !config.apiKey) {
+    if (config.provider !== 'ollama' && !config.credential) {
         throw new Error("API Key is missing for cloud provider. Please provide a valid API Key.");
     }
 
-    const endpoint = config.baseUrl.replace(/\/$/, '') + '/chat/completions';
-
-    const res = await fetch(endpoint, {
-        method: "POST",
-        headers: {
-            "Content-Type": "application/json",
-            ...(config.apiKey ? { "Authorization": `Bearer ${config.apiKey}` } : {})
-        },
-        body: JSON.stringify({
+    const data = await invoke<any>("llm_chat_completion", {
+        baseUrl: config.baseUrl,
+        credential: config.credential ?? null,
+        request: {
             model: config.modelName,
             messages: [
                 { role: "system", content: SYSTEM_PROMPT },
@@ -51,15 +48,11 @@ export const synthesizeArchitecture = async (prompt: string, config: AIConfig):
             ],
             response_format: { type: "json_object" },
             temperature: 0.2
-        })
+        }
+    }).catch((error) => {
+        throw new Error(String(error));
     });
 
-    if (!res.ok) {
-        const errJson = await res.json().catch(() => ({}));
-        throw new Error(errJson.error?.message || `API request failed with status ${res.status}`);
-    }
-
-    const data = await res.json();
     const content = data.choices[0].message.content;
 
     try {
@@ -8,9 +8,16 @@ class SimulationResult(BaseModel):
     issues: List[str]
     report_data: dict
 
+def emit_progress(percent: float, phase: str, issues: Optional[List[str]] = None):
+    # Line-delimited progress protocol: the desktop bridge forwards these as events
+    # and strips them from the final result.
+    print(json.dumps({"type": "progress", "percent": percent, "phase": phase, "issues": issues or []}), flush=True)
+
 def run_simulation(input_file_path: str) -> SimulationResult:
     issues = []
     total_power = 0.0
+    ambient_c = 25.0
+    load_factor = 1.0
     nodes_count = 0
     pmic_count = 0
     soc_count = 0
@@ -20,9 +27,13 @@ def run_simulation(input_file_path: str) -> SimulationResult:
             data = json.load(f)
             nodes = data.get("nodes", [])
             edges = data.get("edges", [])
+            profile = data.get("profile") or {}
+            ambient_c = float(profile.get("ambientC", 25.0))
+            load_factor = float(profile.get("loadFactor", 1.0))
             nodes_count = len(nodes)
             
             node_dict = {n["id"]: n for n in nodes}
+            emit_progress(10, "load")
 
             # 1. Power Aggregation
             for node in nodes:
@@ -31,11 +42,27 @@ def run_simulation(input_file_path: str) -> SimulationResult:
                 if cat == "SoC": soc_count += 1
                 
                 tdp = node.get("data", {}).get("tdp_w", 0.0)
+                quantity = node.get("data", {}).get("quantity", 1)
                 try:
-                    total_power += float(tdp)
-                except ValueError:
-                    pass
+                    node_power = float(tdp) * load_factor
+                    total_power += node_power * max(1, int(float(quantity)))
+                except (TypeError, ValueError):
+                    continue
+
+                # Thermal check: junction estimate from ambient and theta-JA, when the part declares a limit
+                max_temp = node.get("data", {}).get("max_temp_c")
+                theta_ja = node.get("data", {}).get("theta_ja_c_per_w", 0.0)
+                if max_temp is not None:
+                    try:
+                        junction_c = ambient_c + node_power * float(theta_ja)
+                        if junction_c > float(max_temp):
+                            label = node.get("data", {}).get("label", "Unknown")
+                            issues.append(f"Thermal Violation (Fatal): '{label}' reaches an estimated {junction_c:.1f} °C, above its {float(max_temp):.1f} °C limit.")
+                    except (TypeError, ValueError):
+                        pass
             
+            emit_progress(40, "power", issues)
+
             # 2. DRC Checks on Edges
             for edge in edges:
                 src_handle = edge.get("sourceHandle")
@@ -49,6 +76,8 @@ def run_simulation(input_file_path: str) -> SimulationResult:
                     if "data" in src_handle and "pwr" in tgt_handle:
                         issues.append(f"DRC Violation (Fatal): Data output from '{src_node}' connected to Power input on '{tgt_node}'.")
 
+            emit_progress(80, "drc", issues)
+
             # 3. Structural DRC
             if soc_count > 0 and pmic_count == 0:
                 issues.append("DRC Warning: System contains an SoC but lacks a dedicated PMIC for power delivery.")
@@ -68,6 +97,8 @@ def run_simulation(input_file_path: str) -> SimulationResult:
         report_data={
             "nodes_analyzed": nodes_count,
             "total_power_w": total_power,
+            "ambient_c": ambient_c,
+            "load_factor": load_factor,
             "drc_checks_passed": len(issues) == 0
         }
     )